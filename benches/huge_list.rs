@@ -0,0 +1,139 @@
+//! how `Select` and `MultiSelect` hold up with a lot of options
+//!
+//! run with `cargo bench --features bench`. drawing a page should take about
+//! as long at a million options as at ten thousand, since only the rows on
+//! screen are looked at.
+
+use {
+    bearask::{
+        __bench::{MultiSelectPage, SelectPage, Typing},
+        AskOption, IndexedOptions, MultiSelect, Select,
+    },
+    criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main},
+    std::hint::black_box,
+};
+
+const SIZES: [usize; 3] = [10_000, 100_000, 1_000_000];
+
+const WORDS: [&str; 8] = [
+    "alpha", "bravo", "charlie", "delta", "echo", "foxtrot", "golf", "hotel",
+];
+
+fn option(i: usize) -> AskOption<usize> {
+    AskOption::new(
+        format!("{}-{}-{:07}", WORDS[i % 8], WORDS[i / 8 % 8], i),
+        format!("option number {}", i),
+        i,
+    )
+}
+
+fn options(len: usize) -> Vec<AskOption<usize>> {
+    (0..len).map(option).collect()
+}
+
+/// the same options as `options`, made only when they're asked for
+struct Indexed(usize);
+
+impl IndexedOptions<usize> for Indexed {
+    fn len(&self) -> usize {
+        self.0
+    }
+
+    fn get(&self, index: usize) -> AskOption<usize> {
+        option(index)
+    }
+}
+
+fn filtering(c: &mut Criterion) {
+    let mut group = c.benchmark_group("filter");
+    group.sample_size(10);
+
+    for len in SIZES {
+        let options = options(len);
+
+        // each key after the first only looks at what the last one matched
+        group.bench_with_input(
+            BenchmarkId::new("type 4 keys", len),
+            &options,
+            |b, options| {
+                b.iter_batched(
+                    || Typing::new(options),
+                    |mut typing| {
+                        typing.type_text("ech9");
+                        black_box(typing.matches())
+                    },
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+
+        group.bench_with_input(BenchmarkId::new("layout", len), &options, |b, options| {
+            let typing = Typing::new(options);
+            b.iter(|| black_box(typing.layout()))
+        });
+
+        // what a key costs in the prompt itself: matching, checking whether
+        // the query names a new option, laying out and drawing the page
+        let select = Select::new("Pick one")
+            .with_options(options.clone())
+            .with_create(|_| 0);
+        let mut out = Vec::with_capacity(4096);
+        group.bench_function(BenchmarkId::new("select type 4 keys", len), |b| {
+            b.iter_batched(
+                || SelectPage::new(&select, 0),
+                |mut page| {
+                    page.type_text("ech9");
+                    out.clear();
+                    black_box(page.render(&mut out).unwrap())
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+fn rendering(c: &mut Criterion) {
+    let mut group = c.benchmark_group("render");
+
+    for len in SIZES {
+        let select = Select::new("Pick one").with_options(options(len));
+        let page = SelectPage::new(&select, len / 2);
+        let mut out = Vec::with_capacity(4096);
+        group.bench_function(BenchmarkId::new("select page", len), |b| {
+            b.iter(|| {
+                out.clear();
+                black_box(page.render(&mut out).unwrap())
+            })
+        });
+
+        // building the prompt and drawing its first page only makes the
+        // options on that page
+        group.bench_function(BenchmarkId::new("indexed select first page", len), |b| {
+            b.iter(|| {
+                let select = Select::new("Pick one").with_indexed_options(Indexed(len));
+                out.clear();
+                black_box(SelectPage::new(&select, 0).render(&mut out).unwrap())
+            })
+        });
+
+        let multiselect = MultiSelect::new("Pick some").with_options(options(len));
+        let mut page = MultiSelectPage::new(&multiselect);
+        group.bench_function(BenchmarkId::new("multiselect page", len), |b| {
+            b.iter(|| {
+                out.clear();
+                black_box(page.render(&mut out).unwrap())
+            })
+        });
+
+        group.bench_function(BenchmarkId::new("multiselect toggle", len), |b| {
+            b.iter(|| black_box(page.toggle(black_box(len / 2))))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, filtering, rendering);
+criterion_main!(benches);
@@ -0,0 +1,131 @@
+//! ways in to the prompts' internals for the benchmarks in `benches/`
+//!
+//! only built with the `bench` feature, and not meant for anything else.
+
+use {
+    crate::{
+        filter::Filter,
+        list::List,
+        multiselect::{MultiSelect, Selection},
+        option::AskOption,
+        preview::Pane,
+        select::Select,
+    },
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+};
+
+/// a filter over `options`, typed into a key at a time
+pub struct Typing<'a, T> {
+    options: &'a [AskOption<T>],
+    filter: Filter,
+}
+
+impl<'a, T> Typing<'a, T> {
+    pub fn new(options: &'a [AskOption<T>]) -> Self {
+        Self {
+            options,
+            filter: Filter::new(options),
+        }
+    }
+
+    /// type `text` after what's there already
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            let key_event = KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+            self.filter.edit(key_event, self.options);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.filter.clear(self.options);
+    }
+
+    /// how many options match what's been typed
+    pub fn matches(&self) -> usize {
+        self.filter.matches.len()
+    }
+
+    /// lay out the matching rows, returning how many there are
+    pub fn layout(&self) -> usize {
+        List::new(&self.filter, &[], self.options.len()).len()
+    }
+}
+
+/// a `Select` laid out with the cursor on one option, ready to draw
+pub struct SelectPage<'a, T: Clone> {
+    select: &'a Select<T>,
+    filter: Filter,
+    list: List,
+}
+
+impl<'a, T: Clone> SelectPage<'a, T> {
+    pub fn new(select: &'a Select<T>, cursor: usize) -> Self {
+        let filter = Filter::new(select.option_list());
+        let list = select.list(&filter, Some(cursor));
+        Self {
+            select,
+            filter,
+            list,
+        }
+    }
+
+    /// type `text` after what's there already, laying the rows out again
+    /// after each key like the prompt does
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            let key_event = KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+            if self.filter.edit(key_event, self.select.option_list()) {
+                self.list = self.select.list(&self.filter, None);
+            }
+        }
+    }
+
+    /// draw the prompt into `out`, returning how many lines it took
+    pub fn render(&self, out: &mut Vec<u8>) -> miette::Result<usize> {
+        self.select
+            .render(out, &self.list, &self.filter, &Pane::default())
+    }
+}
+
+/// a `MultiSelect` laid out with every option selected, ready to draw
+pub struct MultiSelectPage<'a, T: Clone> {
+    multiselect: &'a MultiSelect<T>,
+    filter: Filter,
+    list: List,
+    selected: Selection,
+}
+
+impl<'a, T: Clone> MultiSelectPage<'a, T> {
+    pub fn new(multiselect: &'a MultiSelect<T>) -> Self {
+        let options = multiselect.option_list();
+        let filter = Filter::new(options);
+        let selected = Selection::new((0..options.len()).collect(), false);
+        let list = multiselect.list(&filter, &selected, None);
+        Self {
+            multiselect,
+            filter,
+            list,
+            selected,
+        }
+    }
+
+    /// select `index` if it isn't, or deselect it if it is, returning how
+    /// many are selected
+    pub fn toggle(&mut self, index: usize) -> usize {
+        if !self.selected.remove(index) {
+            self.selected.insert(index);
+        }
+        self.selected.len()
+    }
+
+    /// draw the prompt into `out`, returning how many lines it took
+    pub fn render(&self, out: &mut Vec<u8>) -> miette::Result<usize> {
+        self.multiselect.render(
+            out,
+            &self.list,
+            &self.selected,
+            &self.filter,
+            &Pane::default(),
+        )
+    }
+}
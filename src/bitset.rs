@@ -0,0 +1,88 @@
+/// a set of option indices, one bit each
+///
+/// a `MultiSelect` with a million options needs 125KB for its selection
+/// this way, and counting what's selected doesn't look at any of it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct BitSet {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitSet {
+    pub(crate) fn contains(&self, index: usize) -> bool {
+        self.words
+            .get(index / 64)
+            .is_some_and(|word| word & (1 << (index % 64)) != 0)
+    }
+
+    /// add `index`, returning whether it wasn't there already
+    pub(crate) fn insert(&mut self, index: usize) -> bool {
+        let word = index / 64;
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+
+        let bit = 1 << (index % 64);
+        let added = self.words[word] & bit == 0;
+        if added {
+            self.words[word] |= bit;
+            self.len += 1;
+        }
+        added
+    }
+
+    /// take out `index`, returning whether it was there
+    pub(crate) fn remove(&mut self, index: usize) -> bool {
+        let Some(word) = self.words.get_mut(index / 64) else {
+            return false;
+        };
+
+        let bit = 1 << (index % 64);
+        let removed = *word & bit != 0;
+        if removed {
+            *word &= !bit;
+            self.len -= 1;
+        }
+        removed
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.words.clear();
+        self.len = 0;
+    }
+
+    /// the indices in the set, smallest first
+    pub(crate) fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(i * 64 + bit)
+            })
+        })
+    }
+}
+
+impl Extend<usize> for BitSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for index in iter {
+            self.insert(index);
+        }
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::default();
+        set.extend(iter);
+        set
+    }
+}
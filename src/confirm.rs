@@ -1,5 +1,6 @@
 use {
     crate::{
+        keymap::{Action, KeyMap},
         style::ConfirmStyle,
         util::CursorGuard,
         validation::{Validate, run_validator},
@@ -12,9 +13,20 @@ use {
     },
     miette::IntoDiagnostic,
     owo_colors::OwoColorize,
-    std::io::{Write, stdout},
+    std::{
+        io::{Write, stdout},
+        sync::LazyLock,
+    },
 };
 
+/// the keys when typing y/n, where space submitting would take the default
+/// by accident, so it's invalid like any other key
+static TEXT_KEYMAP: LazyLock<KeyMap> =
+    LazyLock::new(|| KeyMap::new().without_binding(' ', Action::Submit));
+
+/// the keys when picking with the arrows
+static INTERACTIVE_KEYMAP: LazyLock<KeyMap> = LazyLock::new(KeyMap::new);
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum ConfirmMode {
     TextInput,
@@ -35,6 +47,7 @@ pub struct Confirm {
     show_error_hint: bool,
     show_confirmation: bool,
     allow_escape: bool,
+    keymap: Option<KeyMap>,
     style: ConfirmStyle,
     _cursor_guard: CursorGuard,
     validation: Option<Box<dyn Validate<bool>>>,
@@ -57,6 +70,7 @@ impl Confirm {
             show_error_hint: true,
             show_confirmation: true,
             allow_escape: true,
+            keymap: None,
             style: ConfirmStyle::default(),
             _cursor_guard: cursor_guard,
             validation: None,
@@ -118,6 +132,15 @@ impl Confirm {
         self
     }
 
+    /// set which keys do what
+    ///
+    /// without one, space submits when picking with the arrows but not when
+    /// typing y/n.
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.keymap = Some(keymap);
+        self
+    }
+
     pub fn with_style(mut self, style: ConfirmStyle) -> Self {
         self.style = style;
         self
@@ -273,18 +296,20 @@ impl Confirm {
             std::process::exit(130);
         }
 
-        match key_event.code {
-            KeyCode::Char('y') | KeyCode::Char('Y') => self.validate_and_return(true),
-            KeyCode::Char('n') | KeyCode::Char('N') => self.validate_and_return(false),
-            KeyCode::Char('1') | KeyCode::Char('t') | KeyCode::Char('T') => {
-                self.validate_and_return(true)
-            }
-            KeyCode::Char('0') | KeyCode::Char('f') | KeyCode::Char('F') => {
-                self.validate_and_return(false)
-            }
-            KeyCode::Enter => self.validate_and_return(self.default),
-            KeyCode::Esc if self.allow_escape => Err("Cancelled".into()),
-            _ => Err("Invalid input. Expected: y/n, 1/0, t/f, or Enter for default".to_string()),
+        const ACTIONS: &[Action] = &[Action::Yes, Action::No, Action::Submit, Action::Cancel];
+
+        match self.keymap().action(&key_event, ACTIONS) {
+            Some(Action::Yes) => self.validate_and_return(true),
+            Some(Action::No) => self.validate_and_return(false),
+            Some(Action::Submit) => self.validate_and_return(self.default),
+            Some(Action::Cancel) if self.allow_escape => Err("Cancelled".into()),
+            _ => Err(format!(
+                "Invalid input. Expected: {}, or {} for default",
+                self.keymap()
+                    .hint_pair(Action::Yes, Action::No)
+                    .unwrap_or_default(),
+                self.keymap().hint(Action::Submit).unwrap_or_default(),
+            )),
         }
     }
 
@@ -300,25 +325,42 @@ impl Confirm {
             std::process::exit(130);
         }
 
-        match key_event.code {
-            KeyCode::Left | KeyCode::Right | KeyCode::Tab => {
+        const ACTIONS: &[Action] = &[
+            Action::SwitchChoice,
+            Action::Yes,
+            Action::No,
+            Action::Submit,
+            Action::Cancel,
+        ];
+
+        match self.keymap().action(&key_event, ACTIONS) {
+            Some(Action::SwitchChoice) => {
                 *selected = !*selected;
                 Ok(None)
             }
-            KeyCode::Char('y') | KeyCode::Char('Y') => {
+            Some(Action::Yes) => {
                 *selected = true;
                 Ok(None)
             }
-            KeyCode::Char('n') | KeyCode::Char('N') => {
+            Some(Action::No) => {
                 *selected = false;
                 Ok(None)
             }
-            KeyCode::Enter | KeyCode::Char(' ') => self.validate_and_return(*selected),
-            KeyCode::Esc if self.allow_escape => Err("Cancelled".into()),
+            Some(Action::Submit) => self.validate_and_return(*selected),
+            Some(Action::Cancel) if self.allow_escape => Err("Cancelled".into()),
             _ => Ok(None),
         }
     }
 
+    /// the keys to use, falling back on the ones for the mode
+    fn keymap(&self) -> &KeyMap {
+        match (&self.keymap, &self.mode) {
+            (Some(keymap), _) => keymap,
+            (None, ConfirmMode::TextInput) => &TEXT_KEYMAP,
+            (None, ConfirmMode::Interactive) => &INTERACTIVE_KEYMAP,
+        }
+    }
+
     fn validate_and_return(&self, value: bool) -> Result<Option<bool>, String> {
         if let Some(ref validator) = self.validation {
             run_validator(validator.as_ref(), &value)?;
@@ -407,10 +449,25 @@ impl Confirm {
         crate::util::writeln_physical(out, &options_line, tw)?;
 
         if self.show_hints {
-            let hint_line = format!(
-                "  {}",
-                "← → to select, Enter to confirm, Esc to cancel".style(self.style.hint)
-            );
+            let mut hints = vec![];
+            let switch: Vec<String> = self
+                .keymap()
+                .keys(Action::SwitchChoice)
+                .take(2)
+                .map(|k| k.to_string())
+                .collect();
+            if !switch.is_empty() {
+                hints.push(format!("{} to select", switch.join(" ")));
+            }
+            if let Some(key) = self.keymap().hint(Action::Submit) {
+                hints.push(format!("{} to confirm", key));
+            }
+            if self.allow_escape
+                && let Some(key) = self.keymap().hint(Action::Cancel)
+            {
+                hints.push(format!("{} to cancel", key));
+            }
+            let hint_line = format!("  {}", hints.join(", ").style(self.style.hint));
             crate::util::writeln_physical(out, &hint_line, tw)?;
         }
 
@@ -0,0 +1,953 @@
+use {
+    crate::{
+        editor::Editor,
+        keymap::{Action, KeyMap},
+        style::DiffConfirmStyle,
+        util::CursorGuard,
+        validation::{Validate, run_validator},
+    },
+    crossterm::{
+        cursor,
+        event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+        queue,
+        terminal::{self, Clear, ClearType},
+    },
+    miette::IntoDiagnostic,
+    owo_colors::{OwoColorize, Style},
+    std::{
+        io::{Write, stdout},
+        ops::Range,
+    },
+};
+
+/// how a [`DiffConfirm`] lays out the changes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DiffView {
+    Unified,
+    SideBySide,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Op {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum LineKind {
+    Context,
+    Removed,
+    Added,
+}
+
+#[derive(Clone, Debug)]
+enum Row {
+    Header(String),
+    Unified {
+        old: Option<usize>,
+        new: Option<usize>,
+        kind: LineKind,
+        text: String,
+    },
+    Split {
+        left: Option<(usize, String)>,
+        right: Option<(usize, String)>,
+        changed: bool,
+    },
+}
+
+/// Myers' O(ND) line diff, in linear space
+///
+/// rather than keeping every round to trace the path back, each step finds
+/// the middle of the path and works out the two halves on their own.
+fn diff_lines(a: &[&str], b: &[&str]) -> Vec<Op> {
+    let mut differ = Differ {
+        a,
+        b,
+        forward: Frontier::new(a.len() + b.len()),
+        backward: Frontier::new(a.len() + b.len()),
+        ops: Vec::with_capacity(a.len().max(b.len())),
+    };
+    differ.diff(0..a.len(), 0..b.len());
+    let mut ops = differ.ops;
+
+    // each half puts its own deletions and insertions in, so gather them up
+    // the way `diff -u` shows them
+    let mut i = 0;
+    while i < ops.len() {
+        let len = ops[i..]
+            .iter()
+            .take_while(|op| !matches!(op, Op::Equal(..)))
+            .count();
+        ops[i..i + len].sort_by_key(|op| matches!(op, Op::Insert(_)));
+        i += len.max(1);
+    }
+
+    ops
+}
+
+/// how far along each diagonal a search has got, indexed by `x - y`
+struct Frontier {
+    x: Vec<usize>,
+    offset: isize,
+}
+
+impl Frontier {
+    fn new(len: usize) -> Self {
+        let max = len.div_ceil(2) + 1;
+        Self {
+            x: vec![0; 2 * max + 1],
+            offset: max as isize,
+        }
+    }
+
+    fn get(&self, k: isize) -> usize {
+        self.x[(k + self.offset) as usize]
+    }
+
+    fn set(&mut self, k: isize, x: usize) {
+        self.x[(k + self.offset) as usize] = x;
+    }
+}
+
+/// the state of a [`diff_lines`], shared by every step so the frontiers are
+/// only allocated once
+struct Differ<'a> {
+    a: &'a [&'a str],
+    b: &'a [&'a str],
+    forward: Frontier,
+    backward: Frontier,
+    ops: Vec<Op>,
+}
+
+impl Differ<'_> {
+    /// add the ops turning `old` lines of `a` into `new` lines of `b`
+    fn diff(&mut self, mut old: Range<usize>, mut new: Range<usize>) {
+        let (a, b) = (self.a, self.b);
+
+        while !old.is_empty() && !new.is_empty() && a[old.start] == b[new.start] {
+            self.ops.push(Op::Equal(old.start, new.start));
+            old.start += 1;
+            new.start += 1;
+        }
+        let mut suffix = 0;
+        while suffix < old.len()
+            && suffix < new.len()
+            && a[old.end - suffix - 1] == b[new.end - suffix - 1]
+        {
+            suffix += 1;
+        }
+        old.end -= suffix;
+        new.end -= suffix;
+
+        if old.is_empty() || new.is_empty() {
+            self.ops.extend(old.clone().map(Op::Delete));
+            self.ops.extend(new.clone().map(Op::Insert));
+        } else {
+            let (x, y) = self.middle_snake(old.clone(), new.clone());
+            self.diff(old.start..x, new.start..y);
+            self.diff(x..old.end, y..new.end);
+        }
+
+        self.ops
+            .extend((0..suffix).map(|i| Op::Equal(old.end + i, new.end + i)));
+    }
+
+    /// where the shortest path from the start of `old` and `new` to their
+    /// ends crosses the middle, found by searching from both ends at once
+    ///
+    /// both ranges have to be non-empty, and differ in their first and last
+    /// lines.
+    fn middle_snake(&mut self, old: Range<usize>, new: Range<usize>) -> (usize, usize) {
+        let (a, b) = (self.a, self.b);
+        let (forward, backward) = (&mut self.forward, &mut self.backward);
+        let n = old.len();
+        let m = new.len();
+        let delta = n as isize - m as isize;
+        let odd = delta % 2 != 0;
+        forward.set(1, 0);
+        backward.set(1, 0);
+
+        for d in 0..=(n + m).div_ceil(2) as isize {
+            for k in (-d..=d).rev().step_by(2) {
+                let mut x = if k == -d || (k != d && forward.get(k - 1) < forward.get(k + 1)) {
+                    forward.get(k + 1)
+                } else {
+                    forward.get(k - 1) + 1
+                };
+                let mut y = (x as isize - k) as usize;
+                let start = (old.start + x, new.start + y);
+                while x < n && y < m && a[old.start + x] == b[new.start + y] {
+                    x += 1;
+                    y += 1;
+                }
+                forward.set(k, x);
+                if odd && (k - delta).abs() < d && x + backward.get(delta - k) >= n {
+                    return start;
+                }
+            }
+
+            for k in (-d..=d).rev().step_by(2) {
+                let mut x = if k == -d || (k != d && backward.get(k - 1) < backward.get(k + 1)) {
+                    backward.get(k + 1)
+                } else {
+                    backward.get(k - 1) + 1
+                };
+                let mut y = (x as isize - k) as usize;
+                while x < n && y < m && a[old.end - x - 1] == b[new.end - y - 1] {
+                    x += 1;
+                    y += 1;
+                }
+                backward.set(k, x);
+                if !odd && (k - delta).abs() <= d && x + forward.get(delta - k) >= n {
+                    return (old.end - x, new.end - y);
+                }
+            }
+        }
+
+        unreachable!("the searches from both ends always meet")
+    }
+}
+
+/// group changed ops together with `context` unchanged ops on either side
+fn hunks(ops: &[Op], context: usize) -> Vec<Range<usize>> {
+    let mut hunks: Vec<Range<usize>> = Vec::new();
+
+    for (i, op) in ops.iter().enumerate() {
+        if matches!(op, Op::Equal(..)) {
+            continue;
+        }
+
+        let start = i.saturating_sub(context);
+        let end = (i + context + 1).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.end => last.end = last.end.max(end),
+            _ => hunks.push(start..end),
+        }
+    }
+
+    hunks
+}
+
+fn build_rows(
+    old_lines: &[&str],
+    new_lines: &[&str],
+    ops: &[Op],
+    view: DiffView,
+    context: usize,
+) -> Vec<Row> {
+    let display = |s: &str| s.replace('\t', "    ");
+
+    let mut positions = Vec::with_capacity(ops.len());
+    let (mut old_pos, mut new_pos) = (0, 0);
+    for op in ops {
+        positions.push((old_pos, new_pos));
+        match op {
+            Op::Equal(..) => {
+                old_pos += 1;
+                new_pos += 1;
+            }
+            Op::Delete(_) => old_pos += 1,
+            Op::Insert(_) => new_pos += 1,
+        }
+    }
+
+    let mut rows = Vec::new();
+
+    for hunk in hunks(ops, context) {
+        let slice = &ops[hunk.clone()];
+        let old_count = slice
+            .iter()
+            .filter(|op| !matches!(op, Op::Insert(_)))
+            .count();
+        let new_count = slice
+            .iter()
+            .filter(|op| !matches!(op, Op::Delete(_)))
+            .count();
+        // like `diff -u`, an empty side reports the line before the hunk
+        let (old_start, new_start) = positions[hunk.start];
+        let old_start = old_start + usize::from(old_count > 0);
+        let new_start = new_start + usize::from(new_count > 0);
+        rows.push(Row::Header(format!(
+            "@@ -{},{} +{},{} @@",
+            old_start, old_count, new_start, new_count,
+        )));
+
+        match view {
+            DiffView::Unified => {
+                for op in slice {
+                    rows.push(match *op {
+                        Op::Equal(o, n) => Row::Unified {
+                            old: Some(o),
+                            new: Some(n),
+                            kind: LineKind::Context,
+                            text: display(old_lines[o]),
+                        },
+                        Op::Delete(o) => Row::Unified {
+                            old: Some(o),
+                            new: None,
+                            kind: LineKind::Removed,
+                            text: display(old_lines[o]),
+                        },
+                        Op::Insert(n) => Row::Unified {
+                            old: None,
+                            new: Some(n),
+                            kind: LineKind::Added,
+                            text: display(new_lines[n]),
+                        },
+                    });
+                }
+            }
+            DiffView::SideBySide => {
+                let mut i = 0;
+                while i < slice.len() {
+                    if let Op::Equal(o, n) = slice[i] {
+                        rows.push(Row::Split {
+                            left: Some((o, display(old_lines[o]))),
+                            right: Some((n, display(new_lines[n]))),
+                            changed: false,
+                        });
+                        i += 1;
+                        continue;
+                    }
+
+                    let mut removed = Vec::new();
+                    let mut added = Vec::new();
+                    while i < slice.len() {
+                        match slice[i] {
+                            Op::Delete(o) => removed.push(o),
+                            Op::Insert(n) => added.push(n),
+                            Op::Equal(..) => break,
+                        }
+                        i += 1;
+                    }
+
+                    for j in 0..removed.len().max(added.len()) {
+                        rows.push(Row::Split {
+                            left: removed.get(j).map(|&o| (o, display(old_lines[o]))),
+                            right: added.get(j).map(|&n| (n, display(new_lines[n]))),
+                            changed: true,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    rows
+}
+
+/// the changes between two texts, laid out for one view, worked out once
+/// rather than on every redraw
+struct Diff {
+    ops: Vec<Op>,
+    rows: Vec<Row>,
+    added: usize,
+    removed: usize,
+    /// how wide the widest line number is
+    number_width: usize,
+}
+
+impl Diff {
+    fn new(old: &str, new: &str, view: DiffView, context: usize) -> Self {
+        let old_lines: Vec<&str> = old.lines().collect();
+        let new_lines: Vec<&str> = new.lines().collect();
+        let ops = diff_lines(&old_lines, &new_lines);
+        let (added, removed) = ops.iter().fold((0, 0), |(added, removed), op| match op {
+            Op::Insert(_) => (added + 1, removed),
+            Op::Delete(_) => (added, removed + 1),
+            Op::Equal(..) => (added, removed),
+        });
+        let rows = build_rows(&old_lines, &new_lines, &ops, view, context);
+        let number_width = old_lines
+            .len()
+            .max(new_lines.len())
+            .max(1)
+            .to_string()
+            .len();
+
+        Self {
+            ops,
+            rows,
+            added,
+            removed,
+            number_width,
+        }
+    }
+
+    /// lay the same changes out for another view
+    fn relayout(&mut self, old: &str, new: &str, view: DiffView, context: usize) {
+        let old_lines: Vec<&str> = old.lines().collect();
+        let new_lines: Vec<&str> = new.lines().collect();
+        self.rows = build_rows(&old_lines, &new_lines, &self.ops, view, context);
+    }
+}
+
+/// show the changes between two texts and ask whether to apply them
+///
+/// `ask` returns the proposed text (possibly tweaked in the editor) when
+/// accepted, or `None` when rejected.
+#[derive(Clone)]
+pub struct DiffConfirm {
+    prompt: String,
+    original: String,
+    proposed: String,
+    prompt_prefix: String,
+    help_message: Option<String>,
+    view: DiffView,
+    context_lines: usize,
+    page_size: Option<usize>,
+    show_hints: bool,
+    allow_escape: bool,
+    allow_edit: bool,
+    keymap: KeyMap,
+    editor: Option<Editor>,
+    style: DiffConfirmStyle,
+    validation: Option<Box<dyn Validate<str>>>,
+    _cursor_guard: CursorGuard,
+}
+
+impl DiffConfirm {
+    /// make a new diff prompt
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt` - the question to ask
+    /// * `original` - the current text
+    /// * `proposed` - the text that would replace it
+    pub fn new(
+        prompt: impl Into<String>,
+        original: impl Into<String>,
+        proposed: impl Into<String>,
+    ) -> Self {
+        let _cursor_guard = CursorGuard::new().expect("Failed to initialize cursor guard");
+        Self {
+            prompt: prompt.into(),
+            original: original.into(),
+            proposed: proposed.into(),
+            prompt_prefix: "±".into(),
+            help_message: None,
+            view: DiffView::Unified,
+            context_lines: 3,
+            page_size: None,
+            show_hints: true,
+            allow_escape: true,
+            allow_edit: true,
+            keymap: KeyMap::vim().with_binding(' ', Action::PageDown),
+            editor: None,
+            style: DiffConfirmStyle::default(),
+            validation: None,
+            _cursor_guard,
+        }
+    }
+
+    pub fn prompt(&self) -> &str {
+        &self.prompt
+    }
+
+    pub fn with_prompt_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prompt_prefix = prefix.into();
+        self
+    }
+
+    pub fn with_help_message(mut self, message: impl Into<String>) -> Self {
+        self.help_message = Some(message.into());
+        self
+    }
+
+    pub fn with_view(mut self, view: DiffView) -> Self {
+        self.view = view;
+        self
+    }
+
+    /// set how many unchanged lines are shown around each change
+    pub fn with_context_lines(mut self, lines: usize) -> Self {
+        self.context_lines = lines;
+        self
+    }
+
+    /// set how many diff lines are shown at once, instead of fitting the terminal
+    pub fn with_page_size(mut self, size: usize) -> Self {
+        self.page_size = Some(size.max(3));
+        self
+    }
+
+    pub fn with_hints(mut self, enabled: bool) -> Self {
+        self.show_hints = enabled;
+        self
+    }
+
+    pub fn with_escape(mut self, allow: bool) -> Self {
+        self.allow_escape = allow;
+        self
+    }
+
+    /// allow opening the proposed text in the editor before deciding
+    pub fn with_edit(mut self, allow: bool) -> Self {
+        self.allow_edit = allow;
+        self
+    }
+
+    /// set the editor used to tweak the proposed text
+    ///
+    /// its validation runs on the edited text, and its file extension
+    /// is used for the temp file.
+    pub fn with_editor(mut self, editor: Editor) -> Self {
+        self.editor = Some(editor);
+        self
+    }
+
+    /// set which keys do what
+    ///
+    /// the default is [`KeyMap::vim`] with Space bound to paging down.
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
+    pub fn with_style(mut self, style: DiffConfirmStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// register a validator that runs on the proposed text when it is accepted
+    pub fn with_validation(mut self, validation: impl Validate<str> + 'static) -> Self {
+        self.validation = Some(Box::new(validation));
+        self
+    }
+
+    pub fn ask(&self) -> miette::Result<Option<String>> {
+        let original_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |panic_info| {
+            let _ = terminal::disable_raw_mode();
+            std::panic::take_hook()(panic_info);
+        }));
+
+        let result = self.ask_internal();
+
+        let _ = std::panic::take_hook();
+        std::panic::set_hook(original_hook);
+
+        result
+    }
+
+    fn page_size(&self, has_help: bool) -> usize {
+        if let Some(size) = self.page_size {
+            return size;
+        }
+
+        let chrome = 4 + usize::from(has_help) + usize::from(self.show_hints);
+        (crate::util::term_height() as usize)
+            .saturating_sub(chrome)
+            .max(3)
+    }
+
+    fn ask_internal(&self) -> miette::Result<Option<String>> {
+        let mut proposed = self.proposed.clone();
+        let mut view = self.view;
+        let mut diff = Diff::new(&self.original, &proposed, view, self.context_lines);
+        let mut scroll = 0usize;
+        let mut error_message: Option<String> = None;
+        let mut buf = Vec::with_capacity(4096);
+        let mut out = stdout();
+
+        terminal::enable_raw_mode().into_diagnostic()?;
+
+        while event::poll(std::time::Duration::from_millis(0)).into_diagnostic()? {
+            event::read().into_diagnostic()?;
+        }
+
+        let mut last_render_lines =
+            self.render(&mut buf, &diff, scroll, error_message.as_deref())?;
+        out.write_all(&buf).into_diagnostic()?;
+        out.flush().into_diagnostic()?;
+
+        loop {
+            if let Event::Key(key_event) = event::read().into_diagnostic()? {
+                if key_event.kind != KeyEventKind::Press {
+                    continue;
+                }
+
+                error_message = None;
+                let mut edit_requested = false;
+                let shown = view;
+
+                match self.handle_key(
+                    key_event,
+                    &proposed,
+                    &diff.rows,
+                    &mut view,
+                    &mut scroll,
+                    &mut edit_requested,
+                ) {
+                    Ok(Some(accepted)) => {
+                        terminal::disable_raw_mode().into_diagnostic()?;
+                        buf.clear();
+                        if last_render_lines > 0 {
+                            queue!(buf, cursor::MoveUp(last_render_lines as u16))
+                                .into_diagnostic()?;
+                        }
+                        queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
+                        queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+                        self.show_result(&mut buf, accepted)?;
+                        out.write_all(&buf).into_diagnostic()?;
+                        out.flush().into_diagnostic()?;
+                        return Ok(accepted.then_some(proposed));
+                    }
+                    Ok(None) => {}
+                    Err(e) if e == "Cancelled" => {
+                        terminal::disable_raw_mode().into_diagnostic()?;
+                        buf.clear();
+                        if last_render_lines > 0 {
+                            queue!(buf, cursor::MoveUp(last_render_lines as u16))
+                                .into_diagnostic()?;
+                        }
+                        queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
+                        queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+                        self.show_error(&mut buf, &e)?;
+                        out.write_all(&buf).into_diagnostic()?;
+                        out.flush().into_diagnostic()?;
+                        return Err(miette::miette!(e));
+                    }
+                    Err(e) => {
+                        error_message = Some(e);
+                    }
+                }
+
+                buf.clear();
+                if last_render_lines > 0 {
+                    queue!(buf, cursor::MoveUp(last_render_lines as u16)).into_diagnostic()?;
+                }
+                queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
+                queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+
+                if edit_requested {
+                    out.write_all(&buf).into_diagnostic()?;
+                    out.flush().into_diagnostic()?;
+                    buf.clear();
+
+                    terminal::disable_raw_mode().into_diagnostic()?;
+                    let editor = self
+                        .editor
+                        .clone()
+                        .unwrap_or_else(|| Editor::new(self.prompt.clone()));
+                    match editor.edit_text(&proposed) {
+                        Ok(edited) => {
+                            proposed = edited;
+                            diff = Diff::new(&self.original, &proposed, view, self.context_lines);
+                            scroll = 0;
+                        }
+                        Err(e) => error_message = Some(e.to_string()),
+                    }
+                    terminal::enable_raw_mode().into_diagnostic()?;
+                }
+
+                if view != shown {
+                    diff.relayout(&self.original, &proposed, view, self.context_lines);
+                }
+                let page_size = self.page_size(self.help_message.is_some());
+                scroll = scroll.min(diff.rows.len().saturating_sub(page_size));
+
+                last_render_lines =
+                    self.render(&mut buf, &diff, scroll, error_message.as_deref())?;
+                out.write_all(&buf).into_diagnostic()?;
+                out.flush().into_diagnostic()?;
+            }
+        }
+    }
+
+    const ACTIONS: &[Action] = &[
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::PageUp,
+        Action::PageDown,
+        Action::MoveToFirst,
+        Action::MoveToLast,
+        Action::SwitchView,
+        Action::Edit,
+        Action::Yes,
+        Action::No,
+        Action::Submit,
+        Action::Cancel,
+    ];
+
+    fn handle_key(
+        &self,
+        key_event: KeyEvent,
+        proposed: &str,
+        rows: &[Row],
+        view: &mut DiffView,
+        scroll: &mut usize,
+        edit_requested: &mut bool,
+    ) -> Result<Option<bool>, String> {
+        if key_event.modifiers.contains(KeyModifiers::CONTROL)
+            && matches!(key_event.code, KeyCode::Char('c'))
+        {
+            let _ = terminal::disable_raw_mode();
+            std::process::exit(130);
+        }
+
+        let page_size = self.page_size(self.help_message.is_some());
+        let max_scroll = rows.len().saturating_sub(page_size);
+
+        match self.keymap.action(&key_event, Self::ACTIONS) {
+            Some(Action::MoveUp) => {
+                *scroll = scroll.saturating_sub(1);
+                Ok(None)
+            }
+            Some(Action::MoveDown) => {
+                *scroll = (*scroll + 1).min(max_scroll);
+                Ok(None)
+            }
+            Some(Action::PageUp) => {
+                *scroll = scroll.saturating_sub(page_size);
+                Ok(None)
+            }
+            Some(Action::PageDown) => {
+                *scroll = (*scroll + page_size).min(max_scroll);
+                Ok(None)
+            }
+            Some(Action::MoveToFirst) => {
+                *scroll = 0;
+                Ok(None)
+            }
+            Some(Action::MoveToLast) => {
+                *scroll = max_scroll;
+                Ok(None)
+            }
+            Some(Action::SwitchView) => {
+                *view = match *view {
+                    DiffView::Unified => DiffView::SideBySide,
+                    DiffView::SideBySide => DiffView::Unified,
+                };
+                Ok(None)
+            }
+            Some(Action::Edit) if self.allow_edit => {
+                *edit_requested = true;
+                Ok(None)
+            }
+            Some(Action::Yes | Action::Submit) => {
+                if let Some(ref validator) = self.validation {
+                    run_validator(validator.as_ref(), proposed)?;
+                }
+                Ok(Some(true))
+            }
+            Some(Action::No) => Ok(Some(false)),
+            Some(Action::Cancel) if self.allow_escape => Err("Cancelled".into()),
+            _ => Ok(None),
+        }
+    }
+
+    fn render(
+        &self,
+        out: &mut impl Write,
+        diff: &Diff,
+        scroll: usize,
+        error: Option<&str>,
+    ) -> miette::Result<usize> {
+        let tw = crate::util::term_width();
+        let mut line_count = 0;
+        let rows = &diff.rows;
+
+        let line = format!(
+            "{} {} {} {}",
+            self.prompt_prefix.style(self.style.prompt_prefix),
+            self.prompt.style(self.style.prompt),
+            format!("+{}", diff.added).style(self.style.added),
+            format!("-{}", diff.removed).style(self.style.removed),
+        );
+        line_count += crate::util::writeln_physical(out, &line, tw)?;
+
+        if let Some(ref help) = self.help_message {
+            let line = format!("  {}", help.style(self.style.hint));
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        let page_size = self.page_size(self.help_message.is_some());
+        let end = (scroll + page_size).min(rows.len());
+
+        if rows.is_empty() {
+            let line = format!("  {}", "(no changes)".style(self.style.hint));
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        for row in &rows[scroll..end] {
+            let line = self.render_row(row, diff.number_width, tw as usize);
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        if rows.len() > page_size {
+            let line = format!(
+                "  {}",
+                format!("(lines {}-{} of {})", scroll + 1, end, rows.len()).style(self.style.hint)
+            );
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        if let Some(err) = error {
+            let line = format!(
+                "  {} {}",
+                self.style
+                    .error_prefix
+                    .as_deref()
+                    .unwrap_or("✗")
+                    .style(self.style.error),
+                err.style(self.style.error_hint)
+            );
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        if self.show_hints {
+            let keymap = &self.keymap;
+            let mut hints = vec![];
+
+            if rows.len() > page_size {
+                let scroll: Vec<String> = [
+                    keymap.hint_pair(Action::MoveUp, Action::MoveDown),
+                    keymap.hint_pair(Action::PageUp, Action::PageDown),
+                ]
+                .into_iter()
+                .flatten()
+                .collect();
+                if !scroll.is_empty() {
+                    hints.push(format!("{} to scroll", scroll.join(" or ")));
+                }
+            }
+
+            if let Some(key) = keymap.hint(Action::SwitchView) {
+                hints.push(format!("{} to switch view", key));
+            }
+            if let Some(key) = keymap.hint(Action::Yes) {
+                hints.push(format!("{} to accept", key));
+            }
+            if let Some(key) = keymap.hint(Action::No) {
+                hints.push(format!("{} to reject", key));
+            }
+
+            if self.allow_edit
+                && let Some(key) = keymap.hint(Action::Edit)
+            {
+                hints.push(format!("{} to edit", key));
+            }
+
+            if self.allow_escape
+                && let Some(key) = keymap.hint(Action::Cancel)
+            {
+                hints.push(format!("{} to cancel", key));
+            }
+
+            let line = format!("  {}", hints.join(", ").style(self.style.hint));
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        Ok(line_count)
+    }
+
+    fn render_row(&self, row: &Row, number_width: usize, tw: usize) -> String {
+        let number = |n: Option<usize>| match n {
+            Some(n) => format!("{:>width$}", n + 1, width = number_width),
+            None => " ".repeat(number_width),
+        };
+        let kind_style = |kind: LineKind| -> (&str, Style) {
+            match kind {
+                LineKind::Context => (" ", self.style.context),
+                LineKind::Removed => ("-", self.style.removed),
+                LineKind::Added => ("+", self.style.added),
+            }
+        };
+
+        match row {
+            Row::Header(text) => format!(
+                "  {}",
+                crate::util::truncate(text, tw.saturating_sub(3)).style(self.style.hunk_header)
+            ),
+            Row::Unified {
+                old,
+                new,
+                kind,
+                text,
+            } => {
+                let (sign, style) = kind_style(*kind);
+                let text_width = tw.saturating_sub(2 * number_width + 7);
+                format!(
+                    "  {} {} {}",
+                    number(*old).style(self.style.line_number),
+                    number(*new).style(self.style.line_number),
+                    format!("{} {}", sign, crate::util::truncate(text, text_width)).style(style),
+                )
+            }
+            Row::Split {
+                left,
+                right,
+                changed,
+            } => {
+                let column_width = tw.saturating_sub(2 * number_width + 10) / 2;
+                let side = |cell: &Option<(usize, String)>, kind: LineKind, pad: bool| {
+                    let (sign, style) = if *changed {
+                        kind_style(kind)
+                    } else {
+                        kind_style(LineKind::Context)
+                    };
+                    let (n, text) = match cell {
+                        Some((n, text)) => (Some(*n), crate::util::truncate(text, column_width)),
+                        None => (None, String::new()),
+                    };
+                    let sign = if cell.is_some() { sign } else { " " };
+                    let text = if pad {
+                        format!("{:<width$}", text, width = column_width)
+                    } else {
+                        text
+                    };
+                    format!(
+                        "{} {}",
+                        number(n).style(self.style.line_number),
+                        format!("{}{}", sign, text).style(style),
+                    )
+                };
+
+                format!(
+                    "  {} {} {}",
+                    side(left, LineKind::Removed, true),
+                    "│".style(self.style.line_number),
+                    side(right, LineKind::Added, false),
+                )
+            }
+        }
+    }
+
+    fn show_error(&self, out: &mut impl Write, error: &str) -> miette::Result<()> {
+        let tw = crate::util::term_width();
+        let line = format!(
+            "{} {}",
+            self.style
+                .error_prefix
+                .as_deref()
+                .unwrap_or("✗")
+                .style(self.style.error),
+            error.style(self.style.error_hint),
+        );
+        crate::util::writeln_physical(out, &line, tw)?;
+
+        Ok(())
+    }
+
+    fn show_result(&self, out: &mut impl Write, accepted: bool) -> miette::Result<()> {
+        let (result_text, result_style) = if accepted {
+            ("accepted", self.style.yes_style)
+        } else {
+            ("rejected", self.style.no_style)
+        };
+
+        let tw = crate::util::term_width();
+        let line = format!(
+            "{} {} {}",
+            self.prompt_prefix.style(self.style.prompt_prefix),
+            self.prompt.style(self.style.prompt),
+            result_text.style(result_style).bold(),
+        );
+        crate::util::writeln_physical(out, &line, tw)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+use {
+    crate::confirm::Confirm,
+    miette::IntoDiagnostic,
+    std::{
+        fs,
+        path::{Path, PathBuf},
+    },
+};
+
+/// where unsaved prompt content is kept between runs, keyed by a prompt id
+///
+/// defaults to `$XDG_STATE_HOME/bearask/drafts` (or `~/.local/state/bearask/drafts`).
+#[derive(Clone, Debug)]
+pub struct DraftStore {
+    dir: PathBuf,
+}
+
+impl Default for DraftStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DraftStore {
+    /// make a store in the default state directory
+    pub fn new() -> Self {
+        Self {
+            dir: crate::util::state_dir().join("drafts"),
+        }
+    }
+
+    /// make a store that keeps drafts in `dir`
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - the directory to keep drafts in
+    pub fn in_dir(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// the directory drafts are kept in
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// throw away the draft for `id`, if there is one, whichever prompt
+    /// saved it
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the id of the prompt the draft belongs to
+    pub fn discard(&self, id: &str) {
+        // stems never have a dot in them, so this can't catch another id's
+        let prefix = format!("{}.", crate::util::file_stem(id));
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with(&prefix) {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+
+    /// throw away the draft for `id` saved with `extension`
+    pub(crate) fn remove(&self, id: &str, extension: &str) {
+        let _ = fs::remove_file(self.path(id, extension));
+    }
+
+    pub(crate) fn path(&self, id: &str, extension: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}.{}", crate::util::file_stem(id), extension))
+    }
+
+    pub(crate) fn load(&self, id: &str, extension: &str) -> Option<String> {
+        fs::read_to_string(self.path(id, extension)).ok()
+    }
+
+    pub(crate) fn save(&self, id: &str, extension: &str, content: &str) -> miette::Result<()> {
+        fs::create_dir_all(&self.dir).into_diagnostic()?;
+        fs::write(self.path(id, extension), content).into_diagnostic()
+    }
+
+    /// ask whether to pick up the draft for `id` where it was left off
+    ///
+    /// returns the draft if the user wants it back, and discards it otherwise.
+    /// drafts that match `initial` are not worth asking about.
+    pub(crate) fn offer_restore(
+        &self,
+        id: &str,
+        extension: &str,
+        prompt: &str,
+        initial: &str,
+    ) -> miette::Result<Option<String>> {
+        let Some(draft) = self.load(id, extension) else {
+            return Ok(None);
+        };
+
+        if draft.trim().is_empty() || draft.trim() == initial.trim() {
+            return Ok(None);
+        }
+
+        let restore = Confirm::new(format!("Restore unsaved draft for \"{}\"?", prompt))
+            .with_default(true)
+            .ask()?;
+
+        if restore {
+            Ok(Some(draft))
+        } else {
+            self.remove(id, extension);
+            Ok(None)
+        }
+    }
+}
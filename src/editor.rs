@@ -1,5 +1,7 @@
 use {
     crate::{
+        draft::DraftStore,
+        keymap::{Action, KeyMap},
         style::EditorStyle,
         util::CursorGuard,
         validation::{Validate, run_validator},
@@ -11,12 +13,285 @@ use {
     miette::IntoDiagnostic,
     owo_colors::OwoColorize,
     std::{
+        collections::HashSet,
         env,
         io::{Write, stdout},
+        path::{Path, PathBuf},
         process::Command,
     },
 };
 
+#[cfg(feature = "serde")]
+use serde::{Serialize, de::DeserializeOwned};
+
+/// a text format used to round-trip a value through the editor
+#[cfg(feature = "serde")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EditorFormat {
+    Toml,
+    Json,
+    Yaml,
+}
+
+#[cfg(feature = "serde")]
+impl EditorFormat {
+    /// the file extension the temp file is given, so editors pick the right syntax
+    pub fn extension(self) -> &'static str {
+        match self {
+            EditorFormat::Toml => "toml",
+            EditorFormat::Json => "json",
+            EditorFormat::Yaml => "yaml",
+        }
+    }
+
+    fn annotation_prefix(self) -> &'static str {
+        match self {
+            EditorFormat::Toml | EditorFormat::Yaml => "# bearask:",
+            EditorFormat::Json => "// bearask:",
+        }
+    }
+
+    fn serialize<T: Serialize>(self, value: &T) -> miette::Result<String> {
+        match self {
+            EditorFormat::Toml => toml::to_string_pretty(value).into_diagnostic(),
+            EditorFormat::Json => serde_json::to_string_pretty(value).into_diagnostic(),
+            EditorFormat::Yaml => serde_yaml_ng::to_string(value).into_diagnostic(),
+        }
+    }
+
+    fn deserialize<T: DeserializeOwned>(self, text: &str) -> Result<T, String> {
+        match self {
+            EditorFormat::Toml => toml::from_str(text).map_err(|e| e.to_string()),
+            EditorFormat::Json => serde_json::from_str(text).map_err(|e| e.to_string()),
+            EditorFormat::Yaml => serde_yaml_ng::from_str(text).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+const ANNOTATION_PREFIX: &str = "# bearask:";
+
+/// append the error below the content so reported line numbers still match the file
+///
+/// an error block left over from a previous attempt is replaced rather than stacked.
+fn annotate(prefix: &str, content: &str, error: &str) -> String {
+    let mut lines: Vec<&str> = content.lines().collect();
+    while lines
+        .last()
+        .is_some_and(|line| line.trim().is_empty() || line.trim_start().starts_with(prefix))
+    {
+        lines.pop();
+    }
+
+    let mut annotated = format!("{}\n\n", lines.join("\n"));
+    annotated.push_str(&format!(
+        "{} fix the error below and save, or save without changes to cancel\n",
+        prefix
+    ));
+    for line in error.lines() {
+        if line.trim().is_empty() {
+            annotated.push_str(&format!("{}\n", prefix));
+        } else {
+            annotated.push_str(&format!("{} {}\n", prefix, line));
+        }
+    }
+    annotated
+}
+
+/// blank out annotation lines, leaving the rest where they were so line numbers
+/// in parse errors still match the file
+fn strip_annotations(prefix: &str, content: &str) -> String {
+    content
+        .lines()
+        .map(|line| {
+            if line.trim_start().starts_with(prefix) {
+                ""
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_end()
+        .to_string()
+}
+
+/// write `message` above `content` as annotation lines
+#[cfg(feature = "serde")]
+fn with_header(prefix: &str, message: &str, content: &str) -> String {
+    let mut text = String::new();
+    for line in message.lines() {
+        if line.trim().is_empty() {
+            text.push_str(&format!("{}\n", prefix));
+        } else {
+            text.push_str(&format!("{} {}\n", prefix, line));
+        }
+    }
+    text.push_str(content);
+    text
+}
+
+/// a single change made to the list passed to [`Editor::ask_items`]
+///
+/// `id` is the index of the item in the original list, and `to` and `index`
+/// are where an item ends up in the final list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ItemEdit {
+    Renamed { id: usize, from: String, to: String },
+    Deleted { id: usize, name: String },
+    Moved { id: usize, to: usize },
+    Added { index: usize, name: String },
+}
+
+/// the result of [`Editor::ask_items`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ItemEdits {
+    /// the items in their final order, with the original index of every kept item
+    pub items: Vec<(Option<usize>, String)>,
+    /// the changes relative to the original list
+    pub changes: Vec<ItemEdit>,
+}
+
+fn id_width(count: usize) -> usize {
+    count.max(1).to_string().len()
+}
+
+fn format_items(items: &[String]) -> String {
+    let width = id_width(items.len());
+    let mut text = format!(
+        "{} edit names after the tab, delete lines to remove items, reorder lines to move them\n\
+         {} lines without an id are added as new items, and a leading \\ keeps one as written\n",
+        ANNOTATION_PREFIX, ANNOTATION_PREFIX
+    );
+    for (i, item) in items.iter().enumerate() {
+        text.push_str(&format!("{:0width$}\t{}\n", i + 1, item, width = width));
+    }
+    text
+}
+
+fn parse_items(original: &[String], text: &str) -> Result<ItemEdits, String> {
+    let mut items: Vec<(Option<usize>, String)> = Vec::new();
+    let mut seen = vec![false; original.len()];
+
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        // a new item whose name would be taken for an id or an instruction
+        // can be written after a backslash
+        if let Some(name) = line.strip_prefix('\\') {
+            if name.trim().is_empty() {
+                return Err(format!("Missing name after \\ in line: {}", line));
+            }
+            if name.contains('\t') {
+                return Err(format!("Tab in name in line: {}", line));
+            }
+            items.push((None, name.to_string()));
+            continue;
+        }
+
+        let tagged = line
+            .split_once('\t')
+            .filter(|(id, _)| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()));
+
+        match tagged {
+            Some((id, name)) => {
+                let id = id
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&id| id >= 1 && id <= original.len())
+                    .ok_or_else(|| format!("Unknown id {} in line: {}", id, line))?
+                    - 1;
+                if seen[id] {
+                    return Err(format!("Id {} is used more than once", id + 1));
+                }
+                if name.trim().is_empty() {
+                    return Err(format!("Missing name for id {} in line: {}", id + 1, line));
+                }
+                if name.contains('\t') {
+                    return Err(format!("Tab in name in line: {}", line));
+                }
+                seen[id] = true;
+                items.push((Some(id), name.to_string()));
+            }
+            None if line.contains('\t') => {
+                return Err(format!("Tab in name in line: {}", line));
+            }
+            None => items.push((None, line.to_string())),
+        }
+    }
+
+    let mut changes = Vec::new();
+
+    for (id, name) in original.iter().enumerate() {
+        if !seen[id] {
+            changes.push(ItemEdit::Deleted {
+                id,
+                name: name.clone(),
+            });
+        }
+    }
+
+    for (id, name) in items.iter() {
+        if let Some(id) = *id
+            && original[id] != *name
+        {
+            changes.push(ItemEdit::Renamed {
+                id,
+                from: original[id].clone(),
+                to: name.clone(),
+            });
+        }
+    }
+
+    let kept: Vec<usize> = items.iter().filter_map(|(id, _)| *id).collect();
+    let in_place = longest_increasing(&kept);
+    for (index, (id, _)) in items.iter().enumerate() {
+        if let Some(id) = *id
+            && !in_place.contains(&id)
+        {
+            changes.push(ItemEdit::Moved { id, to: index });
+        }
+    }
+
+    for (index, (id, name)) in items.iter().enumerate() {
+        if id.is_none() {
+            changes.push(ItemEdit::Added {
+                index,
+                name: name.clone(),
+            });
+        }
+    }
+
+    Ok(ItemEdits { items, changes })
+}
+
+/// the largest set of ids that kept their relative order, so only the rest count as moved
+fn longest_increasing(ids: &[usize]) -> HashSet<usize> {
+    let mut tails: Vec<usize> = Vec::new();
+    let mut parents: Vec<Option<usize>> = vec![None; ids.len()];
+
+    for (i, &id) in ids.iter().enumerate() {
+        let pos = tails.partition_point(|&t| ids[t] < id);
+        if pos > 0 {
+            parents[i] = Some(tails[pos - 1]);
+        }
+        if pos == tails.len() {
+            tails.push(i);
+        } else {
+            tails[pos] = i;
+        }
+    }
+
+    let mut result = HashSet::new();
+    let mut current = tails.last().copied();
+    while let Some(i) = current {
+        result.insert(ids[i]);
+        current = parents[i];
+    }
+    result
+}
+
 #[derive(Clone)]
 pub struct Editor {
     prompt: String,
@@ -28,8 +303,11 @@ pub struct Editor {
     require_changes: bool,
     show_hints: bool,
     allow_escape: bool,
+    keymap: KeyMap,
     style: EditorStyle,
     validation: Option<Box<dyn Validate<str>>>,
+    draft_id: Option<String>,
+    draft_store: DraftStore,
     _cursor_guard: CursorGuard,
 }
 
@@ -47,8 +325,11 @@ impl Editor {
             require_changes: false,
             show_hints: true,
             allow_escape: true,
+            keymap: KeyMap::new(),
             style: EditorStyle::default(),
             validation: None,
+            draft_id: None,
+            draft_store: DraftStore::default(),
             _cursor_guard,
         }
     }
@@ -97,6 +378,12 @@ impl Editor {
         self
     }
 
+    /// set which keys open the editor and cancel
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
     pub fn with_style(mut self, style: EditorStyle) -> Self {
         self.style = style;
         self
@@ -107,6 +394,29 @@ impl Editor {
         self
     }
 
+    /// keep the file being edited in the draft store under `id`
+    ///
+    /// the file survives crashes and failed editor runs, and the next `ask`
+    /// with the same id offers to pick up where it was left off.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - a name for this prompt that stays the same between runs
+    pub fn with_draft(mut self, id: impl Into<String>) -> Self {
+        self.draft_id = Some(id.into());
+        self
+    }
+
+    /// set where drafts are kept, see [`Editor::with_draft`]
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - the draft store to use
+    pub fn with_draft_store(mut self, store: DraftStore) -> Self {
+        self.draft_store = store;
+        self
+    }
+
     fn detect_editor(&self) -> String {
         if let Some(ref cmd) = self.editor_command {
             return cmd.clone();
@@ -141,21 +451,233 @@ impl Editor {
         result
     }
 
+    /// serialize `value`, let the user edit it, and parse the result back
+    ///
+    /// the temp file uses the extension of `format`. if the edited text fails
+    /// to parse, the editor is re-opened with the error appended as comments;
+    /// saving the file unchanged at that point gives up and returns the error.
+    /// a message set with `with_predefined_message` is written above the value
+    /// as comments, and left out again when parsing.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - the value to start editing from
+    /// * `format` - the text format to edit the value as
+    #[cfg(feature = "serde")]
+    pub fn ask_value<T: Serialize + DeserializeOwned>(
+        &self,
+        value: &T,
+        format: EditorFormat,
+    ) -> miette::Result<T> {
+        let original_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |panic_info| {
+            let _ = terminal::disable_raw_mode();
+            std::panic::take_hook()(panic_info);
+        }));
+
+        let result = self.ask_value_internal(value, format);
+
+        let _ = std::panic::take_hook();
+        std::panic::set_hook(original_hook);
+
+        result
+    }
+
+    #[cfg(feature = "serde")]
+    fn ask_value_internal<T: Serialize + DeserializeOwned>(
+        &self,
+        value: &T,
+        format: EditorFormat,
+    ) -> miette::Result<T> {
+        let mut out = stdout();
+        let editor = self.detect_editor();
+        let tw = crate::util::term_width();
+
+        let original = format.serialize(value)?;
+        let text = match self.predefined_message {
+            Some(ref message) => with_header(format.annotation_prefix(), message, &original),
+            None => original.clone(),
+        };
+        let initial = self.restore_draft(format.extension(), &text)?;
+
+        self.wait_for_open(&mut out, &editor, tw)?;
+
+        let temp_path = self.edit_path(format.extension());
+        let (parsed, edited) = self.edit_until_parsed(
+            &editor,
+            &temp_path,
+            &initial,
+            format.annotation_prefix(),
+            |text| format.deserialize::<T>(text),
+        )?;
+
+        if self.require_changes && edited.trim() == original.trim() {
+            return Err(miette::miette!("No changes were made"));
+        }
+
+        if let Some(ref validator) = self.validation {
+            run_validator(validator.as_ref(), &edited).map_err(|e| miette::miette!(e))?;
+        }
+
+        self.discard_draft(format.extension());
+        self.show_result(&mut out, edited.lines().count(), tw)?;
+
+        Ok(parsed)
+    }
+
+    /// write `items` one per line, tagged with an id, and diff the edited file against them
+    ///
+    /// deleting a line deletes the item, changing the text after the tab renames it,
+    /// reordering lines moves items and lines without an id are added as new items.
+    /// a new line starting with `\` is added as the rest of it, for names that would
+    /// otherwise be read as an id or an instruction. a malformed file is re-opened
+    /// with the problem appended, like `Editor::ask_value`.
+    /// names with tabs or line breaks can't be written as one line, so they're an error,
+    /// and a line left with an id or `\` but no name is sent back to be fixed.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - the items to edit
+    pub fn ask_items(&self, items: &[impl AsRef<str>]) -> miette::Result<ItemEdits> {
+        let original_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |panic_info| {
+            let _ = terminal::disable_raw_mode();
+            std::panic::take_hook()(panic_info);
+        }));
+
+        let items: Vec<String> = items.iter().map(|i| i.as_ref().to_string()).collect();
+        let result = self.ask_items_internal(&items);
+
+        let _ = std::panic::take_hook();
+        std::panic::set_hook(original_hook);
+
+        result
+    }
+
+    fn ask_items_internal(&self, items: &[String]) -> miette::Result<ItemEdits> {
+        if let Some(id) = items
+            .iter()
+            .position(|item| item.contains(['\t', '\n', '\r']))
+        {
+            return Err(miette::miette!(
+                "Item {} has a tab or line break in its name, so it can't be edited as a line",
+                id + 1
+            ));
+        }
+
+        let mut out = stdout();
+        let editor = self.detect_editor();
+        let tw = crate::util::term_width();
+
+        let initial = self.restore_draft(&self.file_extension, &format_items(items))?;
+
+        self.wait_for_open(&mut out, &editor, tw)?;
+
+        let temp_path = self.edit_path(&self.file_extension);
+        let (edits, _) =
+            self.edit_until_parsed(&editor, &temp_path, &initial, ANNOTATION_PREFIX, |text| {
+                parse_items(items, text)
+            })?;
+
+        if self.require_changes && edits.changes.is_empty() {
+            return Err(miette::miette!("No changes were made"));
+        }
+
+        self.discard_draft(&self.file_extension);
+
+        let line = format!(
+            "{} {} {}",
+            self.prompt_prefix.style(self.style.prompt_prefix),
+            self.prompt.style(self.style.prompt),
+            format!(
+                "({} change{})",
+                edits.changes.len(),
+                if edits.changes.len() == 1 { "" } else { "s" }
+            )
+            .style(self.style.success)
+            .bold(),
+        );
+        crate::util::writeln_physical(&mut out, &line, tw)?;
+        out.flush().into_diagnostic()?;
+
+        Ok(edits)
+    }
+
+    /// keep re-opening the editor until `parse` accepts the content
+    ///
+    /// returns the parsed value along with the content it was parsed from,
+    /// with annotation lines removed.
+    fn edit_until_parsed<R>(
+        &self,
+        editor: &str,
+        path: &Path,
+        initial: &str,
+        prefix: &str,
+        parse: impl Fn(&str) -> Result<R, String>,
+    ) -> miette::Result<(R, String)> {
+        let mut contents = initial.to_string();
+        let mut failed: Option<(String, String)> = None;
+
+        loop {
+            let edited = self.edit_file(editor, path, &contents)?;
+            let stripped = strip_annotations(prefix, &edited);
+
+            match parse(&stripped) {
+                Ok(parsed) => return Ok((parsed, stripped.trim_start().to_string())),
+                Err(error) => {
+                    if let Some((previous, previous_error)) = failed.take()
+                        && previous == stripped
+                    {
+                        return Err(miette::miette!(previous_error));
+                    }
+
+                    contents = annotate(prefix, &edited, &error);
+                    failed = Some((stripped, error));
+                }
+            }
+        }
+    }
+
     fn ask_internal(&self) -> miette::Result<String> {
         let mut out = stdout();
         let editor = self.detect_editor();
         let tw = crate::util::term_width();
 
+        let predefined = self.predefined_message.as_deref().unwrap_or("");
+        let initial = self.restore_draft(&self.file_extension, predefined)?;
+
+        self.wait_for_open(&mut out, &editor, tw)?;
+
+        let temp_path = self.edit_path(&self.file_extension);
+        let content = self.edit_file(&editor, &temp_path, &initial)?;
+
+        let trimmed = content.trim().to_string();
+
+        if self.require_changes && trimmed == predefined.trim() {
+            return Err(miette::miette!("No changes were made"));
+        }
+
+        if let Some(ref validator) = self.validation {
+            run_validator(validator.as_ref(), &trimmed).map_err(|e| miette::miette!(e))?;
+        }
+
+        self.discard_draft(&self.file_extension);
+        self.show_result(&mut out, trimmed.lines().count(), tw)?;
+
+        Ok(trimmed)
+    }
+
+    fn wait_for_open(&self, out: &mut impl Write, editor: &str, tw: u16) -> miette::Result<()> {
         let line = format!(
             "{} {}",
             self.prompt_prefix.style(self.style.prompt_prefix),
             self.prompt.style(self.style.prompt),
         );
-        crate::util::writeln_physical(&mut out, &line, tw)?;
+        crate::util::writeln_physical(out, &line, tw)?;
 
         if let Some(ref help) = self.help_message {
             let line = format!("  {}", help.style(self.style.hint));
-            crate::util::writeln_physical(&mut out, &line, tw)?;
+            crate::util::writeln_physical(out, &line, tw)?;
         }
 
         let line = format!(
@@ -163,15 +685,20 @@ impl Editor {
             "Editor:".style(self.style.hint),
             editor.style(self.style.editor_command),
         );
-        crate::util::writeln_physical(&mut out, &line, tw)?;
+        crate::util::writeln_physical(out, &line, tw)?;
 
         if self.show_hints {
-            let mut hints = vec!["Enter to open editor"];
-            if self.allow_escape {
-                hints.push("Esc to cancel");
+            let mut hints = vec![];
+            if let Some(key) = self.keymap.hint(Action::Submit) {
+                hints.push(format!("{} to open editor", key));
+            }
+            if self.allow_escape
+                && let Some(key) = self.keymap.hint(Action::Cancel)
+            {
+                hints.push(format!("{} to cancel", key));
             }
             let line = format!("  {}", hints.join(", ").style(self.style.hint));
-            crate::util::writeln_physical(&mut out, &line, tw)?;
+            crate::util::writeln_physical(out, &line, tw)?;
         }
 
         out.flush().into_diagnostic()?;
@@ -195,12 +722,15 @@ impl Editor {
                     std::process::exit(130);
                 }
 
-                match key_event.code {
-                    KeyCode::Enter => {
+                match self
+                    .keymap
+                    .action(&key_event, &[Action::Submit, Action::Cancel])
+                {
+                    Some(Action::Submit) => {
                         terminal::disable_raw_mode().into_diagnostic()?;
-                        break;
+                        return Ok(());
                     }
-                    KeyCode::Esc if self.allow_escape => {
+                    Some(Action::Cancel) if self.allow_escape => {
                         terminal::disable_raw_mode().into_diagnostic()?;
                         return Err(miette::miette!("Cancelled"));
                     }
@@ -208,16 +738,58 @@ impl Editor {
                 }
             }
         }
+    }
 
-        let temp_dir = env::temp_dir();
-        let file_name = format!("bearask_{}.{}", std::process::id(), self.file_extension);
-        let temp_path = temp_dir.join(file_name);
+    fn edit_path(&self, extension: &str) -> PathBuf {
+        if let Some(ref id) = self.draft_id {
+            return self.draft_store.path(id, extension);
+        }
 
-        if let Some(ref message) = self.predefined_message {
-            std::fs::write(&temp_path, message).into_diagnostic()?;
-        } else {
-            std::fs::write(&temp_path, "").into_diagnostic()?;
+        let file_name = format!("bearask_{}.{}", std::process::id(), extension);
+        env::temp_dir().join(file_name)
+    }
+
+    fn restore_draft(&self, extension: &str, initial: &str) -> miette::Result<String> {
+        let Some(ref id) = self.draft_id else {
+            return Ok(initial.to_string());
+        };
+
+        let draft = self
+            .draft_store
+            .offer_restore(id, extension, &self.prompt, initial)?;
+        Ok(draft.unwrap_or_else(|| initial.to_string()))
+    }
+
+    fn discard_draft(&self, extension: &str) {
+        if let Some(ref id) = self.draft_id {
+            self.draft_store.remove(id, extension);
+        }
+    }
+
+    /// open the editor on `initial` straight away, without asking first
+    ///
+    /// used by prompts that hand text off to the editor on a key press.
+    /// the content is validated but returned untrimmed.
+    pub(crate) fn edit_text(&self, initial: &str) -> miette::Result<String> {
+        let editor = self.detect_editor();
+        let content = self.edit_file(&editor, &self.edit_path(&self.file_extension), initial)?;
+
+        if let Some(ref validator) = self.validation {
+            run_validator(validator.as_ref(), content.trim()).map_err(|e| miette::miette!(e))?;
+        }
+
+        self.discard_draft(&self.file_extension);
+
+        Ok(content)
+    }
+
+    fn edit_file(&self, editor: &str, path: &Path, initial: &str) -> miette::Result<String> {
+        if self.draft_id.is_some()
+            && let Some(dir) = path.parent()
+        {
+            std::fs::create_dir_all(dir).into_diagnostic()?;
         }
+        std::fs::write(path, initial).into_diagnostic()?;
 
         let editor_parts: Vec<&str> = editor.split_whitespace().collect();
         let (program, args) = editor_parts
@@ -226,32 +798,26 @@ impl Editor {
 
         let status = Command::new(program)
             .args(args)
-            .arg(&temp_path)
+            .arg(path)
             .status()
             .into_diagnostic()?;
 
         if !status.success() {
-            let _ = std::fs::remove_file(&temp_path);
+            if self.draft_id.is_none() {
+                let _ = std::fs::remove_file(path);
+            }
             return Err(miette::miette!("Editor exited with status: {}", status));
         }
 
-        let content = std::fs::read_to_string(&temp_path).into_diagnostic()?;
-        let _ = std::fs::remove_file(&temp_path);
-
-        let trimmed = content.trim().to_string();
-
-        if self.require_changes {
-            let original = self.predefined_message.as_deref().unwrap_or("").trim();
-            if trimmed == original {
-                return Err(miette::miette!("No changes were made"));
-            }
+        let content = std::fs::read_to_string(path).into_diagnostic()?;
+        if self.draft_id.is_none() {
+            let _ = std::fs::remove_file(path);
         }
 
-        if let Some(ref validator) = self.validation {
-            run_validator(validator.as_ref(), &trimmed).map_err(|e| miette::miette!(e))?;
-        }
+        Ok(content)
+    }
 
-        let line_count = trimmed.lines().count();
+    fn show_result(&self, out: &mut impl Write, line_count: usize, tw: u16) -> miette::Result<()> {
         let line = format!(
             "{} {} {}",
             self.prompt_prefix.style(self.style.prompt_prefix),
@@ -264,9 +830,9 @@ impl Editor {
             .style(self.style.success)
             .bold(),
         );
-        crate::util::writeln_physical(&mut out, &line, tw)?;
+        crate::util::writeln_physical(out, &line, tw)?;
         out.flush().into_diagnostic()?;
 
-        Ok(trimmed)
+        Ok(())
     }
 }
@@ -0,0 +1,403 @@
+use {
+    crate::{
+        line::{self, KillRing, LineEdit},
+        option::{AskOption, Options},
+    },
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    owo_colors::{OwoColorize, Style},
+    std::{cmp::Reverse, rc::Rc},
+};
+
+/// what has been typed to narrow down the options of a (Multi)Select, and
+/// the options it matches
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Filter {
+    pub(crate) query: String,
+    cursor_pos: usize,
+    kill_ring: KillRing,
+    /// the matching options, best match first
+    pub(crate) matches: Matches,
+    /// the query `matches` are for, without whitespace
+    matched: Vec<char>,
+    /// whether the options were already matched somewhere else, so every one
+    /// is kept in the order it came in
+    pub(crate) unranked: bool,
+}
+
+/// an option that matches the filter
+///
+/// which characters matched is only worked out for the rows on screen, with
+/// [`Filter::positions`].
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Match {
+    /// where the option is in the prompt's options
+    pub(crate) index: usize,
+}
+
+/// how well an option matched: whether its name did, then how closely
+type Score = (bool, i64);
+
+/// the options a filter matches, best match first
+///
+/// while every option matches they aren't listed, and a ranking is shared
+/// rather than copied, so laying out a list doesn't go through them all.
+#[derive(Clone, Debug)]
+pub(crate) enum Matches {
+    /// every one of this many options, in order
+    All(usize),
+    Ranked(Rc<Vec<(Score, Match)>>),
+}
+
+impl Default for Matches {
+    fn default() -> Self {
+        Self::All(0)
+    }
+}
+
+impl Matches {
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Self::All(len) => *len,
+            Self::Ranked(ranked) => ranked.len(),
+        }
+    }
+
+    /// the match at `position`, which must be less than the length
+    pub(crate) fn get(&self, position: usize) -> Match {
+        match self {
+            Self::All(_) => Match { index: position },
+            Self::Ranked(ranked) => ranked[position].1,
+        }
+    }
+
+    /// where the option at `index` is among the matches, if it's one
+    pub(crate) fn position(&self, index: usize) -> Option<usize> {
+        match self {
+            Self::All(len) => (index < *len).then_some(index),
+            Self::Ranked(ranked) => ranked.iter().position(|(_, m)| m.index == index),
+        }
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = Match> + '_ {
+        (0..self.len()).map(|position| self.get(position))
+    }
+}
+
+impl Filter {
+    /// an empty filter, matching every option
+    pub(crate) fn new<T>(options: &(impl Options<T> + ?Sized)) -> Self {
+        let mut filter = Self::default();
+        filter.refresh(options);
+        filter
+    }
+
+    /// whether `key_event` is typed into the query instead of going to the
+    /// keymap
+    ///
+    /// space only is when `space` says so, and otherwise still goes to the
+    /// keymap, so it can toggle or submit.
+    pub(crate) fn takes(key_event: &KeyEvent, space: bool) -> bool {
+        matches!(key_event.code, KeyCode::Char(c) if c != ' ' || space)
+            && !key_event
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+    }
+
+    /// edit the query, returning whether it changed
+    pub(crate) fn edit<T>(
+        &mut self,
+        key_event: KeyEvent,
+        options: &(impl Options<T> + ?Sized),
+    ) -> bool {
+        let started = !self.query.is_empty();
+        let edit = line::edit_line(
+            key_event,
+            &mut self.query,
+            &mut self.cursor_pos,
+            &mut self.kill_ring,
+            // a query can't start with a space, but can have them in it for
+            // the name of a new option
+            |c| c != ' ' || started,
+        );
+
+        if edit == LineEdit::Changed {
+            // anything matching the longer query matched the shorter one, so
+            // only those options need looking at again
+            let query = strip(&self.query);
+            let candidates =
+                (!self.unranked && !self.matched.is_empty() && query.starts_with(&self.matched))
+                    .then(|| self.matches.iter().map(|m| m.index).collect());
+            self.rank(options, candidates);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(crate) fn clear<T>(&mut self, options: &(impl Options<T> + ?Sized)) {
+        self.query.clear();
+        self.cursor_pos = 0;
+        self.refresh(options);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.query.is_empty()
+    }
+
+    /// whether something has been typed that no option is named, ignoring
+    /// case, so it could be made into a new one
+    pub(crate) fn is_new<T>(&self, options: &(impl Options<T> + ?Sized)) -> bool {
+        // an option named what's typed matches it, so only the matches need
+        // looking at
+        !self.is_empty()
+            && !self
+                .matches
+                .iter()
+                .any(|m| eq_ignoring_case(&options[m.index].name, &self.query))
+    }
+
+    /// match the options against the query again
+    ///
+    /// options whose name matches come before ones where only the
+    /// description does, then the closer the match the better.
+    pub(crate) fn refresh<T>(&mut self, options: &(impl Options<T> + ?Sized)) {
+        self.rank(options, None);
+    }
+
+    /// match options added from `from` on against the query, keeping the
+    /// ones already matched as they are
+    pub(crate) fn append<T>(&mut self, options: &(impl Options<T> + ?Sized), from: usize) {
+        let Matches::Ranked(ref ranked) = self.matches else {
+            self.matches = Matches::All(options.len());
+            return;
+        };
+
+        let key = |&(score, m): &(Score, Match)| (Reverse(score), m.index);
+        let mut added = self
+            .score(options, from..options.len())
+            .into_iter()
+            .peekable();
+        let mut merged = Vec::with_capacity(ranked.len() + added.len());
+        for old in ranked.iter() {
+            while let Some(new) = added.next_if(|new| key(new) < key(old)) {
+                merged.push(new);
+            }
+            merged.push(*old);
+        }
+        merged.extend(added);
+        self.matches = Matches::Ranked(Rc::new(merged));
+    }
+
+    /// match the options at `candidates`, or all of them, against the query
+    fn rank<T>(&mut self, options: &(impl Options<T> + ?Sized), candidates: Option<Vec<usize>>) {
+        let query = strip(&self.query);
+
+        if self.unranked || query.is_empty() {
+            self.matches = Matches::All(options.len());
+            self.matched = query;
+            return;
+        }
+
+        self.matched = query;
+        let scored = match candidates {
+            Some(candidates) => self.score(options, candidates),
+            None => self.score(options, 0..options.len()),
+        };
+        self.matches = Matches::Ranked(Rc::new(scored));
+    }
+
+    /// score the options at `indices` against the matched query, keeping
+    /// the ones that match, best first
+    fn score<T>(
+        &self,
+        options: &(impl Options<T> + ?Sized),
+        indices: impl IntoIterator<Item = usize>,
+    ) -> Vec<(Score, Match)> {
+        let query = &self.matched;
+
+        // the buffers are shared by every option, so matching doesn't
+        // allocate per option
+        let mut chars = Vec::new();
+        let mut positions = Vec::new();
+        let mut scored: Vec<(Score, Match)> = indices
+            .into_iter()
+            .filter_map(|index| {
+                let option = &options[index];
+                if let Some(score) = fuzzy_score(&option.name, query, &mut chars, &mut positions) {
+                    return Some(((true, score), Match { index }));
+                }
+                fuzzy_score(&option.description, query, &mut chars, &mut positions)
+                    .map(|score| ((false, score), Match { index }))
+            })
+            .collect();
+
+        // equal matches stay in option order
+        scored.sort_unstable_by_key(|&(score, m)| (Reverse(score), m.index));
+        scored
+    }
+
+    /// which characters of `option`'s name and description to highlight
+    ///
+    /// the name is highlighted if it matches, otherwise the description is.
+    pub(crate) fn positions<T>(&self, option: &AskOption<T>) -> (Vec<usize>, Vec<usize>) {
+        let query = strip(&self.query);
+        if let Some((_, name)) = fuzzy_match(&option.name, &query) {
+            return (name, Vec::new());
+        }
+        if self.unranked {
+            return (Vec::new(), Vec::new());
+        }
+        let description = fuzzy_match(&option.description, &query).map_or(Vec::new(), |(_, d)| d);
+        (Vec::new(), description)
+    }
+}
+
+/// whether `a` and `b` are the same ignoring case, without allocating
+fn eq_ignoring_case(a: &str, b: &str) -> bool {
+    if a.is_ascii() && b.is_ascii() {
+        return a.eq_ignore_ascii_case(b);
+    }
+    a.chars()
+        .flat_map(char::to_lowercase)
+        .eq(b.chars().flat_map(char::to_lowercase))
+}
+
+/// `query` without whitespace, which is ignored when matching
+fn strip(query: &str) -> Vec<char> {
+    query.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+/// find `query` in `text` as a case-insensitive subsequence
+///
+/// returns a score and the char indices that matched. matches that are
+/// tight, run together, or start words score higher.
+pub(crate) fn fuzzy_match(text: &str, query: &[char]) -> Option<(i64, Vec<usize>)> {
+    let mut positions = Vec::with_capacity(query.len());
+    fuzzy_score(text, query, &mut Vec::new(), &mut positions).map(|score| (score, positions))
+}
+
+/// like [`fuzzy_match`], filling `positions` with the chars that matched,
+/// and reusing `chars` to hold the text when it isn't ASCII
+fn fuzzy_score(
+    text: &str,
+    query: &[char],
+    chars: &mut Vec<char>,
+    positions: &mut Vec<usize>,
+) -> Option<i64> {
+    positions.clear();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    // ASCII text is matched on its bytes, which saves decoding and copying
+    // it, and most option names are
+    if text.is_ascii() {
+        return score(text.as_bytes(), |&b| b as char, query, positions);
+    }
+
+    chars.clear();
+    chars.extend(text.chars());
+    score(chars, |&c| c, query, positions)
+}
+
+fn score<C>(
+    text: &[C],
+    char_at: impl Fn(&C) -> char,
+    query: &[char],
+    positions: &mut Vec<usize>,
+) -> Option<i64> {
+    let chars = |range: std::ops::RangeInclusive<usize>| text[range].iter().map(&char_at);
+    let same = |c: char, q: char| {
+        c == q
+            || if c.is_ascii() && q.is_ascii() {
+                c.eq_ignore_ascii_case(&q)
+            } else {
+                c.to_lowercase().eq(q.to_lowercase())
+            }
+    };
+
+    // the first place a match ends, then the latest start before it, gives
+    // the tightest window
+    let mut qi = 0;
+    let mut end = None;
+    for (i, c) in text.iter().map(&char_at).enumerate() {
+        if same(c, query[qi]) {
+            qi += 1;
+            if qi == query.len() {
+                end = Some(i);
+                break;
+            }
+        }
+    }
+    let end = end?;
+
+    let mut start = 0;
+    let mut qi = query.len();
+    for (i, c) in chars(0..=end).enumerate().rev() {
+        if same(c, query[qi - 1]) {
+            qi -= 1;
+            if qi == 0 {
+                start = i;
+                break;
+            }
+        }
+    }
+
+    for (i, c) in chars(start..=end).enumerate() {
+        if positions.len() < query.len() && same(c, query[positions.len()]) {
+            positions.push(start + i);
+        }
+    }
+
+    let mut score = 0;
+    for (n, &i) in positions.iter().enumerate() {
+        score += 16;
+        if n > 0 && positions[n - 1] + 1 == i {
+            score += 8;
+        }
+        let before = i.checked_sub(1).map(|i| char_at(&text[i]));
+        if is_word_start(before, char_at(&text[i])) {
+            score += 8;
+        }
+    }
+    score -= (end + 1 - start - positions.len()) as i64;
+
+    Some(score)
+}
+
+/// whether `c` starts a word, coming after `before`
+fn is_word_start(before: Option<char>, c: char) -> bool {
+    match before {
+        None => true,
+        Some(before) => !before.is_alphanumeric() || (before.is_lowercase() && c.is_uppercase()),
+    }
+}
+
+/// style `text`, with the chars at `positions` in `matched` instead
+pub(crate) fn highlight(text: &str, positions: &[usize], style: Style, matched: Style) -> String {
+    if positions.is_empty() {
+        return text.style(style).to_string();
+    }
+
+    let mut out = String::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+
+    for (i, c) in text.chars().enumerate() {
+        let is_match = positions.binary_search(&i).is_ok();
+        if is_match != run_matched && !run.is_empty() {
+            let run_style = if run_matched { matched } else { style };
+            out.push_str(&run.style(run_style).to_string());
+            run.clear();
+        }
+        run_matched = is_match;
+        run.push(c);
+    }
+
+    if !run.is_empty() {
+        let run_style = if run_matched { matched } else { style };
+        out.push_str(&run.style(run_style).to_string());
+    }
+
+    out
+}
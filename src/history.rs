@@ -0,0 +1,220 @@
+use {
+    crate::input::{Autocomplete, Replacement},
+    miette::IntoDiagnostic,
+    std::{
+        fs,
+        path::{Path, PathBuf},
+    },
+};
+
+/// previous answers to a prompt, kept in a file per key
+///
+/// files live in `$XDG_STATE_HOME/bearask/history` (or
+/// `~/.local/state/bearask/history`) unless [`History::in_dir`] says otherwise.
+/// entries are unique, and only the newest `max_entries` are kept.
+///
+/// a `History` is also an [`Autocomplete`] source that suggests matching
+/// entries, newest first.
+#[derive(Clone, Debug)]
+pub struct History {
+    key: String,
+    dir: PathBuf,
+    max_entries: usize,
+    entries: Vec<String>,
+}
+
+impl History {
+    /// load the history for `key` from the default state directory
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a name for the history that stays the same between runs
+    pub fn new(key: impl Into<String>) -> Self {
+        Self::in_dir(key, crate::util::state_dir().join("history"))
+    }
+
+    /// load the history for `key` from `dir`
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a name for the history that stays the same between runs
+    /// * `dir` - the directory to keep history files in
+    pub fn in_dir(key: impl Into<String>, dir: impl Into<PathBuf>) -> Self {
+        let mut history = Self {
+            key: key.into(),
+            dir: dir.into(),
+            max_entries: 500,
+            entries: Vec::new(),
+        };
+        history.entries = history.load();
+        history
+    }
+
+    /// set how many entries are kept, dropping the oldest ones
+    pub fn with_max_entries(mut self, max: usize) -> Self {
+        self.max_entries = max.max(1);
+        self.entries = self.load();
+        self
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// the file this history is kept in
+    pub fn path(&self) -> PathBuf {
+        self.dir
+            .join(format!("{}.history", crate::util::file_stem(&self.key)))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// the entries, oldest first
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// the entry `n` steps back from the newest one
+    pub fn recent(&self, n: usize) -> Option<&str> {
+        self.entries
+            .len()
+            .checked_sub(n + 1)
+            .map(|i| self.entries[i].as_str())
+    }
+
+    /// record `entry` as the newest one and write the history to disk
+    ///
+    /// blank entries are ignored and an existing copy of `entry` is moved to
+    /// the end. the file is re-read first, so prompts running at the same
+    /// time don't lose each other's entries.
+    pub fn push(&mut self, entry: impl Into<String>) -> miette::Result<()> {
+        let entry = entry.into();
+        if entry.trim().is_empty() {
+            return Ok(());
+        }
+
+        self.entries = self.load();
+        self.entries.retain(|e| *e != entry);
+        self.entries.push(entry);
+        let excess = self.entries.len().saturating_sub(self.max_entries);
+        self.entries.drain(..excess);
+
+        self.save()
+    }
+
+    /// forget every entry and remove the file
+    pub fn clear(&mut self) -> miette::Result<()> {
+        self.entries.clear();
+        match fs::remove_file(self.path()) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e).into_diagnostic(),
+            _ => Ok(()),
+        }
+    }
+
+    /// how far back the newest entry containing `query` is, skipping the
+    /// `from` newest entries
+    pub(crate) fn search(&self, query: &str, from: usize) -> Option<usize> {
+        let query = query.to_lowercase();
+        (from..self.entries.len()).find(|&n| {
+            self.recent(n)
+                .is_some_and(|e| e.to_lowercase().contains(&query))
+        })
+    }
+
+    fn load(&self) -> Vec<String> {
+        let Ok(content) = fs::read_to_string(self.path()) else {
+            return Vec::new();
+        };
+
+        let mut entries: Vec<String> = Vec::new();
+        for entry in content.lines().filter(|l| !l.is_empty()).map(unescape) {
+            entries.retain(|e| *e != entry);
+            entries.push(entry);
+        }
+
+        let excess = entries.len().saturating_sub(self.max_entries);
+        entries.drain(..excess);
+        entries
+    }
+
+    fn save(&self) -> miette::Result<()> {
+        fs::create_dir_all(&self.dir).into_diagnostic()?;
+
+        let mut content = String::new();
+        for entry in &self.entries {
+            content.push_str(&escape(entry));
+            content.push('\n');
+        }
+
+        let path = self.path();
+        let tmp = path.with_extension("history.tmp");
+        fs::write(&tmp, content).into_diagnostic()?;
+        fs::rename(&tmp, &path).into_diagnostic()
+    }
+}
+
+fn escape(entry: &str) -> String {
+    entry
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn unescape(line: &str) -> String {
+    let mut entry = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            entry.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => entry.push('\n'),
+            Some('r') => entry.push('\r'),
+            Some(other) => entry.push(other),
+            None => entry.push('\\'),
+        }
+    }
+    entry
+}
+
+impl Autocomplete for History {
+    fn get_suggestions(&mut self, input: &str) -> Result<Vec<String>, String> {
+        let input = input.to_lowercase();
+
+        Ok(self
+            .entries
+            .iter()
+            .rev()
+            .filter(|e| e.to_lowercase().starts_with(&input))
+            .cloned()
+            .collect())
+    }
+
+    fn get_completion(
+        &mut self,
+        input: &str,
+        highlighted_suggestion: Option<String>,
+    ) -> Result<Replacement, String> {
+        if let Some(suggestion) = highlighted_suggestion {
+            return Ok(Some(suggestion));
+        }
+
+        Ok(self.get_suggestions(input)?.into_iter().next())
+    }
+}
+
+impl From<&str> for History {
+    fn from(key: &str) -> Self {
+        Self::new(key)
+    }
+}
+
+impl From<String> for History {
+    fn from(key: String) -> Self {
+        Self::new(key)
+    }
+}
@@ -1,5 +1,9 @@
 use {
     crate::{
+        draft::DraftStore,
+        history::History,
+        keymap::{Action, KeyMap},
+        line::{self, KillRing, LineEdit, ViMode, ViState},
         style::TextInputStyle,
         util::CursorGuard,
         validation::{Validate, run_validator},
@@ -148,6 +152,24 @@ impl Autocomplete for FuzzyAutocomplete {
     }
 }
 
+/// where Up/Down and Ctrl-R have got to in the history
+#[derive(Clone, Default)]
+struct HistoryNav {
+    /// steps back from the newest entry, while browsing
+    index: Option<usize>,
+    /// what was typed before browsing started
+    stash: String,
+    search: Option<HistorySearch>,
+}
+
+#[derive(Clone, Default)]
+struct HistorySearch {
+    query: String,
+    found: Option<usize>,
+    failed: bool,
+    original: String,
+}
+
 #[derive(Clone)]
 pub struct TextInput {
     prompt: String,
@@ -163,6 +185,13 @@ pub struct TextInput {
     style: TextInputStyle,
     validation: Option<Box<dyn Validate<str>>>,
     autocomplete: Option<Box<dyn Autocomplete>>,
+    draft_id: Option<String>,
+    draft_store: DraftStore,
+    history: Option<History>,
+    history_nav: HistoryNav,
+    vim_mode: bool,
+    vi: ViState,
+    keymap: KeyMap,
     _cursor_guard: CursorGuard,
 }
 
@@ -183,6 +212,13 @@ impl TextInput {
             style: TextInputStyle::default(),
             validation: None,
             autocomplete: None,
+            draft_id: None,
+            draft_store: DraftStore::default(),
+            history: None,
+            history_nav: HistoryNav::default(),
+            vim_mode: false,
+            vi: ViState::default(),
+            keymap: KeyMap::new(),
             _cursor_guard,
         }
     }
@@ -232,6 +268,24 @@ impl TextInput {
         self
     }
 
+    /// edit with vi-style insert and normal modes, starting in insert mode
+    ///
+    /// Esc switches to normal mode, and cancels from there. in normal mode
+    /// j/k act like ↓↑.
+    pub fn with_vim_mode(mut self, enabled: bool) -> Self {
+        self.vim_mode = enabled;
+        self
+    }
+
+    /// set which keys do what
+    ///
+    /// typed characters always go into the input, so only keys with Ctrl or
+    /// Alt, or keys like Enter and Tab, can be bound.
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
     pub fn with_style(mut self, style: TextInputStyle) -> Self {
         self.style = style;
         self
@@ -247,6 +301,42 @@ impl TextInput {
         self
     }
 
+    /// save the input to the draft store under `id` as it is typed
+    ///
+    /// the draft is removed once an answer is submitted; otherwise the next
+    /// `ask` with the same id offers to restore it.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - a name for this prompt that stays the same between runs
+    pub fn with_draft(mut self, id: impl Into<String>) -> Self {
+        self.draft_id = Some(id.into());
+        self
+    }
+
+    /// set where drafts are kept, see [`TextInput::with_draft`]
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - the draft store to use
+    pub fn with_draft_store(mut self, store: DraftStore) -> Self {
+        self.draft_store = store;
+        self
+    }
+
+    /// remember submitted answers and let Up/Down and Ctrl-R bring them back
+    ///
+    /// Up/Down only walk the history when no suggestions are shown. to also
+    /// suggest previous answers, pass a [`History`] to `with_autocomplete`.
+    ///
+    /// # Arguments
+    ///
+    /// * `history` - a history key, or a [`History`] set up by hand
+    pub fn with_history(mut self, history: impl Into<History>) -> Self {
+        self.history = Some(history.into());
+        self
+    }
+
     pub fn prompt(&self) -> &str {
         &self.prompt
     }
@@ -268,7 +358,18 @@ impl TextInput {
 
     pub fn _ask_internal(&mut self) -> miette::Result<String> {
         let mut input = self.initial_value.clone().unwrap_or_default();
+        if let Some(ref id) = self.draft_id
+            && let Some(draft) = self
+                .draft_store
+                .offer_restore(id, "txt", &self.prompt, &input)?
+        {
+            input = draft;
+        }
+        let mut saved_draft = input.clone();
+        self.history_nav = HistoryNav::default();
         let mut cursor_pos = input.len();
+        self.vi = ViState::new(&input, cursor_pos);
+        let mut kill_ring = KillRing::default();
         let mut suggestions: Vec<String> = Vec::new();
         let mut selected_suggestion: Option<usize> = None;
         let mut suggestion_scroll_offset: usize = 0;
@@ -306,6 +407,7 @@ impl TextInput {
                     key_event,
                     &mut input,
                     &mut cursor_pos,
+                    &mut kill_ring,
                     &mut suggestions,
                     &mut selected_suggestion,
                     &mut suggestion_scroll_offset,
@@ -314,6 +416,14 @@ impl TextInput {
                     Ok(Some(answer)) => {
                         terminal::disable_raw_mode().into_diagnostic()?;
 
+                        if let Some(ref id) = self.draft_id {
+                            self.draft_store.remove(id, "txt");
+                        }
+
+                        if let Some(ref mut history) = self.history {
+                            let _ = history.push(answer.clone());
+                        }
+
                         buf.clear();
                         if last_input_line_position > 0 {
                             queue!(buf, cursor::MoveUp(last_input_line_position as u16))
@@ -321,12 +431,22 @@ impl TextInput {
                         }
                         queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
                         queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+                        if self.vim_mode {
+                            queue!(buf, cursor::SetCursorStyle::BlinkingBar).into_diagnostic()?;
+                        }
                         self.show_result(&mut buf, &answer)?;
                         out.write_all(&buf).into_diagnostic()?;
                         out.flush().into_diagnostic()?;
                         return Ok(answer);
                     }
                     Ok(None) => {
+                        if let Some(ref id) = self.draft_id
+                            && input != saved_draft
+                        {
+                            let _ = self.draft_store.save(id, "txt", &input);
+                            saved_draft = input.clone();
+                        }
+
                         buf.clear();
                         if last_input_line_position > 0 {
                             queue!(buf, cursor::MoveUp(last_input_line_position as u16))
@@ -357,6 +477,9 @@ impl TextInput {
                         }
                         queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
                         queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+                        if self.vim_mode {
+                            queue!(buf, cursor::SetCursorStyle::BlinkingBar).into_diagnostic()?;
+                        }
                         self.show_error(&mut buf, &e)?;
                         out.write_all(&buf).into_diagnostic()?;
                         out.flush().into_diagnostic()?;
@@ -367,12 +490,30 @@ impl TextInput {
         }
     }
 
+    const ACTIONS: &[Action] = &[
+        Action::Submit,
+        Action::SearchHistory,
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::Complete,
+        Action::Cancel,
+    ];
+
+    /// the actions that mean something while searching history
+    const SEARCH_ACTIONS: &[Action] = &[
+        Action::SearchHistory,
+        Action::Submit,
+        Action::Complete,
+        Action::Cancel,
+    ];
+
     #[allow(clippy::too_many_arguments)]
     fn handle_key(
         &mut self,
         key_event: KeyEvent,
         input: &mut String,
         cursor_pos: &mut usize,
+        kill_ring: &mut KillRing,
         suggestions: &mut Vec<String>,
         selected_suggestion: &mut Option<usize>,
         suggestion_scroll_offset: &mut usize,
@@ -385,8 +526,56 @@ impl TextInput {
             std::process::exit(130);
         }
 
-        match key_event.code {
-            KeyCode::Enter => {
+        if self.history_nav.search.is_some() {
+            let handled = self.handle_history_search(key_event, input, cursor_pos);
+
+            if self.history_nav.search.is_none()
+                && let Some(ref mut ac) = self.autocomplete
+            {
+                *suggestions = ac.get_suggestions(input).unwrap_or_default();
+            }
+
+            if handled {
+                return Ok(None);
+            }
+        }
+
+        let mut key_event = key_event;
+        if self.vim_mode {
+            if self.vi.mode == ViMode::Normal && key_event.modifiers.is_empty() {
+                key_event.code = match key_event.code {
+                    KeyCode::Char('k') => KeyCode::Up,
+                    KeyCode::Char('j') => KeyCode::Down,
+                    code => code,
+                };
+            }
+
+            match line::edit_line_vi(
+                key_event,
+                input,
+                cursor_pos,
+                kill_ring,
+                &mut self.vi,
+                |_| true,
+            ) {
+                LineEdit::Changed => {
+                    self.input_changed(
+                        input,
+                        suggestions,
+                        selected_suggestion,
+                        suggestion_scroll_offset,
+                    );
+                    return Ok(None);
+                }
+                LineEdit::Moved => return Ok(None),
+                LineEdit::Unhandled => {}
+            }
+        }
+
+        let suggestions_shown = self.show_suggestions && !suggestions.is_empty();
+
+        match self.keymap.text_action(&key_event, Self::ACTIONS) {
+            Some(Action::Submit) => {
                 if let Some(idx) = *selected_suggestion
                     && let Some(suggestion) = suggestions.get(idx)
                 {
@@ -402,47 +591,33 @@ impl TextInput {
 
                 self.validate_and_return(&final_input)
             }
-            KeyCode::Char(c) => {
-                input.insert(*cursor_pos, c);
-                *cursor_pos += 1;
+            Some(Action::SearchHistory) if self.history.is_some() => {
+                self.history_nav.index = None;
+                self.history_nav.search = Some(HistorySearch {
+                    original: input.clone(),
+                    ..Default::default()
+                });
+                suggestions.clear();
                 *selected_suggestion = None;
                 *suggestion_scroll_offset = 0;
-
-                if let Some(ref mut ac) = self.autocomplete {
-                    *suggestions = ac.get_suggestions(input).unwrap_or_default();
-                }
-
                 Ok(None)
             }
-            KeyCode::Backspace if *cursor_pos > 0 => {
-                *cursor_pos -= 1;
-                input.remove(*cursor_pos);
-                *selected_suggestion = None;
-                *suggestion_scroll_offset = 0;
+            Some(Action::MoveUp) if self.history.is_some() && !suggestions_shown => {
+                self.browse_history(true, input, cursor_pos);
+                suggestions.clear();
+                Ok(None)
+            }
+            Some(Action::MoveDown) if self.history_nav.index.is_some() && !suggestions_shown => {
+                self.browse_history(false, input, cursor_pos);
 
-                if let Some(ref mut ac) = self.autocomplete {
+                if self.history_nav.index.is_none()
+                    && let Some(ref mut ac) = self.autocomplete
+                {
                     *suggestions = ac.get_suggestions(input).unwrap_or_default();
                 }
-
-                Ok(None)
-            }
-            KeyCode::Left if *cursor_pos > 0 => {
-                *cursor_pos -= 1;
-                Ok(None)
-            }
-            KeyCode::Right if *cursor_pos < input.len() => {
-                *cursor_pos += 1;
-                Ok(None)
-            }
-            KeyCode::Home => {
-                *cursor_pos = 0;
                 Ok(None)
             }
-            KeyCode::End => {
-                *cursor_pos = input.len();
-                Ok(None)
-            }
-            KeyCode::Up if !suggestions.is_empty() => {
+            Some(Action::MoveUp) if !suggestions.is_empty() => {
                 *selected_suggestion = Some(match *selected_suggestion {
                     None => suggestions.len() - 1,
                     Some(0) => suggestions.len() - 1,
@@ -459,7 +634,7 @@ impl TextInput {
                 }
                 Ok(None)
             }
-            KeyCode::Down if !suggestions.is_empty() => {
+            Some(Action::MoveDown) if !suggestions.is_empty() => {
                 *selected_suggestion = Some(match *selected_suggestion {
                     None => 0,
                     Some(n) if n >= suggestions.len() - 1 => 0,
@@ -476,7 +651,7 @@ impl TextInput {
                 }
                 Ok(None)
             }
-            KeyCode::Tab if self.autocomplete.is_some() => {
+            Some(Action::Complete) if self.autocomplete.is_some() => {
                 if let Some(ref mut ac) = self.autocomplete {
                     let highlighted =
                         selected_suggestion.and_then(|idx| suggestions.get(idx).cloned());
@@ -492,11 +667,144 @@ impl TextInput {
                 }
                 Ok(None)
             }
-            KeyCode::Esc if self.allow_escape => Err("Cancelled".into()),
-            _ => Ok(None),
+            Some(Action::Cancel) if self.allow_escape => Err("Cancelled".into()),
+            _ if self.vim_mode => Ok(None),
+            _ => {
+                if line::edit_line(key_event, input, cursor_pos, kill_ring, |_| true)
+                    == LineEdit::Changed
+                {
+                    self.input_changed(
+                        input,
+                        suggestions,
+                        selected_suggestion,
+                        suggestion_scroll_offset,
+                    );
+                }
+
+                Ok(None)
+            }
         }
     }
 
+    /// forget the history position and refresh suggestions after an edit
+    fn input_changed(
+        &mut self,
+        input: &str,
+        suggestions: &mut Vec<String>,
+        selected_suggestion: &mut Option<usize>,
+        suggestion_scroll_offset: &mut usize,
+    ) {
+        self.history_nav.index = None;
+        *selected_suggestion = None;
+        *suggestion_scroll_offset = 0;
+
+        if let Some(ref mut ac) = self.autocomplete {
+            *suggestions = ac.get_suggestions(input).unwrap_or_default();
+        }
+    }
+
+    /// step one entry older or newer through the history, keeping what was
+    /// typed so stepping past the newest entry brings it back
+    fn browse_history(&mut self, older: bool, input: &mut String, cursor_pos: &mut usize) {
+        let Some(ref history) = self.history else {
+            return;
+        };
+        let nav = &mut self.history_nav;
+
+        let next = match (nav.index, older) {
+            (None, true) => Some(0),
+            (None, false) => return,
+            (Some(n), true) => Some(n + 1),
+            (Some(0), false) => None,
+            (Some(n), false) => Some(n - 1),
+        };
+
+        match next {
+            Some(n) => {
+                let Some(entry) = history.recent(n) else {
+                    return;
+                };
+                if nav.index.is_none() {
+                    nav.stash = input.clone();
+                }
+                nav.index = Some(n);
+                *input = entry.to_string();
+            }
+            None => {
+                nav.index = None;
+                *input = std::mem::take(&mut nav.stash);
+            }
+        }
+
+        *cursor_pos = input.len();
+    }
+
+    /// handle a key during Ctrl-R search, returning false once the search is
+    /// over and the key should be handled as usual
+    fn handle_history_search(
+        &mut self,
+        key_event: KeyEvent,
+        input: &mut String,
+        cursor_pos: &mut usize,
+    ) -> bool {
+        let (Some(history), Some(mut search)) =
+            (self.history.as_ref(), self.history_nav.search.take())
+        else {
+            return false;
+        };
+        let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+
+        let from = match self.keymap.text_action(&key_event, Self::SEARCH_ACTIONS) {
+            Some(Action::SearchHistory) => search.found.map_or(0, |n| n + 1),
+            Some(Action::Cancel) => {
+                *input = search.original;
+                *cursor_pos = input.len();
+                return true;
+            }
+            Some(Action::Submit) => return false,
+            Some(Action::Complete) => {
+                *cursor_pos = input.len();
+                return true;
+            }
+            _ => match key_event.code {
+                KeyCode::Char('g') if ctrl => {
+                    *input = search.original;
+                    *cursor_pos = input.len();
+                    return true;
+                }
+                KeyCode::Left | KeyCode::Right | KeyCode::Home | KeyCode::End => {
+                    *cursor_pos = input.len();
+                    return true;
+                }
+                KeyCode::Backspace => {
+                    search.query.pop();
+                    0
+                }
+                KeyCode::Char(c) if !ctrl => {
+                    search.query.push(c);
+                    search.found.unwrap_or(0)
+                }
+                _ => {
+                    self.history_nav.search = Some(search);
+                    return true;
+                }
+            },
+        };
+
+        match history.search(&search.query, from) {
+            Some(n) => {
+                search.found = Some(n);
+                search.failed = false;
+                *input = history.recent(n).unwrap_or_default().to_string();
+                *cursor_pos = input.len();
+            }
+            None => search.failed = true,
+        }
+
+        self.history_nav.search = Some(search);
+        true
+    }
+
     pub fn validate_and_return(&self, value: &str) -> Result<Option<String>, String> {
         if let Some(ref validator) = self.validation {
             run_validator(validator.as_ref(), value)?;
@@ -518,19 +826,29 @@ impl TextInput {
         let mut line_count = 0;
         let mut prompt_prefix_for_cursor = 0;
 
+        let mode = if !self.vim_mode {
+            String::new()
+        } else if self.vi.mode == ViMode::Normal {
+            format!(" {}", "[normal]".style(self.style.selected))
+        } else {
+            format!(" {}", "[insert]".style(self.style.hint))
+        };
+
         if self.inline {
             let line = format!(
-                "{} {} ",
+                "{} {}{} ",
                 self.prompt_prefix.style(self.style.prompt_prefix),
                 self.prompt.style(self.style.prompt),
+                mode,
             );
             prompt_prefix_for_cursor = crate::util::visible_width(&line);
             write!(out, "{}", line).into_diagnostic()?;
         } else {
             let line = format!(
-                "{} {}",
+                "{} {}{}",
                 self.prompt_prefix.style(self.style.prompt_prefix),
                 self.prompt.style(self.style.prompt),
+                mode,
             );
             line_count += crate::util::writeln_physical(out, &line, tw)?;
         }
@@ -566,6 +884,21 @@ impl TextInput {
 
         let input_line_position = line_count - 1;
 
+        if let Some(ref search) = self.history_nav.search {
+            let status = if search.failed {
+                format!(" {}", "(no match)".style(self.style.error_hint))
+            } else {
+                String::new()
+            };
+            let line = format!(
+                "  {} {}{}",
+                "history search:".style(self.style.hint),
+                search.query.style(self.style.input),
+                status
+            );
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
         if self.show_suggestions && !suggestions.is_empty() {
             let end_offset =
                 (suggestion_scroll_offset + self.suggestion_page_size).min(suggestions.len());
@@ -640,15 +973,53 @@ impl TextInput {
         }
 
         let mut hints = vec![];
-        if self.autocomplete.is_some() {
-            hints.push("Tab to autocomplete");
-        }
-        if !suggestions.is_empty() {
-            hints.push("↑↓ to navigate");
-        }
-        hints.push("Enter to submit");
-        if self.allow_escape {
-            hints.push("Esc to cancel");
+        if self.history_nav.search.is_some() {
+            let keymap = &self.keymap;
+            for (action, what) in [
+                (Action::SearchHistory, "for older"),
+                (Action::Submit, "to submit"),
+                (Action::Complete, "to edit"),
+                (Action::Cancel, "to stop searching"),
+            ] {
+                if let Some(key) = keymap.hint_among(action, Self::SEARCH_ACTIONS) {
+                    hints.push(format!("{} {}", key, what));
+                }
+            }
+        } else {
+            let keymap = &self.keymap;
+            if self.autocomplete.is_some()
+                && let Some(key) = keymap.hint(Action::Complete)
+            {
+                hints.push(format!("{} to autocomplete", key));
+            }
+            let arrows = keymap.hint_pair(Action::MoveUp, Action::MoveDown);
+            if !suggestions.is_empty()
+                && let Some(ref keys) = arrows
+            {
+                hints.push(format!("{} to navigate", keys));
+            }
+            if let Some(ref history) = self.history
+                && !history.entries().is_empty()
+            {
+                if (!self.show_suggestions || suggestions.is_empty())
+                    && let Some(ref keys) = arrows
+                {
+                    hints.push(format!("{} for history", keys));
+                }
+                if let Some(key) = keymap.hint(Action::SearchHistory) {
+                    hints.push(format!("{} to search history", key));
+                }
+            }
+            if let Some(key) = keymap.hint(Action::Submit) {
+                hints.push(format!("{} to submit", key));
+            }
+            if self.vim_mode && self.vi.mode == ViMode::Insert {
+                hints.push("Esc for normal mode".into());
+            } else if self.allow_escape
+                && let Some(key) = keymap.hint(Action::Cancel)
+            {
+                hints.push(format!("{} to cancel", key));
+            }
         }
 
         if !hints.is_empty() {
@@ -665,6 +1036,13 @@ impl TextInput {
         let cursor_column =
             prompt_prefix_for_cursor + 2 + crate::util::visible_width(text_before_cursor);
         queue!(out, cursor::MoveToColumn(cursor_column as u16)).into_diagnostic()?;
+        if self.vim_mode {
+            let shape = match self.vi.mode {
+                ViMode::Insert => cursor::SetCursorStyle::BlinkingBar,
+                ViMode::Normal => cursor::SetCursorStyle::SteadyBlock,
+            };
+            queue!(out, shape).into_diagnostic()?;
+        }
         queue!(out, cursor::Show).into_diagnostic()?;
 
         Ok((line_count, input_line_position))
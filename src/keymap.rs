@@ -0,0 +1,473 @@
+use {
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    std::{borrow::Cow, fmt, str::FromStr},
+};
+
+/// something a key can do in a prompt
+///
+/// prompts ignore actions they have no use for, so one [`KeyMap`] can be
+/// shared by all of them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    PageUp,
+    PageDown,
+    MoveToFirst,
+    MoveToLast,
+    /// select or deselect the option under the cursor
+    Toggle,
+    SelectAll,
+    DeselectAll,
+    InvertSelection,
+    /// select every option the filter matches, or deselect them if they
+    /// all are already
+    ToggleVisible,
+    /// scroll the preview pane of a `Select` or `MultiSelect`
+    PreviewUp,
+    PreviewDown,
+    /// sort a [`crate::TableSelect`] by the next column, or unsort it after
+    /// the last
+    SortColumn,
+    /// flip the order a [`crate::TableSelect`] is sorted in
+    ReverseSort,
+    /// open the node under the cursor in a [`crate::TreeSelect`]
+    Expand,
+    /// close the node under the cursor in a [`crate::TreeSelect`]
+    Collapse,
+    /// pick up or put down the item under the cursor in [`crate::Sort`],
+    /// or the selected option under it in
+    /// [`crate::MultiSelect::ask_ordered`]
+    Grab,
+    /// move the item under the cursor up past the one above it, in the
+    /// selected side of a [`crate::TransferList`] or among the picks of
+    /// [`crate::MultiSelect::ask_ordered`]
+    MoveItemUp,
+    MoveItemDown,
+    /// mark the item under the cursor in [`crate::Sort`], so grabbing picks
+    /// up every marked item together
+    Mark,
+    /// put the items of a [`crate::Sort`] in order by name
+    SortByName,
+    /// turn the order of a [`crate::Sort`] around
+    Reverse,
+    /// take back the last move in [`crate::Sort`], or the last answer in
+    /// [`crate::Rank`]
+    Undo,
+    Yes,
+    No,
+    /// flip between yes and no in an interactive [`crate::Confirm`]
+    SwitchChoice,
+    /// pick the item on the left in [`crate::Rank`]
+    ChooseLeft,
+    /// pick the item on the right in [`crate::Rank`]
+    ChooseRight,
+    /// accept the highlighted autocomplete suggestion
+    Complete,
+    SearchHistory,
+    Increment,
+    Decrement,
+    /// show or hide a masked password
+    Reveal,
+    /// switch between unified and side-by-side in [`crate::DiffConfirm`]
+    SwitchView,
+    /// open the text in the editor
+    Edit,
+    Submit,
+    Cancel,
+}
+
+/// a key, with the modifiers that have to be held for it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // terminals disagree on whether shift is reported for `G` or
+        // shift-tab, so the key alone decides
+        let code = match code {
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            code => code,
+        };
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self { code, modifiers }
+    }
+
+    pub fn ctrl(c: char) -> Self {
+        Self::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    pub fn alt(c: char) -> Self {
+        Self::new(KeyCode::Char(c), KeyModifiers::ALT)
+    }
+
+    /// shift with a key that isn't a character, since those are told apart
+    /// by the key alone
+    pub fn shift(code: KeyCode) -> Self {
+        Self::new(code, KeyModifiers::SHIFT)
+    }
+
+    pub fn matches(&self, key_event: &KeyEvent) -> bool {
+        *self == Self::from(*key_event)
+    }
+
+    fn is_arrow(&self) -> bool {
+        self.modifiers.is_empty()
+            && matches!(
+                self.code,
+                KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right
+            )
+    }
+}
+
+impl From<KeyCode> for KeyBinding {
+    fn from(code: KeyCode) -> Self {
+        Self::new(code, KeyModifiers::NONE)
+    }
+}
+
+impl From<char> for KeyBinding {
+    fn from(c: char) -> Self {
+        Self::new(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+}
+
+impl From<KeyEvent> for KeyBinding {
+    fn from(key_event: KeyEvent) -> Self {
+        Self::new(key_event.code, key_event.modifiers)
+    }
+}
+
+/// parses keys like `enter`, `ctrl-n`, `alt+b`, `pgdn`, `f2` or `G`
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+
+        while let Some((prefix, key)) = rest.split_once(['-', '+'])
+            && !key.is_empty()
+        {
+            modifiers |= match prefix.to_lowercase().as_str() {
+                "ctrl" | "control" | "c" => KeyModifiers::CONTROL,
+                "alt" | "meta" | "m" => KeyModifiers::ALT,
+                "shift" | "s" => KeyModifiers::SHIFT,
+                _ => return Err(format!("Unknown modifier \"{}\" in \"{}\"", prefix, s)),
+            };
+            rest = key;
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n) => KeyCode::F(n),
+                    None => return Err(format!("Unknown key \"{}\"", s)),
+                },
+            },
+        };
+
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift-")?;
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) if !self.modifiers.is_empty() => write!(f, "{}", c.to_uppercase()),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::BackTab => write!(f, "Shift-Tab"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::Delete => write!(f, "Del"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// which keys do which [`Action`]s
+///
+/// a key can be bound to several actions, and each prompt takes the first
+/// one it knows. that's how Space toggles in `MultiSelect` but submits in
+/// `Select`. prompts that take typed text never look up plain characters,
+/// so binding `q` won't stop anyone typing it.
+///
+/// ```no_run
+/// use bearask::{Action, KeyBinding, KeyMap, Select};
+///
+/// let keymap = KeyMap::new()
+///     .without_binding(' ', Action::Submit)
+///     .with_binding(KeyBinding::ctrl('n'), Action::MoveDown)
+///     .with_binding(KeyBinding::ctrl('p'), Action::MoveUp);
+///
+/// let select = Select::<()>::new("Pick one").with_keymap(keymap);
+/// ```
+#[derive(Clone, Debug)]
+pub struct KeyMap {
+    bindings: Vec<(KeyBinding, Action)>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KeyMap {
+    /// the standard bindings, with arrow keys for navigation
+    pub fn new() -> Self {
+        use Action::*;
+
+        let bindings: Vec<(KeyBinding, Action)> = vec![
+            (KeyCode::Up.into(), MoveUp),
+            (KeyCode::Down.into(), MoveDown),
+            (KeyCode::PageUp.into(), PageUp),
+            (KeyCode::PageDown.into(), PageDown),
+            (KeyCode::Home.into(), MoveToFirst),
+            (KeyCode::End.into(), MoveToLast),
+            (' '.into(), Toggle),
+            (' '.into(), Grab),
+            // space toggles in `MultiSelect`, so picks are grabbed with this
+            (KeyBinding::ctrl('g'), Grab),
+            (KeyCode::Right.into(), SelectAll),
+            (KeyCode::Left.into(), DeselectAll),
+            // the arrows move the caret when there's a filter to type in
+            (KeyBinding::alt('a'), SelectAll),
+            (KeyBinding::alt('n'), DeselectAll),
+            (KeyBinding::ctrl('t'), ToggleVisible),
+            (KeyBinding::shift(KeyCode::Up), PreviewUp),
+            (KeyBinding::shift(KeyCode::Down), PreviewDown),
+            (KeyBinding::shift(KeyCode::Up), MoveItemUp),
+            (KeyBinding::shift(KeyCode::Down), MoveItemDown),
+            (KeyBinding::new(KeyCode::Up, KeyModifiers::ALT), MoveItemUp),
+            (
+                KeyBinding::new(KeyCode::Down, KeyModifiers::ALT),
+                MoveItemDown,
+            ),
+            (KeyBinding::alt('m'), Mark),
+            (KeyBinding::alt('a'), SortByName),
+            (KeyBinding::alt('r'), Reverse),
+            (KeyBinding::ctrl('z'), Undo),
+            (KeyCode::Right.into(), Expand),
+            (KeyCode::Left.into(), Collapse),
+            (KeyCode::Left.into(), SwitchChoice),
+            (KeyCode::Right.into(), SwitchChoice),
+            (KeyCode::Tab.into(), SwitchChoice),
+            (KeyCode::Left.into(), ChooseLeft),
+            (KeyCode::Right.into(), ChooseRight),
+            ('1'.into(), ChooseLeft),
+            ('2'.into(), ChooseRight),
+            (KeyCode::Tab.into(), Complete),
+            (KeyCode::Tab.into(), SwitchView),
+            (KeyCode::Tab.into(), SortColumn),
+            (KeyCode::BackTab.into(), ReverseSort),
+            ('v'.into(), SwitchView),
+            ('e'.into(), Edit),
+            ('y'.into(), Yes),
+            ('Y'.into(), Yes),
+            ('t'.into(), Yes),
+            ('T'.into(), Yes),
+            ('1'.into(), Yes),
+            ('n'.into(), No),
+            ('N'.into(), No),
+            ('f'.into(), No),
+            ('F'.into(), No),
+            ('0'.into(), No),
+            (KeyBinding::ctrl('r'), SearchHistory),
+            (KeyBinding::ctrl('r'), Reveal),
+            (KeyCode::Up.into(), Increment),
+            (KeyCode::Down.into(), Decrement),
+            (KeyCode::Enter.into(), Submit),
+            (' '.into(), Submit),
+            (KeyCode::Esc.into(), Cancel),
+        ];
+
+        Self { bindings }
+    }
+
+    /// no bindings at all, to build a keymap up from scratch
+    pub fn empty() -> Self {
+        Self {
+            bindings: Vec::new(),
+        }
+    }
+
+    /// the standard bindings plus `j k g G` to move, `h l` to collapse and
+    /// expand, `a d i` to select all, deselect all and invert, and in
+    /// [`crate::Sort`] `m` to mark, `a` to sort by name, `r` to reverse and
+    /// `u` to undo
+    pub fn vim() -> Self {
+        use Action::*;
+
+        Self::new()
+            .with_binding('u', Undo)
+            .with_binding('r', Reverse)
+            .with_binding('a', SortByName)
+            .with_binding('m', Mark)
+            .with_binding('h', Collapse)
+            .with_binding('l', Expand)
+            .with_binding('i', InvertSelection)
+            .with_binding('d', DeselectAll)
+            .with_binding('a', SelectAll)
+            .with_binding('G', MoveToLast)
+            .with_binding('g', MoveToFirst)
+            .with_binding('j', MoveDown)
+            .with_binding('k', MoveUp)
+    }
+
+    /// the keymap a prompt should use: its own if it has one, otherwise
+    /// the standard or vim bindings
+    pub(crate) fn resolve(custom: Option<&KeyMap>, vim_mode: bool) -> Cow<'_, KeyMap> {
+        match custom {
+            Some(keymap) => Cow::Borrowed(keymap),
+            None if vim_mode => Cow::Owned(Self::vim()),
+            None => Cow::Owned(Self::new()),
+        }
+    }
+
+    /// bind `key` to `action`, ahead of any other actions it already has
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a [`KeyBinding`], [`KeyCode`] or `char`
+    /// * `action` - what the key should do
+    pub fn with_binding(mut self, key: impl Into<KeyBinding>, action: Action) -> Self {
+        self.bindings.insert(0, (key.into(), action));
+        self
+    }
+
+    /// stop `key` from doing `action`, leaving its other actions alone
+    pub fn without_binding(mut self, key: impl Into<KeyBinding>, action: Action) -> Self {
+        let key = key.into();
+        self.bindings.retain(|&(k, a)| k != key || a != action);
+        self
+    }
+
+    /// remove every binding for `key`
+    pub fn without_key(mut self, key: impl Into<KeyBinding>) -> Self {
+        let key = key.into();
+        self.bindings.retain(|&(k, _)| k != key);
+        self
+    }
+
+    /// remove every binding for `action`
+    pub fn without_action(mut self, action: Action) -> Self {
+        self.bindings.retain(|&(_, a)| a != action);
+        self
+    }
+
+    /// every binding, in the order they're looked up
+    pub fn bindings(&self) -> &[(KeyBinding, Action)] {
+        &self.bindings
+    }
+
+    /// the keys bound to `action`
+    pub fn keys(&self, action: Action) -> impl Iterator<Item = KeyBinding> + '_ {
+        self.bindings
+            .iter()
+            .filter(move |&&(_, a)| a == action)
+            .map(|&(k, _)| k)
+    }
+
+    /// the first action out of `supported` that `key_event` is bound to
+    pub(crate) fn action(&self, key_event: &KeyEvent, supported: &[Action]) -> Option<Action> {
+        let key = KeyBinding::from(*key_event);
+        self.bindings
+            .iter()
+            .find(|&&(k, a)| k == key && supported.contains(&a))
+            .map(|&(_, a)| a)
+    }
+
+    /// like [`KeyMap::action`], but plain characters are left for typing
+    pub(crate) fn text_action(&self, key_event: &KeyEvent, supported: &[Action]) -> Option<Action> {
+        let typed = matches!(key_event.code, KeyCode::Char(_))
+            && !key_event
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+
+        if typed {
+            None
+        } else {
+            self.action(key_event, supported)
+        }
+    }
+
+    /// the first key bound to `action`, for hints
+    pub(crate) fn hint(&self, action: Action) -> Option<String> {
+        self.keys(action).next().map(|k| k.to_string())
+    }
+
+    /// the first key that does `action` when only `supported` actions are
+    /// looked up, for hints where some of its keys go to other actions
+    pub(crate) fn hint_among(&self, action: Action, supported: &[Action]) -> Option<String> {
+        self.keys(action)
+            .find(|&key| {
+                self.bindings
+                    .iter()
+                    .find(|&&(k, a)| k == key && supported.contains(&a))
+                    .is_some_and(|&(_, a)| a == action)
+            })
+            .map(|k| k.to_string())
+    }
+
+    /// the keys for a pair of opposite actions, like `↑↓` or `k/j or ↑↓`
+    pub(crate) fn hint_pair(&self, first: Action, second: Action) -> Option<String> {
+        let pairs: Vec<String> = self
+            .keys(first)
+            .zip(self.keys(second))
+            .map(|(a, b)| {
+                if a.is_arrow() && b.is_arrow() {
+                    format!("{}{}", a, b)
+                } else {
+                    format!("{}/{}", a, b)
+                }
+            })
+            .collect();
+
+        if pairs.is_empty() {
+            None
+        } else {
+            Some(pairs.join(" or "))
+        }
+    }
+}
@@ -1,27 +1,59 @@
 #![allow(clippy::type_complexity)]
 pub mod confirm;
+pub mod diffconfirm;
+pub mod draft;
 pub mod editor;
+pub mod history;
 pub mod input;
+pub mod keymap;
 pub mod multiselect;
 pub mod number;
 pub mod option;
 pub mod password;
+pub mod preview;
+pub mod rank;
 pub mod select;
 pub mod sort;
+pub mod source;
 pub mod style;
+pub mod tableselect;
+pub mod transferlist;
+pub mod treeselect;
 pub mod validation;
 
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod __bench;
+
+pub(crate) mod bitset;
+pub(crate) mod filter;
+pub(crate) mod line;
+pub(crate) mod list;
+pub(crate) mod stream;
 pub(crate) mod util;
 
 pub use {
     confirm::Confirm,
-    editor::Editor,
+    diffconfirm::{DiffConfirm, DiffView},
+    draft::DraftStore,
+    editor::{Editor, ItemEdit, ItemEdits},
+    history::History,
     input::{Autocomplete, FuzzyAutocomplete, Replacement, SimpleAutocomplete, TextInput},
+    keymap::{Action, KeyBinding, KeyMap},
     multiselect::MultiSelect,
     number::{Number, NumericType},
-    option::AskOption,
+    option::{AskOption, IndexedOptions},
     password::Password,
+    preview::PreviewPosition,
+    rank::Rank,
     select::Select,
-    sort::Sort,
+    sort::{Sort, Sorted},
+    source::{MemorySource, OptionSource},
+    tableselect::{TableRow, TableSelect},
+    transferlist::TransferList,
+    treeselect::{TreeNode, TreeSelect},
     validation::{CustomUserError, ErrorMessage, Validate, Validation},
 };
+
+#[cfg(feature = "serde")]
+pub use editor::EditorFormat;
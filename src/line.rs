@@ -0,0 +1,587 @@
+use {
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    std::ops::Range,
+};
+
+/// what a key did to the line being edited
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum LineEdit {
+    /// the text changed
+    Changed,
+    /// the key was used, but the text didn't change
+    Moved,
+    /// the key isn't a line editing key
+    Unhandled,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Yank {
+    start: usize,
+    len: usize,
+    index: usize,
+}
+
+/// text removed with the kill keys, newest last, for Ctrl-Y to bring back
+#[derive(Clone, Debug, Default)]
+pub(crate) struct KillRing {
+    entries: Vec<String>,
+    /// the last key was a kill, so the next kill adds to the same entry
+    appending: bool,
+    /// the last key was a yank, so Alt-Y can swap it for an older entry
+    last_yank: Option<Yank>,
+}
+
+impl KillRing {
+    const MAX_ENTRIES: usize = 16;
+
+    fn kill(&mut self, text: String, backward: bool, appending: bool) {
+        match self.entries.last_mut() {
+            Some(last) if appending => {
+                if backward {
+                    last.insert_str(0, &text);
+                } else {
+                    last.push_str(&text);
+                }
+            }
+            _ => {
+                self.entries.push(text);
+                if self.entries.len() > Self::MAX_ENTRIES {
+                    self.entries.remove(0);
+                }
+            }
+        }
+        self.appending = true;
+    }
+}
+
+/// apply a readline-style editing key to `input`
+///
+/// `cursor_pos` is a byte offset into `input`. only characters that pass
+/// `accepts` are inserted, so prompts can restrict what gets typed.
+///
+/// | keys                               | action                                  |
+/// |------------------------------------|-----------------------------------------|
+/// | Ctrl-A, Home / Ctrl-E, End         | line start / end                        |
+/// | Ctrl-B, ← / Ctrl-F, →              | one character back / forward            |
+/// | Alt-B, Ctrl-← / Alt-F, Ctrl-→      | one word back / forward                 |
+/// | Backspace, Ctrl-H / Delete, Ctrl-D | delete a character back / forward       |
+/// | Ctrl-W / Alt-Backspace             | kill to the previous space / word start |
+/// | Alt-D                              | kill to the end of the word             |
+/// | Ctrl-U / Ctrl-K                    | kill to line start / end                |
+/// | Ctrl-Y                             | yank the last kill                      |
+/// | Alt-Y                              | swap the yank for an older kill         |
+pub(crate) fn edit_line(
+    key_event: KeyEvent,
+    input: &mut String,
+    cursor_pos: &mut usize,
+    kill_ring: &mut KillRing,
+    accepts: impl Fn(char) -> bool,
+) -> LineEdit {
+    let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+    let alt = key_event.modifiers.contains(KeyModifiers::ALT);
+    let appending = std::mem::take(&mut kill_ring.appending);
+    let last_yank = kill_ring.last_yank.take();
+    let pos = (*cursor_pos).min(input.len());
+
+    match key_event.code {
+        KeyCode::Home => move_to(cursor_pos, 0),
+        KeyCode::Char('a') if ctrl => move_to(cursor_pos, 0),
+        KeyCode::End => move_to(cursor_pos, input.len()),
+        KeyCode::Char('e') if ctrl => move_to(cursor_pos, input.len()),
+        KeyCode::Left if ctrl || alt => move_to(cursor_pos, word_start(input, pos, is_word)),
+        KeyCode::Char('b') if alt => move_to(cursor_pos, word_start(input, pos, is_word)),
+        KeyCode::Right if ctrl || alt => move_to(cursor_pos, word_end(input, pos, is_word)),
+        KeyCode::Char('f') if alt => move_to(cursor_pos, word_end(input, pos, is_word)),
+        KeyCode::Left => move_to(cursor_pos, prev_char(input, pos)),
+        KeyCode::Char('b') if ctrl => move_to(cursor_pos, prev_char(input, pos)),
+        KeyCode::Right => move_to(cursor_pos, next_char(input, pos)),
+        KeyCode::Char('f') if ctrl => move_to(cursor_pos, next_char(input, pos)),
+        KeyCode::Backspace if alt => {
+            let start = word_start(input, pos, is_word);
+            kill(input, cursor_pos, start..pos, kill_ring, appending)
+        }
+        KeyCode::Char('w') if ctrl => {
+            let start = word_start(input, pos, |c| !c.is_whitespace());
+            kill(input, cursor_pos, start..pos, kill_ring, appending)
+        }
+        KeyCode::Char('d') if alt => {
+            let end = word_end(input, pos, is_word);
+            kill(input, cursor_pos, pos..end, kill_ring, appending)
+        }
+        KeyCode::Char('u') if ctrl => kill(input, cursor_pos, 0..pos, kill_ring, appending),
+        KeyCode::Char('k') if ctrl => {
+            kill(input, cursor_pos, pos..input.len(), kill_ring, appending)
+        }
+        KeyCode::Backspace => delete(input, cursor_pos, prev_char(input, pos)..pos),
+        KeyCode::Char('h') if ctrl => delete(input, cursor_pos, prev_char(input, pos)..pos),
+        KeyCode::Delete => delete(input, cursor_pos, pos..next_char(input, pos)),
+        KeyCode::Char('d') if ctrl => delete(input, cursor_pos, pos..next_char(input, pos)),
+        KeyCode::Char('y') if ctrl => {
+            let Some(index) = kill_ring.entries.len().checked_sub(1) else {
+                return LineEdit::Unhandled;
+            };
+            yank(input, cursor_pos, pos, kill_ring, index)
+        }
+        KeyCode::Char('y') if alt => {
+            let Some(last) = last_yank else {
+                return LineEdit::Unhandled;
+            };
+            let count = kill_ring.entries.len();
+            input.replace_range(last.start..last.start + last.len, "");
+            yank(
+                input,
+                cursor_pos,
+                last.start,
+                kill_ring,
+                (last.index + count - 1) % count,
+            )
+        }
+        KeyCode::Char(c) if !ctrl && !alt && accepts(c) => {
+            input.insert(pos, c);
+            *cursor_pos = pos + c.len_utf8();
+            LineEdit::Changed
+        }
+        _ => LineEdit::Unhandled,
+    }
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric()
+}
+
+fn move_to(cursor_pos: &mut usize, pos: usize) -> LineEdit {
+    *cursor_pos = pos;
+    LineEdit::Moved
+}
+
+fn delete(input: &mut String, cursor_pos: &mut usize, range: Range<usize>) -> LineEdit {
+    if range.is_empty() {
+        return LineEdit::Unhandled;
+    }
+
+    input.replace_range(range.clone(), "");
+    *cursor_pos = range.start;
+    LineEdit::Changed
+}
+
+fn kill(
+    input: &mut String,
+    cursor_pos: &mut usize,
+    range: Range<usize>,
+    kill_ring: &mut KillRing,
+    appending: bool,
+) -> LineEdit {
+    if range.is_empty() {
+        kill_ring.appending = appending;
+        return LineEdit::Unhandled;
+    }
+
+    let backward = range.end == *cursor_pos;
+    let text: String = input.drain(range.clone()).collect();
+    kill_ring.kill(text, backward, appending);
+    *cursor_pos = range.start;
+    LineEdit::Changed
+}
+
+fn yank(
+    input: &mut String,
+    cursor_pos: &mut usize,
+    pos: usize,
+    kill_ring: &mut KillRing,
+    index: usize,
+) -> LineEdit {
+    let text = &kill_ring.entries[index];
+    input.insert_str(pos, text);
+    *cursor_pos = pos + text.len();
+    kill_ring.last_yank = Some(Yank {
+        start: pos,
+        len: text.len(),
+        index,
+    });
+    LineEdit::Changed
+}
+
+fn prev_char(input: &str, pos: usize) -> usize {
+    input[..pos]
+        .chars()
+        .next_back()
+        .map_or(pos, |c| pos - c.len_utf8())
+}
+
+fn next_char(input: &str, pos: usize) -> usize {
+    input[pos..]
+        .chars()
+        .next()
+        .map_or(pos, |c| pos + c.len_utf8())
+}
+
+/// the start of the word before `pos`, skipping any separators first
+fn word_start(input: &str, pos: usize, is_word: impl Fn(char) -> bool) -> usize {
+    let mut start = pos;
+    let mut in_word = false;
+
+    for (i, c) in input[..pos].char_indices().rev() {
+        if is_word(c) {
+            in_word = true;
+        } else if in_word {
+            break;
+        }
+        start = i;
+    }
+
+    start
+}
+
+/// the end of the word after `pos`, skipping any separators first
+fn word_end(input: &str, pos: usize, is_word: impl Fn(char) -> bool) -> usize {
+    let mut end = pos;
+    let mut in_word = false;
+
+    for (i, c) in input[pos..].char_indices() {
+        if is_word(c) {
+            in_word = true;
+        } else if in_word {
+            break;
+        }
+        end = pos + i + c.len_utf8();
+    }
+
+    end
+}
+
+/// which mode vi-style editing is in
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) enum ViMode {
+    #[default]
+    Insert,
+    Normal,
+}
+
+/// vi-style editing state that lasts between keys
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ViState {
+    pub(crate) mode: ViMode,
+    /// an operator (`d` or `c`) waiting for its motion
+    pending: Option<char>,
+    /// the text and cursor before each change, newest last
+    undo: Vec<(String, usize)>,
+}
+
+impl ViState {
+    const MAX_UNDO: usize = 100;
+
+    /// start editing `input` in insert mode, so what's typed before the
+    /// first Esc can be undone
+    pub(crate) fn new(input: &str, cursor_pos: usize) -> Self {
+        let mut vi = Self::default();
+        vi.insert(input, cursor_pos);
+        vi
+    }
+
+    fn snapshot(&mut self, input: &str, cursor_pos: usize) {
+        self.undo.push((input.to_string(), cursor_pos));
+        if self.undo.len() > Self::MAX_UNDO {
+            self.undo.remove(0);
+        }
+    }
+
+    fn insert(&mut self, input: &str, cursor_pos: usize) {
+        self.snapshot(input, cursor_pos);
+        self.mode = ViMode::Insert;
+    }
+}
+
+/// apply a vi-style editing key to `input`
+///
+/// insert mode edits like [`edit_line`] until Esc switches to normal mode,
+/// which has the usual motions (`h l w b e 0 ^ $`), operators (`d c` with a
+/// motion, `dd cc D C`), `x X s`, `i a I A` to go back to insert mode,
+/// `p P` to put the last deletion and `u` to undo. every change made in one
+/// visit to insert mode is undone together.
+///
+/// Enter, Tab and arrow keys other than ←→ are left for the prompt, as is
+/// Esc in normal mode when no operator is pending.
+pub(crate) fn edit_line_vi(
+    key_event: KeyEvent,
+    input: &mut String,
+    cursor_pos: &mut usize,
+    kill_ring: &mut KillRing,
+    vi: &mut ViState,
+    accepts: impl Fn(char) -> bool,
+) -> LineEdit {
+    *cursor_pos = (*cursor_pos).min(input.len());
+
+    if vi.mode == ViMode::Insert {
+        if key_event.code == KeyCode::Esc {
+            vi.mode = ViMode::Normal;
+            if vi.undo.last().is_some_and(|(text, _)| text == input) {
+                vi.undo.pop();
+            }
+            *cursor_pos = prev_char(input, *cursor_pos);
+            return LineEdit::Moved;
+        }
+
+        return edit_line(key_event, input, cursor_pos, kill_ring, accepts);
+    }
+
+    let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+    let alt = key_event.modifiers.contains(KeyModifiers::ALT);
+    if ctrl || alt {
+        return LineEdit::Unhandled;
+    }
+
+    let pos = *cursor_pos;
+    let key = match key_event.code {
+        KeyCode::Char(c) => c,
+        KeyCode::Left | KeyCode::Backspace => 'h',
+        KeyCode::Right => 'l',
+        KeyCode::Home => '0',
+        KeyCode::End => '$',
+        KeyCode::Delete => 'x',
+        KeyCode::Esc if vi.pending.is_some() => {
+            vi.pending = None;
+            return LineEdit::Moved;
+        }
+        _ => return LineEdit::Unhandled,
+    };
+
+    if let Some(op) = vi.pending.take() {
+        let range = if key == op {
+            Some(0..input.len())
+        } else if op == 'c' && key == 'w' && input[pos..].starts_with(|c: char| !c.is_whitespace())
+        {
+            // like vim, `cw` on a word only changes to the end of it
+            Some(pos..next_char(input, vi_word_end(input, pos)))
+        } else {
+            vi_motion(input, pos, key).map(|(target, inclusive)| {
+                if target < pos {
+                    target..pos
+                } else if inclusive {
+                    pos..next_char(input, target)
+                } else {
+                    pos..target
+                }
+            })
+        };
+
+        let Some(range) = range else {
+            return LineEdit::Moved;
+        };
+
+        vi.snapshot(input, pos);
+        if !range.is_empty() {
+            let text: String = input.drain(range.clone()).collect();
+            kill_ring.kill(text, false, false);
+        }
+        *cursor_pos = range.start;
+
+        if op == 'c' {
+            vi.mode = ViMode::Insert;
+        } else {
+            *cursor_pos = clamp_normal(input, *cursor_pos);
+        }
+        return LineEdit::Changed;
+    }
+
+    if let Some((target, _)) = vi_motion(input, pos, key) {
+        *cursor_pos = clamp_normal(input, target);
+        return LineEdit::Moved;
+    }
+
+    match key {
+        'd' | 'c' => {
+            vi.pending = Some(key);
+            LineEdit::Moved
+        }
+        'i' => {
+            vi.insert(input, pos);
+            LineEdit::Moved
+        }
+        'a' => {
+            vi.insert(input, pos);
+            *cursor_pos = next_char(input, pos);
+            LineEdit::Moved
+        }
+        'I' => {
+            vi.insert(input, pos);
+            *cursor_pos = first_non_blank(input);
+            LineEdit::Moved
+        }
+        'A' => {
+            vi.insert(input, pos);
+            *cursor_pos = input.len();
+            LineEdit::Moved
+        }
+        'x' | 'X' | 's' | 'D' | 'C' | 'S' => {
+            let range = match key {
+                'x' | 's' => pos..next_char(input, pos),
+                'X' => prev_char(input, pos)..pos,
+                'D' | 'C' => pos..input.len(),
+                _ => 0..input.len(),
+            };
+
+            if range.is_empty() && !matches!(key, 's' | 'C' | 'S') {
+                return LineEdit::Moved;
+            }
+
+            vi.snapshot(input, pos);
+            let text: String = input.drain(range.clone()).collect();
+            if !text.is_empty() {
+                kill_ring.kill(text, key == 'X', false);
+            }
+            *cursor_pos = range.start;
+
+            if matches!(key, 's' | 'C' | 'S') {
+                vi.mode = ViMode::Insert;
+            } else {
+                *cursor_pos = clamp_normal(input, *cursor_pos);
+            }
+            LineEdit::Changed
+        }
+        'p' | 'P' => {
+            let Some(text) = kill_ring.entries.last().cloned() else {
+                return LineEdit::Moved;
+            };
+
+            vi.snapshot(input, pos);
+            let at = if key == 'p' {
+                next_char(input, pos)
+            } else {
+                pos
+            };
+            input.insert_str(at, &text);
+            *cursor_pos = prev_char(input, at + text.len());
+            LineEdit::Changed
+        }
+        'u' => {
+            let Some((text, undo_pos)) = vi.undo.pop() else {
+                return LineEdit::Moved;
+            };
+
+            *input = text;
+            *cursor_pos = clamp_normal(input, undo_pos);
+            LineEdit::Changed
+        }
+        _ => LineEdit::Moved,
+    }
+}
+
+/// where a normal-mode motion lands, and whether an operator should take
+/// the character it lands on too
+fn vi_motion(input: &str, pos: usize, key: char) -> Option<(usize, bool)> {
+    match key {
+        'h' => Some((prev_char(input, pos), false)),
+        'l' | ' ' => Some((next_char(input, pos), false)),
+        'w' => Some((vi_word_forward(input, pos), false)),
+        'b' => Some((vi_word_back(input, pos), false)),
+        'e' => Some((vi_word_end(input, pos), true)),
+        '0' => Some((0, false)),
+        '^' => Some((first_non_blank(input), false)),
+        '$' => Some((prev_char(input, input.len()), true)),
+        _ => None,
+    }
+}
+
+/// normal mode keeps the cursor on a character rather than after the last one
+fn clamp_normal(input: &str, pos: usize) -> usize {
+    if pos >= input.len() {
+        prev_char(input, input.len())
+    } else {
+        pos
+    }
+}
+
+fn first_non_blank(input: &str) -> usize {
+    input
+        .char_indices()
+        .find(|(_, c)| !c.is_whitespace())
+        .map_or(input.len(), |(i, _)| i)
+}
+
+/// vim's word classes: blanks, word characters, and other punctuation
+fn char_class(c: char) -> u8 {
+    if c.is_whitespace() {
+        0
+    } else if c.is_alphanumeric() || c == '_' {
+        1
+    } else {
+        2
+    }
+}
+
+fn vi_word_forward(input: &str, pos: usize) -> usize {
+    let mut chars = input[pos..]
+        .char_indices()
+        .map(|(i, c)| (pos + i, c))
+        .peekable();
+
+    if let Some(&(_, first)) = chars.peek() {
+        let class = char_class(first);
+        if class != 0 {
+            while let Some(&(_, c)) = chars.peek()
+                && char_class(c) == class
+            {
+                chars.next();
+            }
+        }
+    }
+
+    while let Some(&(_, c)) = chars.peek()
+        && char_class(c) == 0
+    {
+        chars.next();
+    }
+
+    chars.peek().map_or(input.len(), |&(i, _)| i)
+}
+
+fn vi_word_back(input: &str, pos: usize) -> usize {
+    let mut chars = input[..pos].char_indices().rev().peekable();
+    let mut start = pos;
+
+    while let Some(&(i, c)) = chars.peek()
+        && char_class(c) == 0
+    {
+        start = i;
+        chars.next();
+    }
+
+    if let Some(&(_, first)) = chars.peek() {
+        let class = char_class(first);
+        while let Some(&(i, c)) = chars.peek()
+            && char_class(c) == class
+        {
+            start = i;
+            chars.next();
+        }
+    }
+
+    start
+}
+
+fn vi_word_end(input: &str, pos: usize) -> usize {
+    let mut chars = input[pos..]
+        .char_indices()
+        .map(|(i, c)| (pos + i, c))
+        .skip(1)
+        .peekable();
+    let mut end = pos;
+
+    while let Some(&(_, c)) = chars.peek()
+        && char_class(c) == 0
+    {
+        chars.next();
+    }
+
+    if let Some(&(_, first)) = chars.peek() {
+        let class = char_class(first);
+        while let Some(&(i, c)) = chars.peek()
+            && char_class(c) == class
+        {
+            end = i;
+            chars.next();
+        }
+    }
+
+    end
+}
@@ -0,0 +1,425 @@
+use {
+    crate::{
+        bitset::BitSet,
+        filter::{Filter, Match, Matches},
+    },
+    std::ops::Range,
+};
+
+/// something shown between the options of a (Multi)Select
+#[derive(Clone, Debug)]
+pub(crate) enum Heading {
+    Group(String),
+    Separator,
+}
+
+/// a line in the option list of a (Multi)Select
+#[derive(Clone, Debug)]
+pub(crate) enum Row {
+    /// a group title, and the indices of the options under it
+    Group(String, Range<usize>),
+    Separator,
+    Option(Match),
+    /// the entry for making a new option out of what's been typed
+    Create,
+}
+
+/// the rows a (Multi)Select shows, and where the cursor and scroll are
+///
+/// rows are only made when they're asked for, so a list over a lot of
+/// options costs no more to lay out than one over a few. `cursor` and
+/// `scroll` count rows, so headings take up room on a page like options do.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct List {
+    pub(crate) cursor: usize,
+    pub(crate) scroll: usize,
+    /// options shown ahead of the rest, in order
+    first: Vec<usize>,
+    /// what the filter matched, some of which may be in `first`
+    matches: Matches,
+    /// where in `matches` the options in `first` are, in order, so the rest
+    /// can leave them out
+    skipped: Vec<usize>,
+    /// headings, each with the row it's at among the rest
+    headings: Vec<(usize, Row)>,
+    /// whether the last row is for making a new option
+    create: bool,
+}
+
+impl List {
+    /// lay out the options the filter matches
+    ///
+    /// headings are only shown while nothing has been typed, since matches
+    /// are ordered by how well they match rather than by group.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - the filter the options went through
+    /// * `headings` - headings and the option index each one goes before
+    /// * `len` - how many options there are
+    pub(crate) fn new(filter: &Filter, headings: &[(usize, Heading)], len: usize) -> Self {
+        Self::with_first(filter, headings, len, &[])
+    }
+
+    /// lay out the options like [`List::new`], but with the ones in `first`
+    /// ahead of the rest, in that order and under a separator
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - the filter the options went through
+    /// * `headings` - headings and the option index each one goes before
+    /// * `len` - how many options there are
+    /// * `first` - the options to put at the top, if the filter matches them
+    pub(crate) fn with_first(
+        filter: &Filter,
+        headings: &[(usize, Heading)],
+        len: usize,
+        first: &[usize],
+    ) -> Self {
+        let matches = filter.matches.clone();
+        let (first, skipped) = match matches {
+            _ if first.is_empty() => (Vec::new(), Vec::new()),
+            Matches::All(all) => {
+                let first: Vec<usize> = first.iter().copied().filter(|&i| i < all).collect();
+                let mut skipped = first.clone();
+                skipped.sort_unstable();
+                (first, skipped)
+            }
+            Matches::Ranked(_) => {
+                let firsts: BitSet = first.iter().copied().collect();
+                let mut matched = BitSet::default();
+                let mut skipped = Vec::new();
+                for (position, m) in matches.iter().enumerate() {
+                    if firsts.contains(m.index) {
+                        matched.insert(m.index);
+                        skipped.push(position);
+                    }
+                }
+                let first = first.iter().copied().filter(|&i| matched.contains(i));
+                (first.collect(), skipped)
+            }
+        };
+
+        let mut list = Self {
+            first,
+            matches,
+            skipped,
+            ..Default::default()
+        };
+        if !filter.is_empty() {
+            return list;
+        }
+
+        // a group runs up to the next heading, and goes before the first of
+        // the rest at or after where it starts. while nothing's typed every
+        // option matches in order, so the ones before it are the ones with a
+        // smaller index that aren't in `first`
+        let ends = headings
+            .iter()
+            .skip(1)
+            .map(|&(at, _)| at)
+            .chain(std::iter::once(len));
+        for (n, (&(at, ref heading), end)) in headings.iter().zip(ends).enumerate() {
+            let at_most = at.min(list.matches.len());
+            let before = at_most - list.skipped.partition_point(|&s| s < at_most);
+            list.headings
+                .push((before + n, heading_row(heading, at..end)));
+        }
+
+        list
+    }
+
+    /// add the row for making a new option at the end
+    pub(crate) fn offer_create(&mut self) {
+        self.create = true;
+    }
+
+    /// how many of the matches aren't in `first`
+    fn rest_options(&self) -> usize {
+        self.matches.len() - self.skipped.len()
+    }
+
+    /// whether there's a separator between `first` and the rest
+    fn separated(&self) -> bool {
+        !self.first.is_empty() && self.rest_options() + self.headings.len() > 0
+    }
+
+    /// the row the rest start at
+    fn rest_start(&self) -> usize {
+        self.first.len() + usize::from(self.separated())
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.rest_start() + self.rest_options() + self.headings.len() + usize::from(self.create)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// the row at `row`, made when it's asked for
+    pub(crate) fn row(&self, row: usize) -> Option<Row> {
+        if let Some(&index) = self.first.get(row) {
+            return Some(Row::Option(Match { index }));
+        }
+        if self.separated() && row == self.first.len() {
+            return Some(Row::Separator);
+        }
+
+        let rest = row - self.rest_start();
+        let heading = self.headings.partition_point(|&(at, _)| at < rest);
+        match self.headings.get(heading) {
+            Some((at, row)) if *at == rest => Some(row.clone()),
+            _ if rest - heading < self.rest_options() => {
+                Some(Row::Option(self.rest(rest - heading)))
+            }
+            _ if self.create && rest - heading == self.rest_options() => Some(Row::Create),
+            _ => None,
+        }
+    }
+
+    /// the rows at `rows` that are there, each with where it is
+    pub(crate) fn rows(&self, rows: Range<usize>) -> impl Iterator<Item = (usize, Row)> + '_ {
+        rows.map_while(|row| Some((row, self.row(row)?)))
+    }
+
+    /// the `n`th of the rest of the options, past the ones in `first`
+    fn rest(&self, n: usize) -> Match {
+        let skipped = partition(self.skipped.len(), |k| self.skipped[k] - k <= n);
+        self.matches.get(n + skipped)
+    }
+
+    /// the `n`th option shown, counting from 0
+    pub(crate) fn option(&self, n: usize) -> Option<usize> {
+        match n.checked_sub(self.first.len()) {
+            None => Some(self.first[n]),
+            Some(n) if n < self.rest_options() => Some(self.rest(n).index),
+            Some(_) => None,
+        }
+    }
+
+    /// the row the option at `index` is on, if it's shown
+    fn row_of(&self, index: usize) -> Option<usize> {
+        if let Some(row) = self.first.iter().position(|&i| i == index) {
+            return Some(row);
+        }
+
+        let position = self.matches.position(index)?;
+        if self.skipped.binary_search(&position).is_ok() {
+            return None;
+        }
+        let n = position - self.skipped.partition_point(|&s| s < position);
+        let headings = partition(self.headings.len(), |h| self.headings[h].0 - h <= n);
+        Some(self.rest_start() + n + headings)
+    }
+
+    /// whether the row at `row` is there and `wanted` picks it out
+    fn is(&self, row: usize, wanted: impl Fn(&Row) -> bool) -> bool {
+        self.row(row).is_some_and(|row| wanted(&row))
+    }
+
+    /// swap the rows at `a` and `b`, if they're both among `first`,
+    /// returning whether they were
+    pub(crate) fn swap(&mut self, a: usize, b: usize) -> bool {
+        let swapped = a < self.first.len() && b < self.first.len();
+        if swapped {
+            self.first.swap(a, b);
+        }
+        swapped
+    }
+
+    /// the option under the cursor, if the cursor is on one
+    pub(crate) fn current(&self) -> Option<Match> {
+        match self.row(self.cursor) {
+            Some(Row::Option(m)) => Some(m),
+            _ => None,
+        }
+    }
+
+    /// put the cursor on the option at `index`, or on the first row it can
+    /// go to if that option isn't shown
+    pub(crate) fn focus(
+        &mut self,
+        index: Option<usize>,
+        page_size: usize,
+        focusable: impl Fn(&Row) -> bool,
+    ) {
+        let row = index.and_then(|index| self.row_of(index));
+
+        self.scroll = 0;
+        match row {
+            Some(row) if self.is(row, &focusable) => self.cursor = row,
+            _ => self.first(&focusable),
+        }
+        self.scroll_to_cursor(page_size);
+    }
+
+    /// put the cursor on the row for making a new option, if there is one
+    pub(crate) fn focus_create(&mut self, page_size: usize) {
+        if self.create {
+            self.cursor = self.len() - 1;
+            self.scroll_to_cursor(page_size);
+        }
+    }
+
+    /// put the cursor back where it was in `before`, on the same option and
+    /// at the same height on screen, after the rows have been laid out again
+    pub(crate) fn keep_place(
+        &mut self,
+        before: &List,
+        page_size: usize,
+        focusable: impl Fn(&Row) -> bool,
+    ) {
+        let row = match before.current() {
+            Some(current) => self.row_of(current.index),
+            None => Some(before.cursor),
+        };
+
+        if let Some(row) = row
+            && self.is(row, &focusable)
+        {
+            self.cursor = row;
+            self.scroll = row.saturating_sub(before.cursor.saturating_sub(before.scroll));
+            self.scroll_to_cursor(page_size);
+        }
+    }
+
+    /// move to the previous row the cursor can go to, wrapping to the end
+    pub(crate) fn up(&mut self, page_size: usize, focusable: impl Fn(&Row) -> bool) {
+        match (0..self.cursor).rev().find(|&i| self.is(i, &focusable)) {
+            Some(i) => self.cursor = i,
+            None => self.last(&focusable),
+        }
+        self.scroll_to_cursor(page_size);
+    }
+
+    /// move to the next row the cursor can go to, wrapping to the start
+    pub(crate) fn down(&mut self, page_size: usize, focusable: impl Fn(&Row) -> bool) {
+        match (self.cursor + 1..self.len()).find(|&i| self.is(i, &focusable)) {
+            Some(i) => self.cursor = i,
+            None => {
+                self.first(&focusable);
+                self.scroll = 0;
+            }
+        }
+        self.scroll_to_cursor(page_size);
+    }
+
+    /// move a page up, stopping at the first row the cursor can go to
+    pub(crate) fn page_up(&mut self, page_size: usize, focusable: impl Fn(&Row) -> bool) {
+        let target = self.cursor.saturating_sub(page_size);
+        if let Some(i) = (target..self.cursor)
+            .find(|&i| self.is(i, &focusable))
+            .or_else(|| (0..target).rev().find(|&i| self.is(i, &focusable)))
+        {
+            self.cursor = i;
+        }
+        self.scroll = self.scroll.saturating_sub(page_size);
+        self.scroll_to_cursor(page_size);
+    }
+
+    /// move a page down, stopping at the last row the cursor can go to
+    pub(crate) fn page_down(&mut self, page_size: usize, focusable: impl Fn(&Row) -> bool) {
+        let len = self.len();
+        let target = (self.cursor + page_size).min(len.saturating_sub(1));
+        if let Some(i) = (self.cursor + 1..=target)
+            .rev()
+            .find(|&i| self.is(i, &focusable))
+            .or_else(|| (target + 1..len).find(|&i| self.is(i, &focusable)))
+        {
+            self.cursor = i;
+        }
+        self.scroll_to_cursor(page_size);
+    }
+
+    /// move to the next row after the cursor that `wanted` picks out,
+    /// wrapping past the end, returning whether there was one
+    pub(crate) fn next_where(&mut self, page_size: usize, wanted: impl Fn(&Row) -> bool) -> bool {
+        let len = self.len();
+        match (1..=len)
+            .map(|step| (self.cursor + step) % len)
+            .find(|&i| self.is(i, &wanted))
+        {
+            Some(i) => {
+                self.cursor = i;
+                self.scroll_to_cursor(page_size);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub(crate) fn first(&mut self, focusable: impl Fn(&Row) -> bool) {
+        self.cursor = (0..self.len())
+            .find(|&i| self.is(i, &focusable))
+            .unwrap_or(0);
+        self.scroll = 0;
+    }
+
+    pub(crate) fn last(&mut self, focusable: impl Fn(&Row) -> bool) {
+        self.cursor = (0..self.len())
+            .rev()
+            .find(|&i| self.is(i, &focusable))
+            .unwrap_or(0);
+    }
+
+    /// scroll just far enough for the cursor to be on screen, along with the
+    /// title of its group when that fits
+    pub(crate) fn scroll_to_cursor(&mut self, page_size: usize) {
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+            if self.scroll > 0 && matches!(self.row(self.scroll - 1), Some(Row::Group(..))) {
+                self.scroll -= 1;
+            }
+        } else if self.cursor >= self.scroll + page_size {
+            self.scroll = self.cursor + 1 - page_size;
+        }
+    }
+
+    /// the rows on screen
+    pub(crate) fn visible(&self, page_size: usize) -> Range<usize> {
+        let end = (self.scroll + page_size).min(self.len());
+        self.scroll.min(end)..end
+    }
+
+    /// how many of the rows in `rows` are options
+    pub(crate) fn count_options(&self, rows: Range<usize>) -> usize {
+        rows.len() - (self.others_before(rows.end) - self.others_before(rows.start))
+    }
+
+    /// how many of the rows before `row` aren't options
+    fn others_before(&self, row: usize) -> usize {
+        let start = self.rest_start();
+        let separator = self.separated() && self.first.len() < row;
+        let headings = self.headings.partition_point(|&(at, _)| start + at < row);
+        let create = self.create && self.len() - 1 < row;
+        usize::from(separator) + headings + usize::from(create)
+    }
+}
+
+/// how many of `0..len` come before the first one `holds` is false for, when
+/// it holds for all of those before that and none after
+fn partition(len: usize, holds: impl Fn(usize) -> bool) -> usize {
+    let (mut low, mut high) = (0, len);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if holds(mid) {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
+
+fn heading_row(heading: &Heading, options: Range<usize>) -> Row {
+    match heading {
+        Heading::Group(title) => Row::Group(title.clone(), options),
+        Heading::Separator => Row::Separator,
+    }
+}
+
+/// the line drawn for a [`Row::Separator`]
+pub(crate) fn rule(tw: u16) -> String {
+    "─".repeat((tw as usize).saturating_sub(4).min(40))
+}
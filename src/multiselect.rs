@@ -1,6 +1,12 @@
 use {
     crate::{
-        option::AskOption,
+        bitset::BitSet,
+        filter::{self, Filter},
+        keymap::{Action, KeyBinding, KeyMap},
+        list::{self, Heading, List, Row},
+        option::{AskOption, IndexedOptions, OptionList},
+        preview::{Pane, PreviewPosition},
+        stream::{self, Stream, TICK},
         style::MultiSelectStyle,
         util::CursorGuard,
         validation::{Validate, run_validator},
@@ -14,8 +20,11 @@ use {
     miette::IntoDiagnostic,
     owo_colors::OwoColorize,
     std::{
-        collections::HashSet,
+        borrow::Cow,
+        collections::HashMap,
         io::{Write, stdout},
+        process::Command,
+        sync::{Arc, mpsc::Receiver},
     },
 };
 
@@ -23,8 +32,11 @@ use {
 #[allow(clippy::type_complexity)]
 pub struct MultiSelect<T: Clone> {
     prompt: String,
-    options: Vec<AskOption<T>>,
-    default_selections: HashSet<usize>,
+    options: OptionList<T>,
+    headings: Vec<(usize, Heading)>,
+    default_selections: BitSet,
+    /// whether every option that can be picked starts selected
+    all_selected: bool,
     page_size: usize,
     prompt_prefix: String,
     help_message: Option<String>,
@@ -32,8 +44,15 @@ pub struct MultiSelect<T: Clone> {
     show_descriptions: bool,
     allow_escape: bool,
     vim_mode: bool,
+    keymap: Option<KeyMap>,
+    filter: bool,
     min_selections: Option<usize>,
     max_selections: Option<usize>,
+    preview: Option<Arc<dyn Fn(&AskOption<T>) -> String>>,
+    preview_position: PreviewPosition,
+    create: Option<Arc<dyn Fn(&str) -> T>>,
+    create_validation: Option<Box<dyn Validate<str>>>,
+    stream: Option<Stream<T>>,
     style: MultiSelectStyle,
     validation: Option<Box<dyn Validate<[usize]>>>,
     _cursor_guard: CursorGuard,
@@ -44,8 +63,10 @@ impl<T: Clone> MultiSelect<T> {
         let _cursor_guard = CursorGuard::new().expect("Failed to initialize cursor guard");
         Self {
             prompt: prompt.into(),
-            options: Vec::new(),
-            default_selections: HashSet::new(),
+            options: OptionList::default(),
+            headings: Vec::new(),
+            default_selections: BitSet::default(),
+            all_selected: false,
             page_size: 10,
             prompt_prefix: "?".into(),
             help_message: None,
@@ -53,8 +74,15 @@ impl<T: Clone> MultiSelect<T> {
             show_descriptions: true,
             allow_escape: true,
             vim_mode: false,
+            keymap: None,
+            filter: false,
             min_selections: None,
             max_selections: None,
+            preview: None,
+            preview_position: PreviewPosition::default(),
+            create: None,
+            create_validation: None,
+            stream: None,
             style: MultiSelectStyle::default(),
             validation: None,
             _cursor_guard,
@@ -66,26 +94,73 @@ impl<T: Clone> MultiSelect<T> {
     }
 
     pub fn options(&self) -> &[AskOption<T>] {
+        self.options.shared()
+    }
+
+    /// the options as the prompt holds them, without making any that are
+    /// only made when needed
+    #[cfg(feature = "bench")]
+    pub(crate) fn option_list(&self) -> &OptionList<T> {
         &self.options
     }
 
     pub fn with_options(mut self, options: Vec<AskOption<T>>) -> Self {
-        self.options = options;
+        self.options = options.into();
+        self.headings.clear();
+        self
+    }
+
+    /// take options that are only made once they're needed, for lists too
+    /// big to build up front
+    ///
+    /// adding more options with `with_option` or `with_group` makes all of
+    /// them at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - where to get the option at each index
+    pub fn with_indexed_options(mut self, options: impl IndexedOptions<T> + 'static) -> Self {
+        self.options = (Box::new(options) as Box<dyn IndexedOptions<T>>).into();
+        self.headings.clear();
         self
     }
 
     pub fn with_option(mut self, option: AskOption<T>) -> Self {
-        self.options.push(option);
+        self.options.to_mut().push(option);
+        self
+    }
+
+    /// add options under a heading
+    ///
+    /// toggling the heading selects or deselects the whole group. options
+    /// added after the group with `with_option` join it.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - the heading shown above the options
+    /// * `options` - the options in the group
+    pub fn with_group(mut self, title: impl Into<String>, options: Vec<AskOption<T>>) -> Self {
+        self.headings
+            .push((self.options.len(), Heading::Group(title.into())));
+        self.options.to_mut().extend(options);
+        self
+    }
+
+    /// draw a line between the options so far and the ones added after
+    pub fn with_separator(mut self) -> Self {
+        self.headings.push((self.options.len(), Heading::Separator));
         self
     }
 
     pub fn with_default_selections(mut self, indices: &[usize]) -> Self {
         self.default_selections = indices.iter().copied().collect();
+        self.all_selected = false;
         self
     }
 
+    /// start with every option selected, apart from disabled ones
     pub fn with_all_selected(mut self) -> Self {
-        self.default_selections = (0..self.options.len()).collect();
+        self.all_selected = true;
         self
     }
 
@@ -124,6 +199,24 @@ impl<T: Clone> MultiSelect<T> {
         self
     }
 
+    /// set which keys do what, instead of the bindings `with_vim_mode` picks
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.keymap = Some(keymap);
+        self
+    }
+
+    /// narrow the options down by typing, matching names and descriptions
+    /// fuzzily
+    ///
+    /// selections are kept while the filter changes. typed letters go to the
+    /// filter rather than the keymap, so vim-style letter bindings don't apply
+    /// while it's on, and ← → move through what's typed instead of selecting
+    /// or deselecting everything.
+    pub fn with_filter(mut self, enabled: bool) -> Self {
+        self.filter = enabled;
+        self
+    }
+
     pub fn with_min_selections(mut self, min: usize) -> Self {
         self.min_selections = Some(min);
         self
@@ -134,6 +227,71 @@ impl<T: Clone> MultiSelect<T> {
         self
     }
 
+    /// show text about the option under the cursor in a pane beside the
+    /// options
+    ///
+    /// the text is only made again when the cursor moves to another option,
+    /// and nothing is shown while the cursor is on a group title.
+    ///
+    /// # Arguments
+    ///
+    /// * `preview` - makes the text for an option
+    pub fn with_preview(mut self, preview: impl Fn(&AskOption<T>) -> String + 'static) -> Self {
+        self.preview = Some(Arc::new(preview));
+        self
+    }
+
+    /// put the preview pane below the options instead of beside them
+    pub fn with_preview_position(mut self, position: PreviewPosition) -> Self {
+        self.preview_position = position;
+        self
+    }
+
+    /// offer to make a new option out of the filter text when no option has
+    /// that name, turning the filter on
+    ///
+    /// the new option is named after the text, gets its value from `create`,
+    /// and is selected straight away. space types into the text while the
+    /// cursor is on the entry for the new option, rather than toggling.
+    ///
+    /// # Arguments
+    ///
+    /// * `create` - makes the value for a new option from its name
+    pub fn with_create(mut self, create: impl Fn(&str) -> T + 'static) -> Self {
+        self.create = Some(Arc::new(create));
+        self.filter = true;
+        self
+    }
+
+    /// check the name of a new option before it's made
+    pub fn with_create_validation(mut self, validation: impl Validate<str> + 'static) -> Self {
+        self.create_validation = Some(Box::new(validation));
+        self
+    }
+
+    /// keep adding options from `receiver` while the prompt is open, until
+    /// every sender is dropped
+    ///
+    /// a spinner shows while options are still coming, and the cursor stays
+    /// on its option as new ones arrive. the options only go to the first
+    /// ask, so asking again is an error.
+    pub fn with_stream(mut self, receiver: Receiver<AskOption<T>>) -> Self {
+        self.stream = Some(Stream::new(receiver));
+        self
+    }
+
+    /// like [`MultiSelect::with_stream`], taking options from an iterator
+    /// that's run on another thread
+    pub fn with_stream_iter<I>(mut self, iter: I) -> Self
+    where
+        I: IntoIterator<Item = AskOption<T>>,
+        I::IntoIter: Send + 'static,
+        T: Send + 'static,
+    {
+        self.stream = Some(Stream::from_iter(iter));
+        self
+    }
+
     pub fn with_style(mut self, style: MultiSelectStyle) -> Self {
         self.style = style;
         self
@@ -145,17 +303,43 @@ impl<T: Clone> MultiSelect<T> {
     }
 
     pub fn ask(&self) -> miette::Result<Vec<AskOption<T>>> {
-        if self.options.is_empty() {
+        self.ask_with(false)
+    }
+
+    /// pick options and put them in order, returning their values in that
+    /// order
+    ///
+    /// picks are listed ahead of the other options, numbered in the order
+    /// they were made. alt with the up and down arrows moves the pick under
+    /// the cursor past its neighbours. Ctrl-G, or whatever else is bound to
+    /// [`Action::Grab`], picks it up instead, so the cursor keys carry it like
+    /// in [`crate::Sort`] until any other key puts it down.
+    pub fn ask_ordered(&self) -> miette::Result<Vec<T>> {
+        let picked = self.ask_with(true)?;
+        Ok(picked.into_iter().map(|option| option.value).collect())
+    }
+
+    fn ask_with(&self, ordered: bool) -> miette::Result<Vec<AskOption<T>>> {
+        if self.options.is_empty() && self.stream.is_none() {
             return Err(miette::miette!("No options provided"));
         }
 
+        if let Some(ref stream) = self.stream {
+            stream.open()?;
+        }
+
         let original_hook = std::panic::take_hook();
         std::panic::set_hook(Box::new(move |panic_info| {
             let _ = terminal::disable_raw_mode();
             std::panic::take_hook()(panic_info);
         }));
 
-        let result = self.ask_internal();
+        let result = self.ask_internal(ordered);
+
+        // a command still printing options isn't needed once there's an answer
+        if let Some(ref stream) = self.stream {
+            stream.stop();
+        }
 
         let _ = std::panic::take_hook();
         std::panic::set_hook(original_hook);
@@ -163,21 +347,36 @@ impl<T: Clone> MultiSelect<T> {
         result
     }
 
-    fn ask_internal(&self) -> miette::Result<Vec<AskOption<T>>> {
-        let mut selected_indices: HashSet<usize> = self
-            .default_selections
-            .iter()
-            .copied()
-            .filter(|&i| i < self.options.len())
-            .collect();
+    fn ask_internal(&self, ordered: bool) -> miette::Result<Vec<AskOption<T>>> {
+        // options made or streamed in while asking go into a copy, leaving
+        // the prompt's own options as they were. the copy shares those
+        // rather than copying them
+        let mut this = Cow::Borrowed(self);
 
-        let mut cursor = 0;
-        let mut scroll_offset = 0;
+        let defaults: BitSet = if this.all_selected {
+            this.enabled(0..this.options.len())
+        } else {
+            this.default_selections
+                .iter()
+                .filter(|&i| this.options.get(i).is_some_and(|o| !o.disabled))
+                .collect()
+        };
+        let mut selected_indices = Selection::new(defaults, ordered);
+        if !this.headings.is_empty() {
+            let starts: Vec<usize> = this.headings.iter().map(|&(at, _)| at).collect();
+            selected_indices
+                .count_groups(&starts, this.options.len(), |i| !this.options[i].disabled);
+        }
+
+        let mut filter = Filter::new(&this.options);
+        let mut list = this.list(&filter, &selected_indices, None);
+        let mut pane = Pane::default();
         let mut buf = Vec::with_capacity(4096);
         let mut out = stdout();
 
+        this.update_pane(&mut pane, &list);
         let mut last_render_lines =
-            self.render(&mut buf, cursor, scroll_offset, &selected_indices)?;
+            this.render(&mut buf, &list, &selected_indices, &filter, &pane)?;
         out.write_all(&buf).into_diagnostic()?;
         out.flush().into_diagnostic()?;
 
@@ -188,177 +387,415 @@ impl<T: Clone> MultiSelect<T> {
         }
 
         loop {
-            if let Event::Key(key_event) = event::read().into_diagnostic()? {
-                if key_event.kind != KeyEventKind::Press {
-                    continue;
+            let key_event = if this.loading() && !event::poll(TICK).into_diagnostic()? {
+                None
+            } else {
+                match event::read().into_diagnostic()? {
+                    Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                        Some(key_event)
+                    }
+                    _ => continue,
                 }
+            };
 
-                match self.handle_key(
+            let received = if this.loading() {
+                this.to_mut()
+                    .receive(&mut list, &mut selected_indices, &mut filter)
+            } else {
+                Ok(())
+            };
+
+            let result = match (received, key_event) {
+                (Err(e), _) => Err(e),
+                (Ok(()), None) => Ok(None),
+                (Ok(()), Some(key_event)) if this.creating(&key_event, &list) => this
+                    .to_mut()
+                    .create(&mut list, &mut selected_indices, &mut filter),
+                (Ok(()), Some(key_event)) => this.handle_key(
                     key_event,
-                    &mut cursor,
-                    &mut scroll_offset,
+                    &mut list,
                     &mut selected_indices,
-                ) {
-                    Ok(Some(())) => {
-                        terminal::disable_raw_mode().into_diagnostic()?;
-                        buf.clear();
-                        if last_render_lines > 0 {
-                            queue!(buf, cursor::MoveUp(last_render_lines as u16))
-                                .into_diagnostic()?;
-                        }
-                        queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
-                        queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
-
-                        let mut indices: Vec<usize> = selected_indices.into_iter().collect();
-                        indices.sort_unstable();
-                        self.show_result(&mut buf, &indices)?;
-                        out.write_all(&buf).into_diagnostic()?;
-                        out.flush().into_diagnostic()?;
-
-                        let selected_options = indices
-                            .iter()
-                            .filter_map(|&i| self.options.get(i).cloned())
-                            .collect();
-                        return Ok(selected_options);
+                    &mut filter,
+                    &mut pane,
+                ),
+            };
+
+            if selected_indices.take_rearranged() {
+                this.rearrange(&mut list, &selected_indices, &filter);
+            }
+
+            match result {
+                Ok(Some(())) => {
+                    terminal::disable_raw_mode().into_diagnostic()?;
+                    buf.clear();
+                    if last_render_lines > 0 {
+                        queue!(buf, cursor::MoveUp(last_render_lines as u16)).into_diagnostic()?;
                     }
-                    Ok(None) => {
-                        buf.clear();
-                        if last_render_lines > 0 {
-                            queue!(buf, cursor::MoveUp(last_render_lines as u16))
-                                .into_diagnostic()?;
-                        }
-                        queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
-                        queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
-                        last_render_lines =
-                            self.render(&mut buf, cursor, scroll_offset, &selected_indices)?;
-                        out.write_all(&buf).into_diagnostic()?;
-                        out.flush().into_diagnostic()?;
+                    queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
+                    queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+
+                    let indices = selected_indices.picks();
+                    this.show_result(&mut buf, &indices, ordered)?;
+                    out.write_all(&buf).into_diagnostic()?;
+                    out.flush().into_diagnostic()?;
+
+                    let selected_options = indices
+                        .iter()
+                        .filter_map(|&i| this.options.get(i).cloned())
+                        .collect();
+                    return Ok(selected_options);
+                }
+                Ok(None) => {
+                    buf.clear();
+                    if last_render_lines > 0 {
+                        queue!(buf, cursor::MoveUp(last_render_lines as u16)).into_diagnostic()?;
                     }
-                    Err(e) => {
-                        terminal::disable_raw_mode().into_diagnostic()?;
-                        buf.clear();
-                        if last_render_lines > 0 {
-                            queue!(buf, cursor::MoveUp(last_render_lines as u16))
-                                .into_diagnostic()?;
-                        }
-                        queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
-                        queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
-                        self.show_error(&mut buf, &e)?;
-                        out.write_all(&buf).into_diagnostic()?;
-                        out.flush().into_diagnostic()?;
-                        return Err(miette::miette!(e));
+                    queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
+                    queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+                    this.update_pane(&mut pane, &list);
+                    last_render_lines =
+                        this.render(&mut buf, &list, &selected_indices, &filter, &pane)?;
+                    out.write_all(&buf).into_diagnostic()?;
+                    out.flush().into_diagnostic()?;
+                }
+                Err(e) => {
+                    terminal::disable_raw_mode().into_diagnostic()?;
+                    buf.clear();
+                    if last_render_lines > 0 {
+                        queue!(buf, cursor::MoveUp(last_render_lines as u16)).into_diagnostic()?;
                     }
+                    queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
+                    queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+                    this.show_error(&mut buf, &e)?;
+                    out.write_all(&buf).into_diagnostic()?;
+                    out.flush().into_diagnostic()?;
+                    return Err(miette::miette!(e));
                 }
             }
         }
     }
 
+    const ACTIONS: &[Action] = &[
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveToFirst,
+        Action::MoveToLast,
+        Action::PageUp,
+        Action::PageDown,
+        Action::Toggle,
+        Action::SelectAll,
+        Action::DeselectAll,
+        Action::InvertSelection,
+        Action::ToggleVisible,
+        Action::PreviewUp,
+        Action::PreviewDown,
+        Action::Submit,
+        Action::Cancel,
+    ];
+
+    /// the actions for [`MultiSelect::ask_ordered`], which can grab picks to
+    /// move them
+    const ORDERED_ACTIONS: &[Action] = &[
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveToFirst,
+        Action::MoveToLast,
+        Action::PageUp,
+        Action::PageDown,
+        Action::Toggle,
+        Action::Grab,
+        Action::MoveItemUp,
+        Action::MoveItemDown,
+        Action::SelectAll,
+        Action::DeselectAll,
+        Action::InvertSelection,
+        Action::ToggleVisible,
+        Action::PreviewUp,
+        Action::PreviewDown,
+        Action::Submit,
+        Action::Cancel,
+    ];
+
+    fn actions(selected_indices: &Selection) -> &'static [Action] {
+        if selected_indices.ordered() {
+            Self::ORDERED_ACTIONS
+        } else {
+            Self::ACTIONS
+        }
+    }
+
+    /// whether the cursor can land on `row`
+    fn focusable(&self, row: &Row) -> bool {
+        match row {
+            Row::Option(m) => !self.options[m.index].disabled,
+            Row::Group(..) | Row::Create => true,
+            Row::Separator => false,
+        }
+    }
+
+    /// lay out the rows for `filter`, with the cursor on the option at `index`
+    ///
+    /// picks come first when their order matters.
+    pub(crate) fn list(
+        &self,
+        filter: &Filter,
+        selected_indices: &Selection,
+        index: Option<usize>,
+    ) -> List {
+        let mut list = List::with_first(
+            filter,
+            &self.headings,
+            self.options.len(),
+            selected_indices.first(),
+        );
+        if self.create.is_some() && filter.is_new(&self.options) {
+            list.offer_create();
+        }
+        list.focus(index, self.page_size, |row| self.focusable(row));
+        list
+    }
+
+    /// whether options are still arriving from a stream
+    fn loading(&self) -> bool {
+        self.stream.as_ref().is_some_and(|stream| !stream.done)
+    }
+
+    /// add any options that have arrived, keeping the cursor where it is
+    fn receive(
+        &mut self,
+        list: &mut List,
+        selected_indices: &mut Selection,
+        filter: &mut Filter,
+    ) -> Result<(), String> {
+        let Some(ref mut stream) = self.stream else {
+            return Ok(());
+        };
+
+        let from = self.options.len();
+        if stream.take(&mut self.options)? {
+            filter.append(&self.options, from);
+            let enabled = (from..self.options.len())
+                .filter(|&i| !self.options[i].disabled)
+                .count();
+            selected_indices.grow(enabled);
+            self.rearrange(list, selected_indices, filter);
+        }
+        Ok(())
+    }
+
+    /// lay the rows out again, keeping the cursor where it is
+    fn rearrange(&self, list: &mut List, selected_indices: &Selection, filter: &Filter) {
+        let before = std::mem::take(list);
+        *list = self.list(filter, selected_indices, None);
+        list.keep_place(&before, self.page_size, |row| self.focusable(row));
+    }
+
+    /// make the preview for the option under the cursor, if it changed
+    fn update_pane(&self, pane: &mut Pane, list: &List) {
+        if let Some(ref preview) = self.preview {
+            pane.update(list.current().map(|m| m.index), |index| {
+                preview(&self.options[index])
+            });
+        }
+    }
+
+    /// the options in `indices` that aren't disabled
+    fn enabled<C: FromIterator<usize>>(&self, indices: impl IntoIterator<Item = usize>) -> C {
+        indices
+            .into_iter()
+            .filter(|&i| !self.options[i].disabled)
+            .collect()
+    }
+
+    /// select all of `indices` that can be, or deselect them if they all are
+    /// already
+    fn toggle_all(
+        &self,
+        indices: &[usize],
+        selected_indices: &mut Selection,
+        what: &str,
+    ) -> Result<(), String> {
+        let indices: Vec<usize> = self.enabled(indices.iter().copied());
+        if indices.iter().all(|&i| selected_indices.contains(i)) {
+            selected_indices.remove_all(&indices);
+            return Ok(());
+        }
+
+        let added = indices
+            .iter()
+            .filter(|&&i| !selected_indices.contains(i))
+            .count();
+        if let Some(max) = self.max_selections
+            && selected_indices.len() + added > max
+        {
+            return Err(format!(
+                "Cannot select {}: maximum {} selections allowed",
+                what, max
+            ));
+        }
+        selected_indices.extend(indices);
+        Ok(())
+    }
+
+    /// whether `key_event` picks the entry for making a new option
+    fn creating(&self, key_event: &KeyEvent, list: &List) -> bool {
+        let keymap = KeyMap::resolve(self.keymap.as_ref(), self.vim_mode);
+        matches!(list.row(list.cursor), Some(Row::Create))
+            && !Filter::takes(key_event, true)
+            && matches!(
+                keymap.action(key_event, Self::ACTIONS),
+                Some(Action::Toggle | Action::Submit)
+            )
+    }
+
+    /// add an option named after the filter text, and select it
+    fn create(
+        &mut self,
+        list: &mut List,
+        selected_indices: &mut Selection,
+        filter: &mut Filter,
+    ) -> Result<Option<()>, String> {
+        let name = filter.query.clone();
+        if let Some(ref validator) = self.create_validation {
+            run_validator(validator.as_ref(), name.as_str())?;
+        }
+        if let Some(max) = self.max_selections
+            && selected_indices.len() >= max
+        {
+            return Err(format!("Cannot select more than {} options", max));
+        }
+
+        let create = self
+            .create
+            .as_ref()
+            .ok_or("Creating options is not enabled")?;
+        let value = create(&name);
+        self.options.push(AskOption::with_name(name, value));
+
+        let index = self.options.len() - 1;
+        selected_indices.grow(1);
+        selected_indices.insert(index);
+        filter.clear(&self.options);
+        *list = self.list(filter, selected_indices, Some(index));
+        Ok(None)
+    }
+
     fn handle_key(
         &self,
         key_event: KeyEvent,
-        cursor: &mut usize,
-        scroll_offset: &mut usize,
-        selected_indices: &mut HashSet<usize>,
+        list: &mut List,
+        selected_indices: &mut Selection,
+        filter: &mut Filter,
+        pane: &mut Pane,
     ) -> Result<Option<()>, String> {
         if key_event.modifiers.contains(KeyModifiers::CONTROL)
             && matches!(key_event.code, KeyCode::Char('c'))
         {
             let _ = terminal::disable_raw_mode();
+            // exiting skips the cleanup in `ask`, which would leave a command
+            // that's still printing options running
+            if let Some(ref stream) = self.stream {
+                stream.stop();
+            }
             std::process::exit(130);
         }
 
         let total_options = self.options.len();
+        let keymap = KeyMap::resolve(self.keymap.as_ref(), self.vim_mode);
+        // space toggles options, so it's only typed on the row for making a
+        // new one, whose name can then have spaces in it
+        let on_create = matches!(list.row(list.cursor), Some(Row::Create));
+        let action = if self.filter
+            && (Filter::takes(&key_event, on_create) || moves_caret(key_event.into()))
+        {
+            None
+        } else {
+            keymap.action(&key_event, Self::actions(selected_indices))
+        };
+        let focusable = |row: &Row| self.focusable(row);
 
-        match key_event.code {
-            KeyCode::Up | KeyCode::Char('k') if self.vim_mode || key_event.code == KeyCode::Up => {
-                if *cursor > 0 {
-                    *cursor -= 1;
-                    if *cursor < *scroll_offset {
-                        *scroll_offset = *cursor;
-                    }
-                } else {
-                    *cursor = total_options - 1;
-                    *scroll_offset = cursor.saturating_sub(self.page_size - 1);
-                }
+        if selected_indices.grabbed {
+            self.carry(action, list, selected_indices);
+            return Ok(None);
+        }
+
+        match action {
+            Some(Action::MoveUp) => {
+                list.up(self.page_size, focusable);
                 Ok(None)
             }
-            KeyCode::Down | KeyCode::Char('j')
-                if self.vim_mode || key_event.code == KeyCode::Down =>
-            {
-                if *cursor < total_options - 1 {
-                    *cursor += 1;
-                    if *cursor >= *scroll_offset + self.page_size {
-                        *scroll_offset = cursor.saturating_sub(self.page_size - 1);
-                    }
-                } else {
-                    *cursor = 0;
-                    *scroll_offset = 0;
-                }
+            Some(Action::MoveDown) => {
+                list.down(self.page_size, focusable);
                 Ok(None)
             }
-            KeyCode::Home | KeyCode::Char('g')
-                if self.vim_mode || key_event.code == KeyCode::Home =>
-            {
-                *cursor = 0;
-                *scroll_offset = 0;
+            Some(Action::MoveToFirst) => {
+                list.first(focusable);
                 Ok(None)
             }
-            KeyCode::End | KeyCode::Char('G')
-                if self.vim_mode || key_event.code == KeyCode::End =>
-            {
-                *cursor = total_options - 1;
-                *scroll_offset = cursor.saturating_sub(self.page_size - 1);
+            Some(Action::MoveToLast) => {
+                list.last(focusable);
+                list.scroll_to_cursor(self.page_size);
                 Ok(None)
             }
-            KeyCode::PageUp => {
-                *cursor = cursor.saturating_sub(self.page_size);
-                *scroll_offset = scroll_offset.saturating_sub(self.page_size);
+            Some(Action::PageUp) => {
+                list.page_up(self.page_size, focusable);
                 Ok(None)
             }
-            KeyCode::PageDown => {
-                *cursor = (*cursor + self.page_size).min(total_options - 1);
-                if *cursor >= *scroll_offset + self.page_size {
-                    *scroll_offset = cursor.saturating_sub(self.page_size - 1);
-                }
+            Some(Action::PageDown) => {
+                list.page_down(self.page_size, focusable);
                 Ok(None)
             }
-            KeyCode::Char(' ') => {
-                if selected_indices.contains(cursor) {
-                    selected_indices.remove(cursor);
-                } else {
-                    if let Some(max) = self.max_selections
-                        && selected_indices.len() >= max
-                    {
-                        return Err(format!("Cannot select more than {} options", max));
+            Some(Action::Toggle) => match list.row(list.cursor) {
+                Some(Row::Option(m)) if !self.options[m.index].disabled => {
+                    if selected_indices.contains(m.index) {
+                        selected_indices.remove(m.index);
+                    } else {
+                        if let Some(max) = self.max_selections
+                            && selected_indices.len() >= max
+                        {
+                            return Err(format!("Cannot select more than {} options", max));
+                        }
+                        selected_indices.insert(m.index);
                     }
-                    selected_indices.insert(*cursor);
+                    // the option moves in or out of the picks, so carry on
+                    // from the one after it
+                    if selected_indices.ordered() {
+                        list.down(self.page_size, focusable);
+                    }
+                    Ok(None)
+                }
+                Some(Row::Group(_, options)) => {
+                    let indices: Vec<usize> = options.clone().collect();
+                    self.toggle_all(&indices, selected_indices, "the whole group")?;
+                    Ok(None)
                 }
+                _ => Ok(None),
+            },
+            Some(Action::ToggleVisible) if self.filter => {
+                let matched: Vec<usize> = filter.matches.iter().map(|m| m.index).collect();
+                self.toggle_all(&matched, selected_indices, "all matches")?;
                 Ok(None)
             }
-            KeyCode::Right | KeyCode::Char('a')
-                if self.vim_mode || key_event.code == KeyCode::Right =>
-            {
+            Some(Action::SelectAll) => {
+                let all_indices: BitSet = self.enabled(0..total_options);
                 if let Some(max) = self.max_selections
-                    && total_options > max
+                    && all_indices.len() > max
                 {
                     return Err(format!(
                         "Cannot select all: maximum {} selections allowed",
                         max
                     ));
                 }
-                *selected_indices = (0..total_options).collect();
+                selected_indices.replace(all_indices);
                 Ok(None)
             }
-            KeyCode::Left | KeyCode::Char('d')
-                if self.vim_mode || key_event.code == KeyCode::Left =>
-            {
+            Some(Action::DeselectAll) => {
                 selected_indices.clear();
                 Ok(None)
             }
-            KeyCode::Char('i') if self.vim_mode => {
-                let all_indices: HashSet<usize> = (0..total_options).collect();
-                let new_selections: HashSet<usize> =
-                    all_indices.difference(selected_indices).copied().collect();
+            Some(Action::InvertSelection) => {
+                let new_selections: BitSet =
+                    self.enabled((0..total_options).filter(|&i| !selected_indices.contains(i)));
 
                 if let Some(max) = self.max_selections
                     && new_selections.len() > max
@@ -368,15 +805,81 @@ impl<T: Clone> MultiSelect<T> {
                         max
                     ));
                 }
-                *selected_indices = new_selections;
+                selected_indices.replace(new_selections);
                 Ok(None)
             }
-            KeyCode::Enter => {
-                let selected_vec: Vec<usize> = selected_indices.iter().copied().collect();
-                self.validate_and_return(&selected_vec)
+            Some(Action::Grab) => {
+                if list
+                    .current()
+                    .is_some_and(|m| selected_indices.rank(m.index).is_some())
+                {
+                    selected_indices.grabbed = true;
+                }
+                Ok(None)
+            }
+            Some(Action::MoveItemUp) => {
+                self.step(list, selected_indices, true);
+                Ok(None)
+            }
+            Some(Action::MoveItemDown) => {
+                self.step(list, selected_indices, false);
+                Ok(None)
+            }
+            Some(Action::PreviewUp) => {
+                pane.scroll_up();
+                Ok(None)
             }
-            KeyCode::Esc if self.allow_escape => Err("Cancelled".into()),
-            _ => Ok(None),
+            Some(Action::PreviewDown) => {
+                pane.scroll_down();
+                Ok(None)
+            }
+            Some(Action::Submit) => self.validate_and_return(&selected_indices.picks()),
+            Some(Action::Cancel) if self.filter && !filter.is_empty() => {
+                let current = list.current().map(|m| m.index);
+                filter.clear(&self.options);
+                *list = self.list(filter, selected_indices, current);
+                Ok(None)
+            }
+            Some(Action::Cancel) if self.allow_escape => Err("Cancelled".into()),
+            _ => {
+                if self.filter && filter.edit(key_event, &self.options) {
+                    *list = self.list(filter, selected_indices, None);
+                    // stay put while the name of a new option is typed
+                    if on_create {
+                        list.focus_create(self.page_size);
+                    }
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// move the grabbed pick past the pick above or below it, or put it
+    /// down on any other action
+    fn carry(&self, action: Option<Action>, list: &mut List, selected_indices: &mut Selection) {
+        match action {
+            Some(Action::MoveUp) => self.step(list, selected_indices, true),
+            Some(Action::MoveDown) => self.step(list, selected_indices, false),
+            Some(_) => selected_indices.grabbed = false,
+            None => {}
+        }
+    }
+
+    /// move the pick under the cursor past the pick above or below it
+    fn step(&self, list: &mut List, selected_indices: &mut Selection, up: bool) {
+        let next = if up {
+            list.cursor.checked_sub(1)
+        } else {
+            Some(list.cursor + 1)
+        };
+
+        if let (Some(current), Some(next)) = (list.current(), next)
+            && let Some(Row::Option(other)) = list.row(next)
+            && selected_indices.swap(current.index, other.index)
+        {
+            list.swap(list.cursor, next);
+            list.cursor = next;
+            list.scroll_to_cursor(self.page_size);
         }
     }
 
@@ -400,12 +903,13 @@ impl<T: Clone> MultiSelect<T> {
         Ok(Some(()))
     }
 
-    fn render(
+    pub(crate) fn render(
         &self,
         out: &mut impl Write,
-        cursor: usize,
-        scroll_offset: usize,
-        selected_indices: &HashSet<usize>,
+        list: &List,
+        selected_indices: &Selection,
+        filter: &Filter,
+        pane: &Pane,
     ) -> miette::Result<usize> {
         let tw = crate::util::term_width();
         let mut line_count = 0;
@@ -428,101 +932,254 @@ impl<T: Clone> MultiSelect<T> {
             line_count += crate::util::writeln_physical(out, &line, tw)?;
         }
 
-        let end_offset = (scroll_offset + self.page_size).min(self.options.len());
-        let visible_options = &self.options[scroll_offset..end_offset];
-
-        for (i, option) in visible_options.iter().enumerate() {
-            let absolute_index = scroll_offset + i;
-            let is_cursor = absolute_index == cursor;
-            let is_selected = selected_indices.contains(&absolute_index);
-            let cursor_marker = if is_cursor { "󰁕" } else { " " };
-            let checkbox = if is_selected { "󰄲" } else { "󰄮" };
-
-            if self.show_descriptions && !option.description.is_empty() {
-                let line = format!(
+        if self.filter {
+            let line = if filter.is_empty() {
+                format!("  {}", "/ type to filter".style(self.style.hint))
+            } else {
+                format!(
                     "  {} {} {}",
-                    cursor_marker.style(self.style.cursor),
-                    checkbox.style(if is_selected {
-                        self.style.checkbox_selected
-                    } else {
-                        self.style.checkbox_unselected
-                    }),
-                    option.name.style(option.name_style)
-                );
+                    "/".style(self.style.hint),
+                    filter.query.style(self.style.prompt),
+                    format!("({}/{})", filter.matches.len(), self.options.len())
+                        .style(self.style.hint)
+                )
+            };
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
 
+            if list.is_empty() {
+                let line = format!("  {}", "(no matches)".style(self.style.hint));
                 line_count += crate::util::writeln_physical(out, &line, tw)?;
+            }
+        }
 
-                let line = format!(
-                    "        {}",
-                    option.description.style(option.description_style),
-                );
+        let visible = list.visible(self.page_size);
+        let beside = self.preview.is_some() && Pane::beside(self.preview_position, tw);
+        let mut rows = Vec::with_capacity(visible.len());
+
+        for (row_index, row) in list.rows(visible.clone()) {
+            let is_cursor = row_index == list.cursor;
+            let m = match row {
+                Row::Option(m) => m,
+                Row::Group(title, options) => {
+                    let cursor_marker = if is_cursor { "󰁕" } else { " " };
+                    let (checkbox, checkbox_style) =
+                        if selected_indices.group_selected(options.start) {
+                            ("󰄲", self.style.checkbox_selected)
+                        } else {
+                            ("󰄮", self.style.checkbox_unselected)
+                        };
+                    rows.push(format!(
+                        "  {} {} {}",
+                        cursor_marker.style(self.style.cursor),
+                        checkbox.style(checkbox_style),
+                        title.style(self.style.group_header)
+                    ));
+                    continue;
+                }
+                Row::Separator => {
+                    let width = if beside { tw / 2 } else { tw };
+                    rows.push(format!("  {}", list::rule(width).style(self.style.hint)));
+                    continue;
+                }
+                Row::Create => {
+                    let cursor_marker = if is_cursor { "󰁕" } else { " " };
+                    let style = if is_cursor {
+                        self.style.cursor
+                    } else {
+                        self.style.option_name
+                    };
+                    rows.push(format!(
+                        "  {} {} {}",
+                        cursor_marker.style(self.style.cursor),
+                        "󰄮".style(self.style.checkbox_unselected),
+                        format!("Create '{}'", filter.query).style(style)
+                    ));
+                    continue;
+                }
+            };
+            let option = &self.options[m.index];
+            let (name_matched, description_matched) = filter.positions(option);
+            let is_selected = selected_indices.contains(m.index);
+            let is_grabbed = is_cursor && selected_indices.grabbed;
+            let (cursor_marker, marker_style) = if is_grabbed {
+                ("≡", self.style.grabbed)
+            } else {
+                (if is_cursor { "󰁕" } else { " " }, self.style.cursor)
+            };
+            let checkbox = if is_selected { "󰄲" } else { "󰄮" };
+            let show_description = self.show_descriptions && !option.description.is_empty();
 
-                line_count += crate::util::writeln_physical(out, &line, tw)?;
+            let checkbox_style = if option.disabled {
+                self.style.disabled
+            } else if is_selected {
+                self.style.checkbox_selected
+            } else {
+                self.style.checkbox_unselected
+            };
+            let name_style = if option.disabled {
+                self.style.disabled
+            } else if is_grabbed {
+                self.style.grabbed
+            } else if show_description {
+                option.name_style
+            } else if is_cursor {
+                self.style.cursor
+            } else if is_selected {
+                self.style.selected
             } else {
-                let style = if is_cursor {
-                    self.style.cursor
-                } else if is_selected {
-                    self.style.selected
+                self.style.option_name
+            };
+            let reason = match option.disabled_reason {
+                Some(ref reason) => format!(" {}", format!("({})", reason).style(self.style.hint)),
+                None => String::new(),
+            };
+            let rank = match selected_indices.rank(m.index) {
+                Some(rank) => format!("{} ", format!("{}.", rank + 1).style(checkbox_style)),
+                None => String::new(),
+            };
+
+            rows.push(format!(
+                "  {} {} {}{}{}",
+                cursor_marker.style(marker_style),
+                checkbox.style(checkbox_style),
+                rank,
+                filter::highlight(
+                    &option.name,
+                    &name_matched,
+                    name_style,
+                    self.style.filter_match
+                ),
+                reason
+            ));
+
+            if show_description {
+                let desc_style = if option.disabled {
+                    self.style.disabled
                 } else {
-                    self.style.option_name
+                    option.description_style
                 };
+                rows.push(format!(
+                    "        {}",
+                    filter::highlight(
+                        &option.description,
+                        &description_matched,
+                        desc_style,
+                        self.style.filter_match
+                    ),
+                ));
+            }
+        }
 
-                let line = format!(
-                    "  {} {} {}",
-                    cursor_marker.style(self.style.cursor),
-                    checkbox.style(if is_selected {
-                        self.style.checkbox_selected
-                    } else {
-                        self.style.checkbox_unselected
-                    }),
-                    option.name.style(style)
-                );
-
-                line_count += crate::util::writeln_physical(out, &line, tw)?;
+        if beside {
+            line_count += pane.write_beside(out, &rows, self.page_size, self.style.hint, tw)?;
+        } else {
+            for line in &rows {
+                line_count += crate::util::writeln_physical(out, line, tw)?;
             }
         }
 
-        if scroll_offset > 0 {
+        let above = list.count_options(0..visible.start);
+        if above > 0 {
+            let line = format!(
+                "  {}",
+                format!("(↑ {} more above)", above).style(self.style.hint)
+            );
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        let below = list.count_options(visible.end..list.len());
+        if below > 0 {
             let line = format!(
                 "  {}",
-                format!("(↑ {} more above)", scroll_offset).style(self.style.hint)
+                format!("(↓ {} more below)", below).style(self.style.hint)
             );
             line_count += crate::util::writeln_physical(out, &line, tw)?;
         }
 
-        if end_offset < self.options.len() {
+        if self.loading() {
             let line = format!(
                 "  {}",
-                format!("(↓ {} more below)", self.options.len() - end_offset)
-                    .style(self.style.hint)
+                format!(
+                    "{} loading… ({} so far)",
+                    stream::spinner(),
+                    self.options.len()
+                )
+                .style(self.style.hint)
             );
             line_count += crate::util::writeln_physical(out, &line, tw)?;
         }
 
+        if self.preview.is_some() && !beside {
+            line_count += pane.write_below(out, self.style.hint, tw)?;
+        }
+
         if self.show_hints {
+            let keymap = KeyMap::resolve(self.keymap.as_ref(), self.vim_mode);
             let mut hints = vec![];
 
-            hints.push("Space to toggle");
+            if let Some(key) = keymap.hint(Action::Toggle) {
+                hints.push(format!("{} to toggle", key));
+            }
 
-            if self.vim_mode {
-                hints.push("j/k or ↑/↓ to navigate");
-                hints.push("a to select all");
-                hints.push("d to deselect all");
-                hints.push("i to invert");
-            } else {
-                hints.push("↑↓ to navigate");
-                hints.push("→ to select all");
-                hints.push("← to deselect all");
+            if selected_indices.ordered() {
+                if let (Some(up), Some(down)) = (
+                    keymap.hint_among(Action::MoveItemUp, Self::ORDERED_ACTIONS),
+                    keymap.hint_among(Action::MoveItemDown, Self::ORDERED_ACTIONS),
+                ) {
+                    hints.push(format!("{}/{} to move picks", up, down));
+                }
+
+                if let Some(key) = keymap.hint_among(Action::Grab, Self::ORDERED_ACTIONS) {
+                    hints.push(format!("{} to grab/release", key));
+                }
+            }
+
+            if let Some(keys) = keymap.hint_pair(Action::MoveUp, Action::MoveDown) {
+                hints.push(format!("{} to navigate", keys));
+            }
+
+            for (action, text) in [
+                (Action::SelectAll, "select all"),
+                (Action::DeselectAll, "deselect all"),
+                (Action::InvertSelection, "invert"),
+            ] {
+                if let Some(key) = keymap
+                    .keys(action)
+                    .find(|&key| !(self.filter && moves_caret(key)))
+                {
+                    hints.push(format!("{} to {}", key, text));
+                }
+            }
+
+            if self.filter
+                && !filter.is_empty()
+                && let Some(key) = keymap.hint(Action::ToggleVisible)
+            {
+                hints.push(format!("{} to toggle matches", key));
             }
 
-            if self.options.len() > self.page_size {
-                hints.push("PgUp/PgDn to scroll");
+            if list.len() > self.page_size
+                && let Some(keys) = keymap.hint_pair(Action::PageUp, Action::PageDown)
+            {
+                hints.push(format!("{} to scroll", keys));
             }
 
-            hints.push("Enter to submit");
+            if self.preview.is_some()
+                && let Some(keys) = keymap.hint_pair(Action::PreviewUp, Action::PreviewDown)
+            {
+                hints.push(format!("{} to scroll preview", keys));
+            }
 
-            if self.allow_escape {
-                hints.push("Esc to cancel");
+            if let Some(key) = keymap.hint(Action::Submit) {
+                hints.push(format!("{} to submit", key));
+            }
+
+            if let Some(key) = keymap.hint(Action::Cancel) {
+                if self.filter && !filter.is_empty() {
+                    hints.push(format!("{} to clear filter", key));
+                } else if self.allow_escape {
+                    hints.push(format!("{} to cancel", key));
+                }
             }
 
             let line = format!("  {}", hints.join(", ").style(self.style.hint));
@@ -548,10 +1205,23 @@ impl<T: Clone> MultiSelect<T> {
         Ok(())
     }
 
-    fn show_result(&self, out: &mut impl Write, selected: &[usize]) -> miette::Result<()> {
+    fn show_result(
+        &self,
+        out: &mut impl Write,
+        selected: &[usize],
+        ordered: bool,
+    ) -> miette::Result<()> {
         let selected_names: Vec<String> = selected
             .iter()
             .filter_map(|&i| self.options.get(i).map(|opt| opt.name.clone()))
+            .enumerate()
+            .map(|(rank, name)| {
+                if ordered {
+                    format!("{}. {}", rank + 1, name)
+                } else {
+                    name
+                }
+            })
             .collect();
 
         let result_text = if selected_names.is_empty() {
@@ -572,3 +1242,283 @@ impl<T: Clone> MultiSelect<T> {
         Ok(())
     }
 }
+
+impl MultiSelect<String> {
+    /// run `command` and offer each line it prints as an option, like piping
+    /// it into fzf
+    ///
+    /// a line is split at the first `delimiter` into the option's name and
+    /// description, and the name is the value. a command that can't be
+    /// started is an error here, and one that exits unsuccessfully makes
+    /// `ask` fail with what it printed to stderr. the command is killed if
+    /// the prompt is answered or cancelled before it finishes.
+    ///
+    /// ```rust,ignore
+    /// let mut command = Command::new("git");
+    /// command.args(["branch", "--format=%(refname:short)%09%(subject)"]);
+    /// let branch = MultiSelect::from_command("Branches", command, "\t")?.ask()?;
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt` - the question to ask
+    /// * `command` - the command whose stdout lines become options
+    /// * `delimiter` - what separates a line's name from its description
+    pub fn from_command(
+        prompt: impl Into<String>,
+        command: Command,
+        delimiter: &str,
+    ) -> miette::Result<Self> {
+        let mut prompt = Self::new(prompt);
+        prompt.stream = Some(Stream::from_command(command, delimiter)?);
+        Ok(prompt)
+    }
+}
+
+/// whether `key` moves the caret in the filter, so it's left for editing
+/// rather than looked up while the filter is on
+fn moves_caret(key: KeyBinding) -> bool {
+    matches!(key.code, KeyCode::Left | KeyCode::Right)
+}
+
+/// what's selected in a `MultiSelect`, and the order it was picked in when
+/// that matters
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Selection {
+    set: BitSet,
+    /// the picks in order, for [`MultiSelect::ask_ordered`]
+    order: Option<Vec<usize>>,
+    /// where each pick is in `order`, so numbering the rows on screen
+    /// doesn't have to search it
+    ranks: HashMap<usize, usize>,
+    /// whether the order changed, so the picks shown first need laying out
+    /// again
+    rearranged: bool,
+    /// whether the pick under the cursor is being moved
+    grabbed: bool,
+    /// how much of each group is picked, so drawing its checkbox doesn't
+    /// look through it
+    groups: Vec<GroupCount>,
+}
+
+/// how many options in a group can be picked, and how many are
+#[derive(Clone, Copy, Debug, Default)]
+struct GroupCount {
+    /// the first option in the group
+    start: usize,
+    enabled: usize,
+    selected: usize,
+}
+
+impl Selection {
+    /// select `set`, keeping track of the order picks are made in if
+    /// `ordered`, starting with `set` smallest first
+    pub(crate) fn new(set: BitSet, ordered: bool) -> Self {
+        let mut selection = Self {
+            order: ordered.then(|| set.iter().collect()),
+            set,
+            ..Default::default()
+        };
+        selection.reindex();
+        selection
+    }
+
+    /// work out every pick's rank again after the order has changed
+    fn reindex(&mut self) {
+        self.ranks.clear();
+        if let Some(ref order) = self.order {
+            self.ranks
+                .extend(order.iter().enumerate().map(|(rank, &index)| (index, rank)));
+        }
+    }
+
+    fn ordered(&self) -> bool {
+        self.order.is_some()
+    }
+
+    /// keep count of what's picked in the groups starting at `starts`, each
+    /// running up to the next, with the last running to `len`
+    fn count_groups(&mut self, starts: &[usize], len: usize, enabled: impl Fn(usize) -> bool) {
+        let ends = starts.iter().skip(1).copied().chain(std::iter::once(len));
+        self.groups = starts
+            .iter()
+            .zip(ends)
+            .map(|(&start, end)| GroupCount {
+                start,
+                enabled: (start..end).filter(|&i| enabled(i)).count(),
+                selected: (start..end).filter(|&i| self.set.contains(i)).count(),
+            })
+            .collect();
+    }
+
+    /// the group `index` is in, if it's in one
+    fn group_mut(&mut self, index: usize) -> Option<&mut GroupCount> {
+        let after = self.groups.partition_point(|group| group.start <= index);
+        after.checked_sub(1).map(|group| &mut self.groups[group])
+    }
+
+    /// count `enabled` more options that can be picked, added to the end so
+    /// they're in the last group
+    fn grow(&mut self, enabled: usize) {
+        if let Some(group) = self.groups.last_mut() {
+            group.enabled += enabled;
+        }
+    }
+
+    /// whether every option that can be picked in the group starting at
+    /// `start` is, and there's at least one
+    fn group_selected(&self, start: usize) -> bool {
+        // a separator just before a group has no options, so it's the
+        // last one starting here that's wanted
+        let after = self.groups.partition_point(|group| group.start <= start);
+        after
+            .checked_sub(1)
+            .map(|group| &self.groups[group])
+            .filter(|group| group.start == start)
+            .is_some_and(|group| group.enabled > 0 && group.selected == group.enabled)
+    }
+
+    /// count `index` in or out of its group
+    fn count(&mut self, index: usize, selected: bool) {
+        if let Some(group) = self.group_mut(index) {
+            if selected {
+                group.selected += 1;
+            } else {
+                group.selected -= 1;
+            }
+        }
+    }
+
+    fn contains(&self, index: usize) -> bool {
+        self.set.contains(index)
+    }
+
+    /// add `index` as the last pick, returning whether it wasn't there
+    /// already
+    pub(crate) fn insert(&mut self, index: usize) -> bool {
+        let added = self.set.insert(index);
+        if added {
+            self.count(index, true);
+        }
+        if added && let Some(ref mut order) = self.order {
+            self.ranks.insert(index, order.len());
+            order.push(index);
+            self.rearranged = true;
+        }
+        added
+    }
+
+    /// take out `index`, returning whether it was there
+    pub(crate) fn remove(&mut self, index: usize) -> bool {
+        let removed = self.set.remove(index);
+        if removed {
+            self.count(index, false);
+        }
+        if removed && let Some(ref mut order) = self.order {
+            order.retain(|&i| i != index);
+            self.rearranged = true;
+            self.reindex();
+        }
+        removed
+    }
+
+    /// take out every one of `indices`, going through the order just once
+    /// rather than once for each
+    fn remove_all(&mut self, indices: &[usize]) {
+        let mut removed = false;
+        for &index in indices {
+            if self.set.remove(index) {
+                self.count(index, false);
+                removed = true;
+            }
+        }
+        if removed && let Some(ref mut order) = self.order {
+            order.retain(|&i| self.set.contains(i));
+            self.rearranged = true;
+            self.reindex();
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    fn clear(&mut self) {
+        if let Some(ref mut order) = self.order {
+            order.clear();
+            self.rearranged = true;
+        }
+        self.ranks.clear();
+        self.set.clear();
+        for group in self.groups.iter_mut() {
+            group.selected = 0;
+        }
+    }
+
+    /// select just `set`, keeping the order of picks that are still in it
+    /// and adding the new ones after them, smallest first
+    fn replace(&mut self, set: BitSet) {
+        if let Some(ref mut order) = self.order {
+            order.retain(|&i| set.contains(i));
+            order.extend(set.iter().filter(|&i| !self.set.contains(i)));
+            self.rearranged = true;
+        }
+        self.set = set;
+        self.reindex();
+
+        let groups = &mut self.groups;
+        for group in groups.iter_mut() {
+            group.selected = 0;
+        }
+        for index in self.set.iter() {
+            let after = groups.partition_point(|group| group.start <= index);
+            if let Some(group) = after.checked_sub(1) {
+                groups[group].selected += 1;
+            }
+        }
+    }
+
+    /// the picks to show ahead of the other options
+    fn first(&self) -> &[usize] {
+        self.order.as_deref().unwrap_or_default()
+    }
+
+    /// where `index` comes in the order, counting from zero
+    fn rank(&self, index: usize) -> Option<usize> {
+        self.ranks.get(&index).copied()
+    }
+
+    /// swap two picks in the order, returning whether both were picked
+    fn swap(&mut self, a: usize, b: usize) -> bool {
+        let (Some(a), Some(b)) = (self.rank(a), self.rank(b)) else {
+            return false;
+        };
+        if let Some(ref mut order) = self.order {
+            order.swap(a, b);
+            self.ranks.insert(order[a], a);
+            self.ranks.insert(order[b], b);
+        }
+        true
+    }
+
+    /// whether the picks need laying out again since this was last asked
+    fn take_rearranged(&mut self) -> bool {
+        std::mem::take(&mut self.rearranged)
+    }
+
+    /// the picks in order, or smallest first if the order doesn't matter
+    fn picks(&self) -> Vec<usize> {
+        match self.order {
+            Some(ref order) => order.clone(),
+            None => self.set.iter().collect(),
+        }
+    }
+}
+
+impl Extend<usize> for Selection {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for index in iter {
+            self.insert(index);
+        }
+    }
+}
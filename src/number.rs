@@ -1,5 +1,7 @@
 use {
     crate::{
+        keymap::{Action, KeyMap},
+        line::{self, KillRing},
         style::NumberStyle,
         util::CursorGuard,
         validation::{Validate, run_validator},
@@ -49,6 +51,7 @@ pub struct Number<T: NumericType> {
     max: Option<T>,
     step: T,
     allow_escape: bool,
+    keymap: KeyMap,
     show_hints: bool,
     show_bounds: bool,
     style: NumberStyle,
@@ -72,6 +75,7 @@ where
             max: None,
             step: T::from(1),
             allow_escape: true,
+            keymap: KeyMap::new(),
             show_hints: true,
             show_bounds: true,
             style: NumberStyle::default(),
@@ -131,6 +135,15 @@ impl<T: NumericType> Number<T> {
         self
     }
 
+    /// set which keys do what
+    ///
+    /// typed characters always go into the input, so only keys with Ctrl or
+    /// Alt, or keys like Enter and Esc, can be bound.
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
     pub fn with_style(mut self, style: NumberStyle) -> Self {
         self.style = style;
         self
@@ -159,6 +172,7 @@ impl<T: NumericType> Number<T> {
     fn ask_internal(&self) -> miette::Result<T> {
         let mut input = self.default.map(|d| d.to_string()).unwrap_or_default();
         let mut cursor_pos = input.len();
+        let mut kill_ring = KillRing::default();
         let mut error_message: Option<String> = None;
         let mut buf = Vec::with_capacity(4096);
         let mut out = stdout();
@@ -169,7 +183,7 @@ impl<T: NumericType> Number<T> {
             event::read().into_diagnostic()?;
         }
 
-        let mut last_render_lines = self.render(&mut buf, &input, error_message.as_deref())?;
+        let mut input_line = self.render(&mut buf, &input, cursor_pos, error_message.as_deref())?;
         out.write_all(&buf).into_diagnostic()?;
         out.flush().into_diagnostic()?;
 
@@ -181,13 +195,12 @@ impl<T: NumericType> Number<T> {
 
                 error_message = None;
 
-                match self.handle_key(key_event, &mut input, &mut cursor_pos) {
+                match self.handle_key(key_event, &mut input, &mut cursor_pos, &mut kill_ring) {
                     Ok(Some(value)) => {
                         terminal::disable_raw_mode().into_diagnostic()?;
                         buf.clear();
-                        if last_render_lines > 0 {
-                            queue!(buf, cursor::MoveUp(last_render_lines as u16))
-                                .into_diagnostic()?;
+                        if input_line > 0 {
+                            queue!(buf, cursor::MoveUp(input_line as u16)).into_diagnostic()?;
                         }
                         queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
                         queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
@@ -200,9 +213,8 @@ impl<T: NumericType> Number<T> {
                     Err(e) if e == "Cancelled" => {
                         terminal::disable_raw_mode().into_diagnostic()?;
                         buf.clear();
-                        if last_render_lines > 0 {
-                            queue!(buf, cursor::MoveUp(last_render_lines as u16))
-                                .into_diagnostic()?;
+                        if input_line > 0 {
+                            queue!(buf, cursor::MoveUp(input_line as u16)).into_diagnostic()?;
                         }
                         queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
                         queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
@@ -216,12 +228,12 @@ impl<T: NumericType> Number<T> {
                 }
 
                 buf.clear();
-                if last_render_lines > 0 {
-                    queue!(buf, cursor::MoveUp(last_render_lines as u16)).into_diagnostic()?;
+                if input_line > 0 {
+                    queue!(buf, cursor::MoveUp(input_line as u16)).into_diagnostic()?;
                 }
                 queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
                 queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
-                last_render_lines = self.render(&mut buf, &input, error_message.as_deref())?;
+                input_line = self.render(&mut buf, &input, cursor_pos, error_message.as_deref())?;
                 out.write_all(&buf).into_diagnostic()?;
                 out.flush().into_diagnostic()?;
             }
@@ -233,6 +245,7 @@ impl<T: NumericType> Number<T> {
         key_event: KeyEvent,
         input: &mut String,
         cursor_pos: &mut usize,
+        kill_ring: &mut KillRing,
     ) -> Result<Option<T>, String> {
         if key_event.modifiers.contains(KeyModifiers::CONTROL)
             && matches!(key_event.code, KeyCode::Char('c'))
@@ -241,8 +254,15 @@ impl<T: NumericType> Number<T> {
             std::process::exit(130);
         }
 
-        match key_event.code {
-            KeyCode::Enter => {
+        const ACTIONS: &[Action] = &[
+            Action::Submit,
+            Action::Increment,
+            Action::Decrement,
+            Action::Cancel,
+        ];
+
+        match self.keymap.text_action(&key_event, ACTIONS) {
+            Some(Action::Submit) => {
                 if input.is_empty() {
                     if let Some(default) = self.default {
                         return self.validate_value(default);
@@ -255,7 +275,7 @@ impl<T: NumericType> Number<T> {
                     .map_err(|_| format!("Invalid number: {}", input))?;
                 self.validate_value(value)
             }
-            KeyCode::Up => {
+            Some(Action::Increment) => {
                 if let Ok(current) = input.parse::<T>() {
                     let new_val = current.increment(self.step);
                     if let Some(max) = self.max
@@ -271,7 +291,7 @@ impl<T: NumericType> Number<T> {
                 }
                 Ok(None)
             }
-            KeyCode::Down => {
+            Some(Action::Decrement) => {
                 if let Ok(current) = input.parse::<T>() {
                     let new_val = current.decrement(self.step);
                     if let Some(min) = self.min
@@ -287,38 +307,13 @@ impl<T: NumericType> Number<T> {
                 }
                 Ok(None)
             }
-            KeyCode::Char(c) if c.is_ascii_digit() || c == '-' || c == '.' => {
-                input.insert(*cursor_pos, c);
-                *cursor_pos += 1;
-                Ok(None)
-            }
-            KeyCode::Backspace if *cursor_pos > 0 => {
-                *cursor_pos -= 1;
-                input.remove(*cursor_pos);
-                Ok(None)
-            }
-            KeyCode::Delete if *cursor_pos < input.len() => {
-                input.remove(*cursor_pos);
-                Ok(None)
-            }
-            KeyCode::Left if *cursor_pos > 0 => {
-                *cursor_pos -= 1;
+            Some(Action::Cancel) if self.allow_escape => Err("Cancelled".into()),
+            _ => {
+                line::edit_line(key_event, input, cursor_pos, kill_ring, |c| {
+                    c.is_ascii_digit() || c == '-' || c == '.'
+                });
                 Ok(None)
             }
-            KeyCode::Right if *cursor_pos < input.len() => {
-                *cursor_pos += 1;
-                Ok(None)
-            }
-            KeyCode::Home => {
-                *cursor_pos = 0;
-                Ok(None)
-            }
-            KeyCode::End => {
-                *cursor_pos = input.len();
-                Ok(None)
-            }
-            KeyCode::Esc if self.allow_escape => Err("Cancelled".into()),
-            _ => Ok(None),
         }
     }
 
@@ -339,10 +334,13 @@ impl<T: NumericType> Number<T> {
         Ok(Some(value))
     }
 
+    /// draw the prompt, leaving the cursor where it is in the input, and
+    /// return how many lines are above that
     fn render(
         &self,
         out: &mut impl Write,
         input: &str,
+        cursor_pos: usize,
         error: Option<&str>,
     ) -> miette::Result<usize> {
         let tw = crate::util::term_width();
@@ -389,6 +387,7 @@ impl<T: NumericType> Number<T> {
             input.style(self.style.input).to_string()
         };
 
+        let input_line = line_count;
         let line = format!("  {}", display_text);
         line_count += crate::util::writeln_physical(out, &line, tw)?;
 
@@ -402,17 +401,29 @@ impl<T: NumericType> Number<T> {
         }
 
         if self.show_hints {
-            let mut hints = vec!["↑↓ to increment/decrement"];
-            hints.push("Enter to submit");
-            if self.allow_escape {
-                hints.push("Esc to cancel");
+            let mut hints = vec![];
+            if let Some(keys) = self.keymap.hint_pair(Action::Increment, Action::Decrement) {
+                hints.push(format!("{} to increment/decrement", keys));
+            }
+            if let Some(key) = self.keymap.hint(Action::Submit) {
+                hints.push(format!("{} to submit", key));
+            }
+            if self.allow_escape
+                && let Some(key) = self.keymap.hint(Action::Cancel)
+            {
+                hints.push(format!("{} to cancel", key));
             }
 
             let line = format!("  {}", hints.join(", ").style(self.style.hint));
             line_count += crate::util::writeln_physical(out, &line, tw)?;
         }
 
-        Ok(line_count)
+        let cursor_column = 2 + crate::util::visible_width(&input[..cursor_pos.min(input.len())]);
+        queue!(out, cursor::MoveUp((line_count - input_line) as u16)).into_diagnostic()?;
+        queue!(out, cursor::MoveToColumn(cursor_column as u16)).into_diagnostic()?;
+        queue!(out, cursor::Show).into_diagnostic()?;
+
+        Ok(input_line)
     }
 
     fn show_result(&self, out: &mut impl Write, value: T) -> miette::Result<()> {
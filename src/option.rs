@@ -1,4 +1,11 @@
-use owo_colors::Style;
+use {
+    owo_colors::Style,
+    std::{
+        fmt,
+        ops::Index,
+        sync::{Arc, OnceLock},
+    },
+};
 
 /// an option in a (Multi)Select prompt
 #[derive(Clone, Debug)]
@@ -13,6 +20,10 @@ pub struct AskOption<T> {
     pub name_style: Style,
     /// styling settings for displaying the option description
     pub description_style: Style,
+    /// whether the option is shown but can't be picked
+    pub disabled: bool,
+    /// why the option can't be picked, shown next to it
+    pub disabled_reason: Option<String>,
 }
 
 impl<T> AskOption<T> {
@@ -29,6 +40,8 @@ impl<T> AskOption<T> {
             value,
             description: description.into(),
             description_style: crate::style::MultiSelectStyle::default().option_description,
+            disabled: false,
+            disabled_reason: None,
         }
     }
 
@@ -44,6 +57,8 @@ impl<T> AskOption<T> {
             value,
             description: String::new(),
             description_style: crate::style::MultiSelectStyle::default().option_description,
+            disabled: false,
+            disabled_reason: None,
         }
     }
 
@@ -66,4 +81,229 @@ impl<T> AskOption<T> {
         self.description_style = style;
         self
     }
+
+    /// show the option, but don't let it be picked
+    pub fn disabled(mut self) -> Self {
+        self.disabled = true;
+        self
+    }
+
+    /// show the option with the reason it can't be picked
+    ///
+    /// # Arguments
+    ///
+    /// * `reason` - why the option is unavailable
+    pub fn disabled_because(mut self, reason: impl Into<String>) -> Self {
+        self.disabled = true;
+        self.disabled_reason = Some(reason.into());
+        self
+    }
+}
+
+/// options a (Multi)Select looks up by where they are, made only once
+/// they're needed
+///
+/// for lists too big to build every `AskOption` up front. the prompt asks
+/// for the options on screen as they're drawn, and for every option once
+/// something is typed into the filter, keeping each one it's been given.
+pub trait IndexedOptions<T> {
+    /// how many options there are, which mustn't change once the prompt has
+    /// them
+    fn len(&self) -> usize;
+
+    /// the option at `index`, which is less than the length
+    fn get(&self, index: usize) -> AskOption<T>;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// how many options are made at a time from `IndexedOptions`
+const CHUNK: usize = 256;
+
+/// options made from `IndexedOptions` as they're looked at
+struct Lazy<T> {
+    source: Box<dyn IndexedOptions<T>>,
+    len: usize,
+    chunks: Box<[OnceLock<Box<[AskOption<T>]>>]>,
+    /// every option in one slice, only made if asked for
+    all: OnceLock<Vec<AskOption<T>>>,
+}
+
+impl<T> Lazy<T> {
+    fn new(source: Box<dyn IndexedOptions<T>>) -> Self {
+        let len = source.len();
+        Self {
+            source,
+            len,
+            chunks: (0..len.div_ceil(CHUNK)).map(|_| OnceLock::new()).collect(),
+            all: OnceLock::new(),
+        }
+    }
+
+    fn get(&self, index: usize) -> Option<&AskOption<T>> {
+        let chunk = self.chunks.get(index / CHUNK)?.get_or_init(|| {
+            let start = index / CHUNK * CHUNK;
+            (start..self.len.min(start + CHUNK))
+                .map(|index| self.source.get(index))
+                .collect()
+        });
+        chunk.get(index % CHUNK)
+    }
+
+    fn all(&self) -> &[AskOption<T>]
+    where
+        T: Clone,
+    {
+        self.all
+            .get_or_init(|| (0..self.len).filter_map(|i| self.get(i).cloned()).collect())
+    }
+}
+
+impl<T> fmt::Debug for Lazy<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lazy")
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
+
+/// options that can be looked up by where they are, whether or not they're
+/// all in one slice
+pub(crate) trait Options<T>: Index<usize, Output = AskOption<T>> {
+    fn len(&self) -> usize;
+}
+
+impl<T> Options<T> for [AskOption<T>] {
+    fn len(&self) -> usize {
+        <[AskOption<T>]>::len(self)
+    }
+}
+
+impl<T> Options<T> for Vec<AskOption<T>> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+}
+
+/// the options of a (Multi)Select
+///
+/// copies of a prompt share its options, and ones added to a copy while it's
+/// asked are kept apart from them, so asking never has to copy the lot.
+#[derive(Clone, Debug)]
+pub(crate) struct OptionList<T> {
+    shared: Arc<Vec<AskOption<T>>>,
+    /// options made as they're needed, in place of `shared` when there
+    lazy: Option<Arc<Lazy<T>>>,
+    /// options added since this was copied
+    added: Vec<AskOption<T>>,
+}
+
+impl<T> Default for OptionList<T> {
+    fn default() -> Self {
+        Self {
+            shared: Arc::default(),
+            lazy: None,
+            added: Vec::new(),
+        }
+    }
+}
+
+impl<T> From<Vec<AskOption<T>>> for OptionList<T> {
+    fn from(options: Vec<AskOption<T>>) -> Self {
+        Self {
+            shared: Arc::new(options),
+            lazy: None,
+            added: Vec::new(),
+        }
+    }
+}
+
+impl<T> From<Box<dyn IndexedOptions<T>>> for OptionList<T> {
+    fn from(source: Box<dyn IndexedOptions<T>>) -> Self {
+        Self {
+            shared: Arc::default(),
+            lazy: Some(Arc::new(Lazy::new(source))),
+            added: Vec::new(),
+        }
+    }
+}
+
+impl<T: Clone> OptionList<T> {
+    /// the options to change in place, copied first if another copy of the
+    /// prompt shares them
+    ///
+    /// options that were being made as they're needed are all made now.
+    pub(crate) fn to_mut(&mut self) -> &mut Vec<AskOption<T>> {
+        if let Some(lazy) = self.lazy.take() {
+            self.shared = Arc::new(lazy.all().to_vec());
+        }
+        let shared = Arc::make_mut(&mut self.shared);
+        shared.append(&mut self.added);
+        shared
+    }
+
+    /// the options every copy of the prompt has, leaving out any added while
+    /// asking
+    pub(crate) fn shared(&self) -> &[AskOption<T>] {
+        match self.lazy {
+            Some(ref lazy) => lazy.all(),
+            None => &self.shared,
+        }
+    }
+}
+
+impl<T> OptionList<T> {
+    /// how many options every copy of the prompt has
+    fn shared_len(&self) -> usize {
+        match self.lazy {
+            Some(ref lazy) => lazy.len,
+            None => self.shared.len(),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.shared_len() + self.added.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn get(&self, index: usize) -> Option<&AskOption<T>> {
+        match index.checked_sub(self.shared_len()) {
+            None => match self.lazy {
+                Some(ref lazy) => lazy.get(index),
+                None => self.shared.get(index),
+            },
+            Some(index) => self.added.get(index),
+        }
+    }
+
+    /// add an option to the end, without touching the shared ones
+    pub(crate) fn push(&mut self, option: AskOption<T>) {
+        self.added.push(option);
+    }
+}
+
+impl<T> Index<usize> for OptionList<T> {
+    type Output = AskOption<T>;
+
+    fn index(&self, index: usize) -> &AskOption<T> {
+        match self.get(index) {
+            Some(option) => option,
+            None => panic!(
+                "option index {} out of range for {} options",
+                index,
+                self.len()
+            ),
+        }
+    }
+}
+
+impl<T> Options<T> for OptionList<T> {
+    fn len(&self) -> usize {
+        OptionList::len(self)
+    }
 }
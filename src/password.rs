@@ -1,5 +1,7 @@
 use {
     crate::{
+        keymap::{Action, KeyMap},
+        line::{self, KillRing},
         style::PasswordStyle,
         util::CursorGuard,
         validation::{Validate, run_validator},
@@ -34,6 +36,7 @@ pub struct Password {
     min_length: Option<usize>,
     max_length: Option<usize>,
     allow_escape: bool,
+    keymap: KeyMap,
     show_hints: bool,
     confirmation: Option<String>,
     style: PasswordStyle,
@@ -55,6 +58,7 @@ impl Password {
             min_length: None,
             max_length: None,
             allow_escape: true,
+            keymap: KeyMap::new(),
             show_hints: true,
             confirmation: None,
             style: PasswordStyle::default(),
@@ -118,6 +122,15 @@ impl Password {
         self
     }
 
+    /// set which keys do what
+    ///
+    /// typed characters always go into the input, so only keys with Ctrl or
+    /// Alt, or keys like Enter and Esc, can be bound.
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
     pub fn with_style(mut self, style: PasswordStyle) -> Self {
         self.style = style;
         self
@@ -163,6 +176,7 @@ impl Password {
     fn ask_single(&self, prompt: &str) -> miette::Result<String> {
         let mut input = String::new();
         let mut cursor_pos: usize = 0;
+        let mut kill_ring = KillRing::default();
         let mut revealed = self.display_mode == PasswordDisplayMode::Full;
         let mut error_message: Option<String> = None;
         let mut buf = Vec::with_capacity(4096);
@@ -174,10 +188,11 @@ impl Password {
             event::read().into_diagnostic()?;
         }
 
-        let mut last_render_lines = self.render(
+        let mut input_line = self.render(
             &mut buf,
             prompt,
             &input,
+            cursor_pos,
             revealed,
             error_message.as_deref(),
         )?;
@@ -192,13 +207,18 @@ impl Password {
 
                 error_message = None;
 
-                match self.handle_key(key_event, &mut input, &mut cursor_pos, &mut revealed) {
+                match self.handle_key(
+                    key_event,
+                    &mut input,
+                    &mut cursor_pos,
+                    &mut kill_ring,
+                    &mut revealed,
+                ) {
                     Ok(Some(answer)) => {
                         terminal::disable_raw_mode().into_diagnostic()?;
                         buf.clear();
-                        if last_render_lines > 0 {
-                            queue!(buf, cursor::MoveUp(last_render_lines as u16))
-                                .into_diagnostic()?;
+                        if input_line > 0 {
+                            queue!(buf, cursor::MoveUp(input_line as u16)).into_diagnostic()?;
                         }
                         queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
                         queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
@@ -211,9 +231,8 @@ impl Password {
                     Err(e) if e == "Cancelled" => {
                         terminal::disable_raw_mode().into_diagnostic()?;
                         buf.clear();
-                        if last_render_lines > 0 {
-                            queue!(buf, cursor::MoveUp(last_render_lines as u16))
-                                .into_diagnostic()?;
+                        if input_line > 0 {
+                            queue!(buf, cursor::MoveUp(input_line as u16)).into_diagnostic()?;
                         }
                         queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
                         queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
@@ -227,15 +246,16 @@ impl Password {
                 }
 
                 buf.clear();
-                if last_render_lines > 0 {
-                    queue!(buf, cursor::MoveUp(last_render_lines as u16)).into_diagnostic()?;
+                if input_line > 0 {
+                    queue!(buf, cursor::MoveUp(input_line as u16)).into_diagnostic()?;
                 }
                 queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
                 queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
-                last_render_lines = self.render(
+                input_line = self.render(
                     &mut buf,
                     prompt,
                     &input,
+                    cursor_pos,
                     revealed,
                     error_message.as_deref(),
                 )?;
@@ -250,6 +270,7 @@ impl Password {
         key_event: KeyEvent,
         input: &mut String,
         cursor_pos: &mut usize,
+        kill_ring: &mut KillRing,
         revealed: &mut bool,
     ) -> Result<Option<String>, String> {
         if key_event.modifiers.contains(KeyModifiers::CONTROL)
@@ -259,17 +280,16 @@ impl Password {
             std::process::exit(130);
         }
 
-        if key_event.modifiers.contains(KeyModifiers::CONTROL)
-            && matches!(key_event.code, KeyCode::Char('r'))
-            && self.allow_toggle
-            && self.display_mode == PasswordDisplayMode::Masked
-        {
-            *revealed = !*revealed;
-            return Ok(None);
-        }
+        const ACTIONS: &[Action] = &[Action::Submit, Action::Reveal, Action::Cancel];
 
-        match key_event.code {
-            KeyCode::Enter => {
+        match self.keymap.text_action(&key_event, ACTIONS) {
+            Some(Action::Reveal)
+                if self.allow_toggle && self.display_mode == PasswordDisplayMode::Masked =>
+            {
+                *revealed = !*revealed;
+                Ok(None)
+            }
+            Some(Action::Submit) => {
                 if let Some(min) = self.min_length
                     && input.len() < min
                 {
@@ -286,38 +306,11 @@ impl Password {
                 }
                 Ok(Some(input.clone()))
             }
-            KeyCode::Char(c) => {
-                input.insert(*cursor_pos, c);
-                *cursor_pos += 1;
-                Ok(None)
-            }
-            KeyCode::Backspace if *cursor_pos > 0 => {
-                *cursor_pos -= 1;
-                input.remove(*cursor_pos);
-                Ok(None)
-            }
-            KeyCode::Delete if *cursor_pos < input.len() => {
-                input.remove(*cursor_pos);
-                Ok(None)
-            }
-            KeyCode::Left if *cursor_pos > 0 => {
-                *cursor_pos -= 1;
-                Ok(None)
-            }
-            KeyCode::Right if *cursor_pos < input.len() => {
-                *cursor_pos += 1;
-                Ok(None)
-            }
-            KeyCode::Home => {
-                *cursor_pos = 0;
-                Ok(None)
-            }
-            KeyCode::End => {
-                *cursor_pos = input.len();
+            Some(Action::Cancel) if self.allow_escape => Err("Cancelled".into()),
+            _ => {
+                line::edit_line(key_event, input, cursor_pos, kill_ring, |_| true);
                 Ok(None)
             }
-            KeyCode::Esc if self.allow_escape => Err("Cancelled".into()),
-            _ => Ok(None),
         }
     }
 
@@ -352,11 +345,14 @@ impl Password {
         }
     }
 
+    /// draw the prompt, leaving the cursor where it is in the input, and
+    /// return how many lines are above that
     fn render(
         &self,
         out: &mut impl Write,
         prompt: &str,
         input: &str,
+        cursor_pos: usize,
         revealed: bool,
         error: Option<&str>,
     ) -> miette::Result<usize> {
@@ -381,9 +377,9 @@ impl Password {
             input.to_string()
         } else {
             match self.display_mode {
-                PasswordDisplayMode::Hidden => format!("[{} chars]", input.len()),
+                PasswordDisplayMode::Hidden => format!("[{} chars]", input.chars().count()),
                 PasswordDisplayMode::Masked | PasswordDisplayMode::Full => {
-                    self.mask_char.to_string().repeat(input.len())
+                    self.mask_char.to_string().repeat(input.chars().count())
                 }
             }
         };
@@ -394,6 +390,18 @@ impl Password {
             self.style.input_masked
         };
 
+        let before_cursor = &input[..cursor_pos.min(input.len())];
+        let cursor_column = if input.is_empty() {
+            0
+        } else if revealed {
+            crate::util::visible_width(before_cursor)
+        } else if self.display_mode == PasswordDisplayMode::Hidden {
+            crate::util::visible_width(&display_text)
+        } else {
+            crate::util::visible_width(&self.mask_char.to_string()) * before_cursor.chars().count()
+        };
+
+        let input_line = line_count;
         let line = format!("  {}", display_text.style(input_style));
         line_count += crate::util::writeln_physical(out, &line, tw)?;
 
@@ -429,19 +437,30 @@ impl Password {
 
         if self.show_hints {
             let mut hints = vec![];
-            hints.push("Enter to submit");
-            if self.allow_toggle && self.display_mode == PasswordDisplayMode::Masked {
-                hints.push("Ctrl+R to reveal/hide");
+            if let Some(key) = self.keymap.hint(Action::Submit) {
+                hints.push(format!("{} to submit", key));
+            }
+            if self.allow_toggle
+                && self.display_mode == PasswordDisplayMode::Masked
+                && let Some(key) = self.keymap.hint(Action::Reveal)
+            {
+                hints.push(format!("{} to reveal/hide", key));
             }
-            if self.allow_escape {
-                hints.push("Esc to cancel");
+            if self.allow_escape
+                && let Some(key) = self.keymap.hint(Action::Cancel)
+            {
+                hints.push(format!("{} to cancel", key));
             }
 
             let line = format!("  {}", hints.join(", ").style(self.style.hint));
             line_count += crate::util::writeln_physical(out, &line, tw)?;
         }
 
-        Ok(line_count)
+        queue!(out, cursor::MoveUp((line_count - input_line) as u16)).into_diagnostic()?;
+        queue!(out, cursor::MoveToColumn(2 + cursor_column as u16)).into_diagnostic()?;
+        queue!(out, cursor::Show).into_diagnostic()?;
+
+        Ok(input_line)
     }
 
     fn show_result(&self, out: &mut impl Write, prompt: &str) -> miette::Result<()> {
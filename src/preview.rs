@@ -0,0 +1,154 @@
+use {
+    crate::util::{fit, term_height, writeln_physical},
+    owo_colors::{OwoColorize, Style},
+    std::io::Write,
+};
+
+/// where a (Multi)Select shows its preview pane
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum PreviewPosition {
+    /// beside the options, or below them when the terminal is too narrow
+    #[default]
+    Right,
+    /// below the options
+    Bottom,
+}
+
+/// the narrowest terminal the pane goes beside the options in
+const MIN_SIDE_WIDTH: u16 = 60;
+
+/// the preview text for the option under the cursor, and how far it's been
+/// scrolled
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Pane {
+    index: Option<usize>,
+    lines: Vec<String>,
+    scroll: usize,
+}
+
+impl Pane {
+    /// make the text for the option at `index`, if it isn't the one already
+    /// shown
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - the option under the cursor, if the cursor is on one
+    /// * `text` - makes the preview text for an option index
+    pub(crate) fn update(&mut self, index: Option<usize>, text: impl FnOnce(usize) -> String) {
+        if index == self.index {
+            return;
+        }
+
+        self.index = index;
+        self.scroll = 0;
+        self.lines = match index {
+            Some(index) => text(index)
+                .replace('\t', "    ")
+                .lines()
+                .map(|line| {
+                    line.chars()
+                        .filter(|&c| c == '\x1b' || !c.is_control())
+                        .collect()
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+    }
+
+    pub(crate) fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    pub(crate) fn scroll_down(&mut self) {
+        if self.scroll + 1 < self.lines.len() {
+            self.scroll += 1;
+        }
+    }
+
+    /// whether the pane goes beside the options rather than below them
+    pub(crate) fn beside(position: PreviewPosition, tw: u16) -> bool {
+        position == PreviewPosition::Right && tw >= MIN_SIDE_WIDTH
+    }
+
+    /// write the option rows with the pane beside them
+    ///
+    /// the rows are padded out to at least `page_size` so a short list still
+    /// leaves room for the preview.
+    pub(crate) fn write_beside(
+        &self,
+        out: &mut impl Write,
+        rows: &[String],
+        page_size: usize,
+        style: Style,
+        tw: u16,
+    ) -> miette::Result<usize> {
+        // one column is left free so the line never fills the terminal
+        let right = (tw as usize).saturating_sub(4) / 2;
+        let left = (tw as usize).saturating_sub(4) - right;
+        let height = rows.len().max(page_size);
+        let mut line_count = 0;
+
+        for i in 0..height {
+            let row = rows.get(i).map_or("", String::as_str);
+            let pane = if i == 0 {
+                self.header(height - 1, right).style(style).to_string()
+            } else {
+                self.lines
+                    .get(self.scroll + i - 1)
+                    .map_or(String::new(), |line| fit(line, right))
+            };
+
+            let line = format!("{} {} {}", fit(row, left), "│".style(style), pane);
+            line_count += writeln_physical(out, &line, tw)?;
+        }
+
+        Ok(line_count)
+    }
+
+    /// write the pane on its own, below the options
+    ///
+    /// it takes up to a third of the terminal's height, and nothing at all
+    /// when there's no text.
+    pub(crate) fn write_below(
+        &self,
+        out: &mut impl Write,
+        style: Style,
+        tw: u16,
+    ) -> miette::Result<usize> {
+        if self.lines.is_empty() {
+            return Ok(0);
+        }
+
+        let width = (tw as usize).saturating_sub(4);
+        let height = (term_height() as usize / 3).clamp(3, 15);
+        let mut line_count = 0;
+
+        let line = format!("  {}", self.header(height, width).style(style));
+        line_count += writeln_physical(out, &line, tw)?;
+
+        for line in self.lines.iter().skip(self.scroll).take(height) {
+            let line = format!("  {}", fit(line, width));
+            line_count += writeln_physical(out, &line, tw)?;
+        }
+
+        Ok(line_count)
+    }
+
+    /// the rule above the text, with which lines are shown when they don't
+    /// all fit
+    fn header(&self, height: usize, width: usize) -> String {
+        let label = if self.lines.len() > height || self.scroll > 0 {
+            format!(
+                " {}-{}/{} ",
+                self.scroll + 1,
+                (self.scroll + height).min(self.lines.len()),
+                self.lines.len()
+            )
+        } else {
+            String::new()
+        };
+
+        let rule = width.saturating_sub(label.chars().count() + 1);
+        format!("─{}{}", label, "─".repeat(rule))
+    }
+}
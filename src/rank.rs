@@ -0,0 +1,525 @@
+use {
+    crate::{
+        keymap::{Action, KeyMap},
+        option::AskOption,
+        sort::{Sorted, string_item},
+        style::RankStyle,
+        util::{CursorGuard, fit},
+    },
+    crossterm::{
+        cursor,
+        event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+        queue,
+        terminal::{self, Clear, ClearType},
+    },
+    miette::IntoDiagnostic,
+    owo_colors::OwoColorize,
+    std::io::{Write, stdout},
+};
+
+/// how many cells wide the progress bar is
+const BAR_WIDTH: usize = 20;
+
+/// put items in order by asking which of two comes first, over and over
+///
+/// each item is slotted into the ones already ranked with a binary search,
+/// so ranking `n` items takes about `n log2 n` answers. the two items swap
+/// sides every question so neither side is favoured. once every item is
+/// placed the order is shown, and any answer can still be undone until it's
+/// submitted.
+#[derive(Clone)]
+pub struct Rank<T: Clone> {
+    prompt: String,
+    items: Vec<AskOption<T>>,
+    prompt_prefix: String,
+    help_message: Option<String>,
+    show_hints: bool,
+    show_descriptions: bool,
+    allow_escape: bool,
+    vim_mode: bool,
+    keymap: Option<KeyMap>,
+    style: RankStyle,
+    _cursor_guard: CursorGuard,
+}
+
+/// how far a [`Rank`] has got
+#[derive(Clone, Debug, Default)]
+struct RankState {
+    /// the items placed so far, by where they started, first first
+    ranked: Vec<usize>,
+    /// the item being placed
+    next: usize,
+    /// where in `ranked` the item being placed could still go
+    low: usize,
+    high: usize,
+    /// how many questions have been answered
+    answered: usize,
+}
+
+impl RankState {
+    fn new(len: usize) -> Self {
+        Self {
+            ranked: (0..len.min(1)).collect(),
+            next: 1,
+            low: 0,
+            high: len.min(1),
+            answered: 0,
+        }
+    }
+
+    /// the item being placed and the ranked one it's up against, or `None`
+    /// once every item is placed
+    fn question(&self, len: usize) -> Option<(usize, usize)> {
+        (self.next < len).then(|| (self.next, self.ranked[(self.low + self.high) / 2]))
+    }
+
+    /// narrow down where the item being placed goes, placing it once
+    /// there's only one spot left
+    fn answer(&mut self, placed_first: bool) {
+        let middle = (self.low + self.high) / 2;
+        if placed_first {
+            self.high = middle;
+        } else {
+            self.low = middle + 1;
+        }
+        self.answered += 1;
+
+        if self.low == self.high {
+            self.ranked.insert(self.low, self.next);
+            self.next += 1;
+            self.low = 0;
+            self.high = self.ranked.len();
+        }
+    }
+
+    /// the most questions left to ask
+    fn remaining(&self, len: usize) -> usize {
+        if self.next >= len {
+            return 0;
+        }
+        let current = questions(self.high - self.low + 1);
+        let later: usize = (self.ranked.len() + 1..len - self.next + self.ranked.len())
+            .map(|placed| questions(placed + 1))
+            .sum();
+        current + later
+    }
+}
+
+/// how many questions it takes to pick one of `spots` places
+fn questions(spots: usize) -> usize {
+    (usize::BITS - spots.saturating_sub(1).leading_zeros()) as usize
+}
+
+impl<T: Clone> Rank<T> {
+    pub fn new(prompt: impl Into<String>) -> Self {
+        let _cursor_guard = CursorGuard::new().expect("Failed to initialize cursor guard");
+        Self {
+            prompt: prompt.into(),
+            items: Vec::new(),
+            prompt_prefix: "⇅".into(),
+            help_message: None,
+            show_hints: true,
+            show_descriptions: true,
+            allow_escape: true,
+            vim_mode: false,
+            keymap: None,
+            style: RankStyle::default(),
+            _cursor_guard,
+        }
+    }
+
+    pub fn prompt(&self) -> &str {
+        &self.prompt
+    }
+
+    pub fn items(&self) -> &[AskOption<T>] {
+        &self.items
+    }
+
+    /// rank these options, showing their names and descriptions
+    pub fn with_options(mut self, options: Vec<AskOption<T>>) -> Self {
+        self.items = options;
+        self
+    }
+
+    pub fn with_option(mut self, option: AskOption<T>) -> Self {
+        self.items.push(option);
+        self
+    }
+
+    pub fn with_prompt_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prompt_prefix = prefix.into();
+        self
+    }
+
+    pub fn with_help_message(mut self, message: impl Into<String>) -> Self {
+        self.help_message = Some(message.into());
+        self
+    }
+
+    pub fn with_hints(mut self, enabled: bool) -> Self {
+        self.show_hints = enabled;
+        self
+    }
+
+    pub fn with_descriptions(mut self, enabled: bool) -> Self {
+        self.show_descriptions = enabled;
+        self
+    }
+
+    pub fn with_escape(mut self, allow: bool) -> Self {
+        self.allow_escape = allow;
+        self
+    }
+
+    pub fn with_vim_mode(mut self, enabled: bool) -> Self {
+        self.vim_mode = enabled;
+        self
+    }
+
+    /// set which keys do what, instead of the bindings `with_vim_mode` picks
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.keymap = Some(keymap);
+        self
+    }
+
+    pub fn with_style(mut self, style: RankStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// the values, first first
+    pub fn ask(&self) -> miette::Result<Vec<T>> {
+        Ok(self.ask_sorted()?.values)
+    }
+
+    /// the values, first first, along with where each one started out
+    pub fn ask_sorted(&self) -> miette::Result<Sorted<T>> {
+        if self.items.is_empty() {
+            return Err(miette::miette!("No items provided"));
+        }
+
+        let original_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |panic_info| {
+            let _ = terminal::disable_raw_mode();
+            std::panic::take_hook()(panic_info);
+        }));
+
+        let result = self.ask_internal();
+
+        let _ = std::panic::take_hook();
+        std::panic::set_hook(original_hook);
+
+        result
+    }
+
+    fn ask_internal(&self) -> miette::Result<Sorted<T>> {
+        let mut state = RankState::new(self.items.len());
+        let mut history: Vec<RankState> = Vec::new();
+        let mut error_message: Option<String> = None;
+        let mut buf = Vec::with_capacity(4096);
+        let mut out = stdout();
+
+        terminal::enable_raw_mode().into_diagnostic()?;
+
+        while event::poll(std::time::Duration::from_millis(0)).into_diagnostic()? {
+            event::read().into_diagnostic()?;
+        }
+
+        let mut last_render_lines = 0;
+        let mut result: Result<Option<()>, String> = Ok(None);
+
+        loop {
+            match result {
+                Ok(Some(())) => {
+                    terminal::disable_raw_mode().into_diagnostic()?;
+                    buf.clear();
+                    if last_render_lines > 0 {
+                        queue!(buf, cursor::MoveUp(last_render_lines as u16)).into_diagnostic()?;
+                    }
+                    queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
+                    queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+                    self.show_result(&mut buf, &state.ranked)?;
+                    out.write_all(&buf).into_diagnostic()?;
+                    out.flush().into_diagnostic()?;
+                    return Ok(Sorted {
+                        values: state
+                            .ranked
+                            .iter()
+                            .map(|&i| self.items[i].value.clone())
+                            .collect(),
+                        indices: state.ranked,
+                    });
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    if e == "Cancelled" {
+                        terminal::disable_raw_mode().into_diagnostic()?;
+                        buf.clear();
+                        if last_render_lines > 0 {
+                            queue!(buf, cursor::MoveUp(last_render_lines as u16))
+                                .into_diagnostic()?;
+                        }
+                        queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
+                        queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+                        self.show_error(&mut buf, &e)?;
+                        out.write_all(&buf).into_diagnostic()?;
+                        out.flush().into_diagnostic()?;
+                        return Err(miette::miette!(e));
+                    }
+                    error_message = Some(e);
+                }
+            }
+
+            buf.clear();
+            if last_render_lines > 0 {
+                queue!(buf, cursor::MoveUp(last_render_lines as u16)).into_diagnostic()?;
+            }
+            queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
+            queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+            last_render_lines = self.render(&mut buf, &state, error_message.as_deref())?;
+            out.write_all(&buf).into_diagnostic()?;
+            out.flush().into_diagnostic()?;
+
+            result = loop {
+                if let Event::Key(key_event) = event::read().into_diagnostic()?
+                    && key_event.kind == KeyEventKind::Press
+                {
+                    break self.handle_key(key_event, &mut state, &mut history);
+                }
+            };
+            error_message = None;
+        }
+    }
+
+    const ACTIONS: &[Action] = &[
+        Action::ChooseLeft,
+        Action::ChooseRight,
+        Action::Undo,
+        Action::Submit,
+        Action::Cancel,
+    ];
+
+    /// the item on each side for the current question
+    fn sides(&self, state: &RankState) -> Option<(usize, usize)> {
+        let (placing, against) = state.question(self.items.len())?;
+        if state.answered.is_multiple_of(2) {
+            Some((placing, against))
+        } else {
+            Some((against, placing))
+        }
+    }
+
+    fn handle_key(
+        &self,
+        key_event: KeyEvent,
+        state: &mut RankState,
+        history: &mut Vec<RankState>,
+    ) -> Result<Option<()>, String> {
+        if key_event.modifiers.contains(KeyModifiers::CONTROL)
+            && matches!(key_event.code, KeyCode::Char('c'))
+        {
+            let _ = terminal::disable_raw_mode();
+            std::process::exit(130);
+        }
+
+        let keymap = KeyMap::resolve(self.keymap.as_ref(), self.vim_mode);
+        let sides = self.sides(state);
+
+        match keymap.action(&key_event, Self::ACTIONS) {
+            Some(action @ (Action::ChooseLeft | Action::ChooseRight)) => {
+                if let Some((left, _)) = sides {
+                    let placing_on_left = left == state.next;
+                    history.push(state.clone());
+                    state.answer((action == Action::ChooseLeft) == placing_on_left);
+                }
+                Ok(None)
+            }
+            // the last answer can still be taken back until the order is
+            // submitted
+            Some(Action::Submit) if sides.is_none() => Ok(Some(())),
+            Some(Action::Undo) => match history.pop() {
+                Some(previous) => {
+                    *state = previous;
+                    Ok(None)
+                }
+                None => Err("Nothing to undo".into()),
+            },
+            Some(Action::Cancel) if self.allow_escape => Err("Cancelled".into()),
+            _ => Ok(None),
+        }
+    }
+
+    fn render(
+        &self,
+        out: &mut impl Write,
+        state: &RankState,
+        error: Option<&str>,
+    ) -> miette::Result<usize> {
+        let tw = crate::util::term_width();
+        let mut line_count = 0;
+        let keymap = KeyMap::resolve(self.keymap.as_ref(), self.vim_mode);
+
+        // two columns and the divider, leaving the last column of the
+        // terminal free
+        let width = (tw as usize).saturating_sub(6) / 2;
+
+        let line = format!(
+            "{} {}",
+            self.prompt_prefix.style(self.style.prompt_prefix),
+            self.prompt.style(self.style.prompt),
+        );
+        line_count += crate::util::writeln_physical(out, &line, tw)?;
+
+        if let Some(ref help) = self.help_message {
+            let line = format!("  {}", help.style(self.style.hint));
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        if let Some((left, right)) = self.sides(state) {
+            let names = [(left, Action::ChooseLeft), (right, Action::ChooseRight)].map(
+                |(index, action)| {
+                    let key = keymap.hint(action).unwrap_or_default();
+                    format!(
+                        "{} {}",
+                        key.style(self.style.key),
+                        self.items[index].name.style(self.style.item)
+                    )
+                },
+            );
+            let line = self.columns(&names[0], &names[1], width);
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+
+            if self.show_descriptions
+                && (!self.items[left].description.is_empty()
+                    || !self.items[right].description.is_empty())
+            {
+                let [left, right] = [left, right].map(|index| {
+                    format!(
+                        "  {}",
+                        self.items[index].description.style(self.style.description)
+                    )
+                });
+                let line = self.columns(&left, &right, width);
+                line_count += crate::util::writeln_physical(out, &line, tw)?;
+            }
+        } else {
+            let line = format!("  {}", self.order(&state.ranked).style(self.style.item));
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        let total = state.answered + state.remaining(self.items.len());
+        let done = (state.answered * BAR_WIDTH / total.max(1)).min(BAR_WIDTH);
+        let progress = if state.next < self.items.len() {
+            format!("question {} of about {}", state.answered + 1, total)
+        } else {
+            let plural = if state.answered == 1 { "" } else { "s" };
+            format!("done after {} question{}", state.answered, plural)
+        };
+        let line = format!(
+            "  {}{} {}",
+            "━".repeat(done).style(self.style.progress),
+            "─".repeat(BAR_WIDTH - done).style(self.style.hint),
+            progress.style(self.style.hint)
+        );
+        line_count += crate::util::writeln_physical(out, &line, tw)?;
+
+        if let Some(err) = error {
+            let line = format!(
+                "  {} {}",
+                "✗".style(self.style.error),
+                err.style(self.style.error_hint)
+            );
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        if self.show_hints {
+            let mut hints = vec![];
+
+            if state.next < self.items.len() {
+                if let Some(keys) = keymap.hint_pair(Action::ChooseLeft, Action::ChooseRight) {
+                    hints.push(format!("{} to pick which comes first", keys));
+                }
+            } else if let Some(key) = keymap.hint(Action::Submit) {
+                hints.push(format!("{} to submit", key));
+            }
+
+            if state.answered > 0
+                && let Some(key) = keymap.hint(Action::Undo)
+            {
+                hints.push(format!("{} to undo", key));
+            }
+
+            if self.allow_escape
+                && let Some(key) = keymap.hint(Action::Cancel)
+            {
+                hints.push(format!("{} to cancel", key));
+            }
+
+            let line = format!("  {}", hints.join(", ").style(self.style.hint));
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        Ok(line_count)
+    }
+
+    /// a line with `left` and `right` side by side, each `width` wide
+    fn columns(&self, left: &str, right: &str, width: usize) -> String {
+        format!(
+            "  {} {} {}",
+            fit(left, width),
+            "│".style(self.style.hint),
+            fit(right, width)
+        )
+    }
+
+    fn show_error(&self, out: &mut impl Write, error: &str) -> miette::Result<()> {
+        let line = format!(
+            "{} {}",
+            "✗".style(self.style.error),
+            error.style(self.style.error_hint),
+        );
+        let tw = crate::util::term_width();
+        crate::util::writeln_physical(out, &line, tw)?;
+
+        Ok(())
+    }
+
+    /// the ranked items numbered in order, like `1. a, 2. b`
+    fn order(&self, ranked: &[usize]) -> String {
+        ranked
+            .iter()
+            .enumerate()
+            .map(|(i, &item)| format!("{}. {}", i + 1, self.items[item].name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn show_result(&self, out: &mut impl Write, ranked: &[usize]) -> miette::Result<()> {
+        let result_text = self.order(ranked);
+
+        let line = format!(
+            "{} {} {}",
+            self.prompt_prefix.style(self.style.prompt_prefix),
+            self.prompt.style(self.style.prompt),
+            result_text.style(self.style.key).bold(),
+        );
+        let tw = crate::util::term_width();
+        crate::util::writeln_physical(out, &line, tw)?;
+
+        Ok(())
+    }
+}
+
+impl Rank<String> {
+    /// rank these strings, each one its own value
+    pub fn with_items(mut self, items: Vec<impl Into<String>>) -> Self {
+        self.items = items.into_iter().map(string_item).collect();
+        self
+    }
+
+    pub fn with_item(mut self, item: impl Into<String>) -> Self {
+        self.items.push(string_item(item));
+        self
+    }
+}
@@ -1,6 +1,12 @@
 use {
     crate::{
-        option::AskOption,
+        filter::{self, Filter},
+        keymap::{Action, KeyMap},
+        list::{self, Heading, List, Row},
+        option::{AskOption, IndexedOptions, OptionList},
+        preview::{Pane, PreviewPosition},
+        source::{Fetcher, OptionSource},
+        stream::{self, Stream, TICK},
         style::SelectStyle,
         util::CursorGuard,
         validation::{Validate, run_validator},
@@ -13,14 +19,20 @@ use {
     },
     miette::IntoDiagnostic,
     owo_colors::OwoColorize,
-    std::io::{Write, stdout},
+    std::{
+        borrow::Cow,
+        io::{Write, stdout},
+        process::Command,
+        sync::{Arc, mpsc::Receiver},
+    },
 };
 
 #[derive(Clone)]
 #[allow(clippy::type_complexity)]
 pub struct Select<T: Clone> {
     prompt: String,
-    options: Vec<AskOption<T>>,
+    options: OptionList<T>,
+    headings: Vec<(usize, Heading)>,
     default_index: Option<usize>,
     page_size: usize,
     inline: bool,
@@ -30,6 +42,15 @@ pub struct Select<T: Clone> {
     show_descriptions: bool,
     allow_escape: bool,
     vim_mode: bool,
+    keymap: Option<KeyMap>,
+    filter: bool,
+    number_shortcuts: bool,
+    preview: Option<Arc<dyn Fn(&AskOption<T>) -> String>>,
+    preview_position: PreviewPosition,
+    create: Option<Arc<dyn Fn(&str) -> T>>,
+    create_validation: Option<Box<dyn Validate<str>>>,
+    stream: Option<Stream<T>>,
+    source: Option<Fetcher<T>>,
     style: SelectStyle,
     validation: Option<Box<dyn Validate<usize>>>,
     _cursor_guard: CursorGuard,
@@ -40,7 +61,8 @@ impl<T: Clone> Select<T> {
         let _cursor_guard = CursorGuard::new().expect("Failed to initialize cursor guard");
         Self {
             prompt: prompt.into(),
-            options: Vec::new(),
+            options: OptionList::default(),
+            headings: Vec::new(),
             default_index: None,
             page_size: 10,
             inline: false,
@@ -50,6 +72,15 @@ impl<T: Clone> Select<T> {
             show_descriptions: true,
             allow_escape: true,
             vim_mode: false,
+            keymap: None,
+            filter: false,
+            number_shortcuts: false,
+            preview: None,
+            preview_position: PreviewPosition::default(),
+            create: None,
+            create_validation: None,
+            stream: None,
+            source: None,
             style: SelectStyle::default(),
             validation: None,
             _cursor_guard,
@@ -61,16 +92,61 @@ impl<T: Clone> Select<T> {
     }
 
     pub fn options(&self) -> &[AskOption<T>] {
+        self.options.shared()
+    }
+
+    /// the options as the prompt holds them, without making any that are
+    /// only made when needed
+    #[cfg(feature = "bench")]
+    pub(crate) fn option_list(&self) -> &OptionList<T> {
         &self.options
     }
 
     pub fn with_options(mut self, options: Vec<AskOption<T>>) -> Self {
-        self.options = options;
+        self.options = options.into();
+        self.headings.clear();
+        self
+    }
+
+    /// take options that are only made once they're needed, for lists too
+    /// big to build up front
+    ///
+    /// adding more options with `with_option` or `with_group` makes all of
+    /// them at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - where to get the option at each index
+    pub fn with_indexed_options(mut self, options: impl IndexedOptions<T> + 'static) -> Self {
+        self.options = (Box::new(options) as Box<dyn IndexedOptions<T>>).into();
+        self.headings.clear();
         self
     }
 
     pub fn with_option(mut self, option: AskOption<T>) -> Self {
-        self.options.push(option);
+        self.options.to_mut().push(option);
+        self
+    }
+
+    /// add options under a heading
+    ///
+    /// the heading can't be selected, and options added after the group
+    /// with `with_option` join it.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - the heading shown above the options
+    /// * `options` - the options in the group
+    pub fn with_group(mut self, title: impl Into<String>, options: Vec<AskOption<T>>) -> Self {
+        self.headings
+            .push((self.options.len(), Heading::Group(title.into())));
+        self.options.to_mut().extend(options);
+        self
+    }
+
+    /// draw a line between the options so far and the ones added after
+    pub fn with_separator(mut self) -> Self {
+        self.headings.push((self.options.len(), Heading::Separator));
         self
     }
 
@@ -119,6 +195,115 @@ impl<T: Clone> Select<T> {
         self
     }
 
+    /// set which keys do what, instead of the bindings `with_vim_mode` picks
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.keymap = Some(keymap);
+        self
+    }
+
+    /// narrow the options down by typing, matching names and descriptions
+    /// fuzzily
+    ///
+    /// typed letters go to the filter rather than the keymap, so vim-style
+    /// letter bindings don't apply while it's on.
+    pub fn with_filter(mut self, enabled: bool) -> Self {
+        self.filter = enabled;
+        self
+    }
+
+    /// label the first nine options 1-9, and pick one straight away when
+    /// its number is pressed
+    ///
+    /// the numbers follow the options as they're listed, so with the filter
+    /// on they count the matches, and digits aren't typed into it.
+    pub fn with_number_shortcuts(mut self, enabled: bool) -> Self {
+        self.number_shortcuts = enabled;
+        self
+    }
+
+    /// show text about the option under the cursor in a pane beside the
+    /// options, like fzf's preview window
+    ///
+    /// the text is only made again when the cursor moves to another option,
+    /// and it scrolls separately from the options.
+    ///
+    /// # Arguments
+    ///
+    /// * `preview` - makes the text for an option
+    pub fn with_preview(mut self, preview: impl Fn(&AskOption<T>) -> String + 'static) -> Self {
+        self.preview = Some(Arc::new(preview));
+        self
+    }
+
+    /// put the preview pane below the options instead of beside them
+    pub fn with_preview_position(mut self, position: PreviewPosition) -> Self {
+        self.preview_position = position;
+        self
+    }
+
+    /// offer to make a new option out of the filter text when no option has
+    /// that name, turning the filter on
+    ///
+    /// the new option is named after the text, and its value comes from
+    /// `create`. once something's typed, space goes into the text rather
+    /// than submitting, so names can have spaces in them.
+    ///
+    /// # Arguments
+    ///
+    /// * `create` - makes the value for a new option from its name
+    pub fn with_create(mut self, create: impl Fn(&str) -> T + 'static) -> Self {
+        self.create = Some(Arc::new(create));
+        self.filter = true;
+        self
+    }
+
+    /// check the name of a new option before it's made
+    pub fn with_create_validation(mut self, validation: impl Validate<str> + 'static) -> Self {
+        self.create_validation = Some(Box::new(validation));
+        self
+    }
+
+    /// keep adding options from `receiver` while the prompt is open, until
+    /// every sender is dropped
+    ///
+    /// a spinner shows while options are still coming, and the cursor stays
+    /// on its option as new ones arrive. the options only go to the first
+    /// ask, so asking again is an error.
+    pub fn with_stream(mut self, receiver: Receiver<AskOption<T>>) -> Self {
+        self.stream = Some(Stream::new(receiver));
+        self
+    }
+
+    /// like [`Select::with_stream`], taking options from an iterator that's
+    /// run on another thread
+    pub fn with_stream_iter<I>(mut self, iter: I) -> Self
+    where
+        I: IntoIterator<Item = AskOption<T>>,
+        I::IntoIter: Send + 'static,
+        T: Send + 'static,
+    {
+        self.stream = Some(Stream::from_iter(iter));
+        self
+    }
+
+    /// get the options from `source` as the user types, instead of holding
+    /// them all up front
+    ///
+    /// this turns on filtering, and replaces any options and groups already
+    /// added. the source is asked again once typing pauses, and the options
+    /// it gave last stay up in the meantime.
+    pub fn with_source<S>(mut self, source: S) -> Self
+    where
+        S: OptionSource<T> + 'static,
+        T: Send + 'static,
+    {
+        self.source = Some(Fetcher::new(source));
+        self.options = OptionList::default();
+        self.headings.clear();
+        self.filter = true;
+        self
+    }
+
     pub fn with_style(mut self, style: SelectStyle) -> Self {
         self.style = style;
         self
@@ -144,10 +329,14 @@ impl<T: Clone> Select<T> {
     }
 
     pub fn ask(&self) -> miette::Result<AskOption<T>> {
-        if self.options.is_empty() {
+        if self.options.is_empty() && self.stream.is_none() && self.source.is_none() {
             return Err(miette::miette!("No options provided"));
         }
 
+        if let Some(ref stream) = self.stream {
+            stream.open()?;
+        }
+
         let original_hook = std::panic::take_hook();
         std::panic::set_hook(Box::new(move |panic_info| {
             let _ = terminal::disable_raw_mode();
@@ -156,6 +345,11 @@ impl<T: Clone> Select<T> {
 
         let result = self.ask_internal();
 
+        // a command still printing options isn't needed once there's an answer
+        if let Some(ref stream) = self.stream {
+            stream.stop();
+        }
+
         let _ = std::panic::take_hook();
         std::panic::set_hook(original_hook);
 
@@ -163,14 +357,26 @@ impl<T: Clone> Select<T> {
     }
 
     fn ask_internal(&self) -> miette::Result<AskOption<T>> {
-        let default_index = self.default_index.unwrap_or(0).min(self.options.len() - 1);
-
-        let mut selected = default_index;
-        let mut scroll_offset = 0;
+        // options streamed in or fetched while asking go into a copy, leaving
+        // the prompt's own options as they were. the copy shares those
+        // rather than copying them
+        let mut this = Cow::Borrowed(self);
+
+        let default_index = this
+            .default_index
+            .unwrap_or(0)
+            .min(this.options.len().saturating_sub(1));
+
+        let mut filter = Filter::default();
+        filter.unranked = this.source.is_some();
+        filter.refresh(&this.options);
+        let mut list = this.list(&filter, Some(default_index));
+        let mut pane = Pane::default();
         let mut buf = Vec::with_capacity(4096);
         let mut out = stdout();
 
-        let mut last_render_lines = self.render(&mut buf, selected, scroll_offset)?;
+        this.update_pane(&mut pane, &list);
+        let mut last_render_lines = this.render(&mut buf, &list, &filter, &pane)?;
         out.write_all(&buf).into_diagnostic()?;
         out.flush().into_diagnostic()?;
 
@@ -181,145 +387,308 @@ impl<T: Clone> Select<T> {
         }
 
         loop {
-            if let Event::Key(key_event) = event::read().into_diagnostic()? {
-                if key_event.kind != KeyEventKind::Press {
-                    continue;
+            let key_event = if this.loading() && !event::poll(TICK).into_diagnostic()? {
+                None
+            } else {
+                match event::read().into_diagnostic()? {
+                    Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                        Some(key_event)
+                    }
+                    _ => continue,
                 }
+            };
 
-                match self.handle_key(key_event, &mut selected, &mut scroll_offset) {
-                    Ok(Some(())) => {
-                        terminal::disable_raw_mode().into_diagnostic()?;
-                        buf.clear();
-                        if last_render_lines > 0 {
-                            queue!(buf, cursor::MoveUp(last_render_lines as u16))
-                                .into_diagnostic()?;
-                        }
-                        queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
-                        queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
-
-                        let selected_option = &self.options[selected];
-                        self.show_result(&mut buf, selected_option)?;
-                        out.write_all(&buf).into_diagnostic()?;
-                        out.flush().into_diagnostic()?;
-                        return Ok(selected_option.clone());
+            let received = if this.loading() {
+                this.to_mut().receive(&mut list, &mut filter, &mut pane)
+            } else {
+                Ok(())
+            };
+
+            let result = match (received, key_event) {
+                (Err(e), _) => Err(e),
+                (Ok(()), Some(key_event)) => {
+                    this.handle_key(key_event, &mut list, &mut filter, &mut pane)
+                }
+                (Ok(()), None) => Ok(None),
+            };
+
+            match result {
+                Ok(Some(selected_option)) => {
+                    terminal::disable_raw_mode().into_diagnostic()?;
+                    buf.clear();
+                    if last_render_lines > 0 {
+                        queue!(buf, cursor::MoveUp(last_render_lines as u16)).into_diagnostic()?;
                     }
-                    Ok(None) => {
-                        buf.clear();
-                        if last_render_lines > 0 {
-                            queue!(buf, cursor::MoveUp(last_render_lines as u16))
-                                .into_diagnostic()?;
-                        }
-                        queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
-                        queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
-                        last_render_lines = self.render(&mut buf, selected, scroll_offset)?;
-                        out.write_all(&buf).into_diagnostic()?;
-                        out.flush().into_diagnostic()?;
+                    queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
+                    queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+
+                    this.show_result(&mut buf, &selected_option)?;
+                    out.write_all(&buf).into_diagnostic()?;
+                    out.flush().into_diagnostic()?;
+                    return Ok(selected_option);
+                }
+                Ok(None) => {
+                    if this.source.is_some() {
+                        this.to_mut().request(&filter);
                     }
-                    Err(e) => {
-                        terminal::disable_raw_mode().into_diagnostic()?;
-                        buf.clear();
-                        if last_render_lines > 0 {
-                            queue!(buf, cursor::MoveUp(last_render_lines as u16))
-                                .into_diagnostic()?;
-                        }
-                        queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
-                        queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
-                        self.show_error(&mut buf, &e)?;
-                        out.write_all(&buf).into_diagnostic()?;
-                        out.flush().into_diagnostic()?;
-                        return Err(miette::miette!(e));
+
+                    buf.clear();
+                    if last_render_lines > 0 {
+                        queue!(buf, cursor::MoveUp(last_render_lines as u16)).into_diagnostic()?;
                     }
+                    queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
+                    queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+                    this.update_pane(&mut pane, &list);
+                    last_render_lines = this.render(&mut buf, &list, &filter, &pane)?;
+                    out.write_all(&buf).into_diagnostic()?;
+                    out.flush().into_diagnostic()?;
+                }
+                Err(e) => {
+                    terminal::disable_raw_mode().into_diagnostic()?;
+                    buf.clear();
+                    if last_render_lines > 0 {
+                        queue!(buf, cursor::MoveUp(last_render_lines as u16)).into_diagnostic()?;
+                    }
+                    queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
+                    queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+                    this.show_error(&mut buf, &e)?;
+                    out.write_all(&buf).into_diagnostic()?;
+                    out.flush().into_diagnostic()?;
+                    return Err(miette::miette!(e));
                 }
             }
         }
     }
 
+    const ACTIONS: &[Action] = &[
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveToFirst,
+        Action::MoveToLast,
+        Action::PageUp,
+        Action::PageDown,
+        Action::PreviewUp,
+        Action::PreviewDown,
+        Action::Submit,
+        Action::Cancel,
+    ];
+
+    /// whether the cursor can land on `row`
+    fn focusable(&self, row: &Row) -> bool {
+        match row {
+            Row::Option(m) => !self.options[m.index].disabled,
+            Row::Create => true,
+            _ => false,
+        }
+    }
+
+    /// lay out the rows for `filter`, with the cursor on the option at `index`
+    pub(crate) fn list(&self, filter: &Filter, index: Option<usize>) -> List {
+        let mut list = List::new(filter, &self.headings, self.options.len());
+        if self.create.is_some() && filter.is_new(&self.options) {
+            list.offer_create();
+        }
+        list.focus(index, self.page_size, |row| self.focusable(row));
+        list
+    }
+
+    /// whether options are still arriving from a stream, or being fetched
+    /// from a source
+    fn loading(&self) -> bool {
+        self.stream.as_ref().is_some_and(|stream| !stream.done) || self.fetching()
+    }
+
+    fn fetching(&self) -> bool {
+        self.source.as_ref().is_some_and(Fetcher::busy)
+    }
+
+    /// ask the source for the options matching what's typed, once typing
+    /// pauses
+    fn request(&mut self, filter: &Filter) {
+        if let Some(ref mut source) = self.source {
+            source.request(&filter.query);
+        }
+    }
+
+    /// add any options that have arrived, keeping the cursor where it is
+    ///
+    /// options fetched from a source replace the ones shown, and the cursor
+    /// goes back to the top.
+    fn receive(
+        &mut self,
+        list: &mut List,
+        filter: &mut Filter,
+        pane: &mut Pane,
+    ) -> Result<(), String> {
+        if let Some(ref mut source) = self.source
+            && let Some(options) = source.poll()
+        {
+            self.options = options.into();
+            filter.refresh(&self.options);
+            *list = self.list(filter, None);
+            // the indices are for the new options now, so the preview shown
+            // is for one that's gone
+            *pane = Pane::default();
+        }
+
+        let Some(ref mut stream) = self.stream else {
+            return Ok(());
+        };
+
+        let from = self.options.len();
+        if stream.take(&mut self.options)? {
+            filter.append(&self.options, from);
+            let before = std::mem::take(list);
+            *list = self.list(filter, None);
+            list.keep_place(&before, self.page_size, |row| self.focusable(row));
+        }
+        Ok(())
+    }
+
+    /// make the preview for the option under the cursor, if it changed
+    fn update_pane(&self, pane: &mut Pane, list: &List) {
+        if let Some(ref preview) = self.preview {
+            pane.update(list.current().map(|m| m.index), |index| {
+                preview(&self.options[index])
+            });
+        }
+    }
+
     fn handle_key(
         &self,
         key_event: KeyEvent,
-        selected: &mut usize,
-        scroll_offset: &mut usize,
-    ) -> Result<Option<()>, String> {
+        list: &mut List,
+        filter: &mut Filter,
+        pane: &mut Pane,
+    ) -> Result<Option<AskOption<T>>, String> {
         if key_event.modifiers.contains(KeyModifiers::CONTROL)
             && matches!(key_event.code, KeyCode::Char('c'))
         {
             let _ = terminal::disable_raw_mode();
+            // exiting skips the cleanup in `ask`, which would leave a command
+            // that's still printing options running
+            if let Some(ref stream) = self.stream {
+                stream.stop();
+            }
             std::process::exit(130);
         }
 
-        let total_options = self.options.len();
+        if self.number_shortcuts
+            && let Some(n) = shortcut_number(&key_event)
+        {
+            return match list.option(n - 1) {
+                Some(index) if !self.options[index].disabled => self.validate_and_return(index),
+                _ => Ok(None),
+            };
+        }
 
-        match key_event.code {
-            KeyCode::Up | KeyCode::Char('k') if self.vim_mode || key_event.code == KeyCode::Up => {
-                if *selected > 0 {
-                    *selected -= 1;
-                    if *selected < *scroll_offset {
-                        *scroll_offset = *selected;
-                    }
-                } else {
-                    *selected = total_options - 1;
-                    *scroll_offset = selected.saturating_sub(self.page_size - 1);
-                }
+        let keymap = KeyMap::resolve(self.keymap.as_ref(), self.vim_mode);
+        // once something's typed, Enter still submits, so space can go into
+        // the name of a new option
+        let action = if self.filter && Filter::takes(&key_event, !filter.is_empty()) {
+            None
+        } else {
+            keymap.action(&key_event, Self::ACTIONS)
+        };
+        let focusable = |row: &Row| self.focusable(row);
+
+        match action {
+            Some(Action::MoveUp) => {
+                list.up(self.page_size, focusable);
                 Ok(None)
             }
-            KeyCode::Down | KeyCode::Char('j')
-                if self.vim_mode || key_event.code == KeyCode::Down =>
-            {
-                if *selected < total_options - 1 {
-                    *selected += 1;
-                    if *selected >= *scroll_offset + self.page_size {
-                        *scroll_offset = selected.saturating_sub(self.page_size - 1);
-                    }
-                } else {
-                    *selected = 0;
-                    *scroll_offset = 0;
-                }
+            Some(Action::MoveDown) => {
+                list.down(self.page_size, focusable);
                 Ok(None)
             }
-            KeyCode::Home | KeyCode::Char('g')
-                if self.vim_mode || key_event.code == KeyCode::Home =>
-            {
-                *selected = 0;
-                *scroll_offset = 0;
+            Some(Action::MoveToFirst) => {
+                list.first(focusable);
                 Ok(None)
             }
-            KeyCode::End | KeyCode::Char('G')
-                if self.vim_mode || key_event.code == KeyCode::End =>
-            {
-                *selected = total_options - 1;
-                *scroll_offset = selected.saturating_sub(self.page_size - 1);
+            Some(Action::MoveToLast) => {
+                list.last(focusable);
+                list.scroll_to_cursor(self.page_size);
                 Ok(None)
             }
-            KeyCode::PageUp => {
-                *selected = selected.saturating_sub(self.page_size);
-                *scroll_offset = scroll_offset.saturating_sub(self.page_size);
+            Some(Action::PageUp) => {
+                list.page_up(self.page_size, focusable);
                 Ok(None)
             }
-            KeyCode::PageDown => {
-                *selected = (*selected + self.page_size).min(total_options - 1);
-                if *selected >= *scroll_offset + self.page_size {
-                    *scroll_offset = selected.saturating_sub(self.page_size - 1);
+            Some(Action::PageDown) => {
+                list.page_down(self.page_size, focusable);
+                Ok(None)
+            }
+            Some(Action::PreviewUp) => {
+                pane.scroll_up();
+                Ok(None)
+            }
+            Some(Action::PreviewDown) => {
+                pane.scroll_down();
+                Ok(None)
+            }
+            Some(Action::Submit) => match list.row(list.cursor) {
+                Some(Row::Option(m)) if !self.options[m.index].disabled => {
+                    self.validate_and_return(m.index)
+                }
+                Some(Row::Create) => self.create(&filter.query).map(Some),
+                _ => Ok(None),
+            },
+            Some(Action::Cancel) if self.filter && !filter.is_empty() => {
+                let current = list.current().map(|m| m.index);
+                filter.clear(&self.options);
+                *list = self.list(filter, current);
+                Ok(None)
+            }
+            Some(Action::Cancel) if self.allow_escape => Err("Cancelled".into()),
+            _ if self.filter => {
+                if filter.edit(key_event, &self.options) {
+                    *list = self.list(filter, None);
+                }
+                Ok(None)
+            }
+            _ => {
+                if let KeyCode::Char(c) = key_event.code
+                    && !key_event
+                        .modifiers
+                        .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+                {
+                    list.next_where(self.page_size, |row| match row {
+                        Row::Option(m) => {
+                            focusable(row) && starts_with(&self.options[m.index].name, c)
+                        }
+                        _ => false,
+                    });
                 }
                 Ok(None)
             }
-            KeyCode::Enter | KeyCode::Char(' ') => self.validate_and_return(*selected),
-            KeyCode::Esc if self.allow_escape => Err("Cancelled".into()),
-            _ => Ok(None),
         }
     }
 
-    fn validate_and_return(&self, index: usize) -> Result<Option<()>, String> {
+    fn validate_and_return(&self, index: usize) -> Result<Option<AskOption<T>>, String> {
         if let Some(ref validator) = self.validation {
             run_validator(validator.as_ref(), &index)?;
         }
-        Ok(Some(()))
+        Ok(Some(self.options[index].clone()))
+    }
+
+    /// make a new option named `name`
+    fn create(&self, name: &str) -> Result<AskOption<T>, String> {
+        if let Some(ref validator) = self.create_validation {
+            run_validator(validator.as_ref(), name)?;
+        }
+        let create = self
+            .create
+            .as_ref()
+            .ok_or("Creating options is not enabled")?;
+        Ok(AskOption::with_name(name, create(name)))
     }
 
-    fn render(
+    pub(crate) fn render(
         &self,
         out: &mut impl Write,
-        selected: usize,
-        scroll_offset: usize,
+        list: &List,
+        filter: &Filter,
+        pane: &Pane,
     ) -> miette::Result<usize> {
         let tw = crate::util::term_width();
         let mut line_count = 0;
@@ -346,86 +715,193 @@ impl<T: Clone> Select<T> {
             line_count += crate::util::writeln_physical(out, &line, tw)?;
         }
 
-        let end_offset = (scroll_offset + self.page_size).min(self.options.len());
-        let visible_options = &self.options[scroll_offset..end_offset];
+        if self.filter {
+            let line = if filter.is_empty() {
+                format!("  {}", "/ type to filter".style(self.style.hint))
+            } else {
+                format!(
+                    "  {} {} {}",
+                    "/".style(self.style.hint),
+                    filter.query.style(self.style.prompt),
+                    format!("({}/{})", filter.matches.len(), self.options.len())
+                        .style(self.style.hint)
+                )
+            };
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
 
-        for (i, option) in visible_options.iter().enumerate() {
-            let absolute_index = scroll_offset + i;
-            let is_selected = absolute_index == selected;
+            if list.is_empty() {
+                let line = format!("  {}", "(no matches)".style(self.style.hint));
+                line_count += crate::util::writeln_physical(out, &line, tw)?;
+            }
+        }
 
+        let visible = list.visible(self.page_size);
+        let beside = self.preview.is_some() && Pane::beside(self.preview_position, tw);
+        let mut rows = Vec::with_capacity(visible.len());
+        let mut number = list.count_options(0..visible.start);
+
+        for (row_index, row) in list.rows(visible.clone()) {
+            let m = match row {
+                Row::Option(m) => m,
+                Row::Group(title, _) => {
+                    rows.push(format!("  {}", title.style(self.style.group_header)));
+                    continue;
+                }
+                Row::Separator => {
+                    let width = if beside { tw / 2 } else { tw };
+                    rows.push(format!("  {}", list::rule(width).style(self.style.hint)));
+                    continue;
+                }
+                Row::Create => {
+                    let is_selected = row_index == list.cursor;
+                    let marker = if is_selected { "▸" } else { " " };
+                    let style = if is_selected {
+                        self.style.selected
+                    } else {
+                        self.style.option_name
+                    };
+                    rows.push(format!(
+                        "  {} {}{}",
+                        marker.style(self.style.selected),
+                        if self.number_shortcuts { "  " } else { "" },
+                        format!("Create '{}'", filter.query).style(style)
+                    ));
+                    continue;
+                }
+            };
+            let option = &self.options[m.index];
+            let (name_matched, description_matched) = filter.positions(option);
+            let is_selected = row_index == list.cursor;
             let marker = if is_selected { "▸" } else { " " };
+            number += 1;
+
+            let label = match number {
+                _ if !self.number_shortcuts => String::new(),
+                1..=9 => format!("{} ", number.style(self.style.hint)),
+                _ => "  ".into(),
+            };
+
+            let (name_style, desc_style) = if option.disabled {
+                (self.style.disabled, self.style.disabled)
+            } else if is_selected {
+                (self.style.selected, self.style.selected_description)
+            } else {
+                (self.style.option_name, self.style.option_description)
+            };
+            let reason = match option.disabled_reason {
+                Some(ref reason) => format!(" {}", format!("({})", reason).style(self.style.hint)),
+                None => String::new(),
+            };
+
+            rows.push(format!(
+                "  {} {}{}{}",
+                marker.style(self.style.selected),
+                label,
+                filter::highlight(
+                    &option.name,
+                    &name_matched,
+                    name_style,
+                    self.style.filter_match
+                ),
+                reason
+            ));
 
             if self.show_descriptions && !option.description.is_empty() {
-                let name_style = if is_selected {
-                    self.style.selected
-                } else {
-                    self.style.option_name
-                };
-                let desc_style = if is_selected {
-                    self.style.selected_description
-                } else {
-                    self.style.option_description
-                };
-
-                let line = format!(
-                    "  {} {}",
-                    marker.style(self.style.selected),
-                    option.name.style(name_style)
-                );
-                line_count += crate::util::writeln_physical(out, &line, tw)?;
+                rows.push(format!(
+                    "      {}{}",
+                    if self.number_shortcuts { "  " } else { "" },
+                    filter::highlight(
+                        &option.description,
+                        &description_matched,
+                        desc_style,
+                        self.style.filter_match
+                    )
+                ));
+            }
+        }
 
-                let line = format!("      {}", option.description.style(desc_style));
-                line_count += crate::util::writeln_physical(out, &line, tw)?;
-            } else {
-                let style = if is_selected {
-                    self.style.selected
-                } else {
-                    self.style.option_name
-                };
-
-                let line = format!(
-                    "  {} {}",
-                    marker.style(self.style.selected),
-                    option.name.style(style)
-                );
-                line_count += crate::util::writeln_physical(out, &line, tw)?;
+        if beside {
+            line_count += pane.write_beside(out, &rows, self.page_size, self.style.hint, tw)?;
+        } else {
+            for line in &rows {
+                line_count += crate::util::writeln_physical(out, line, tw)?;
             }
         }
 
-        if scroll_offset > 0 {
+        let above = list.count_options(0..visible.start);
+        if above > 0 {
             let line = format!(
                 "  {}",
-                format!("(↑ {} more above)", scroll_offset).style(self.style.hint)
+                format!("(↑ {} more above)", above).style(self.style.hint)
             );
             line_count += crate::util::writeln_physical(out, &line, tw)?;
         }
 
-        if end_offset < self.options.len() {
+        let below = list.count_options(visible.end..list.len());
+        if below > 0 {
             let line = format!(
                 "  {}",
-                format!("(↓ {} more below)", self.options.len() - end_offset)
-                    .style(self.style.hint)
+                format!("(↓ {} more below)", below).style(self.style.hint)
             );
             line_count += crate::util::writeln_physical(out, &line, tw)?;
         }
 
+        if self.loading() {
+            let status = if self.fetching() {
+                format!("{} searching…", stream::spinner())
+            } else {
+                format!(
+                    "{} loading… ({} so far)",
+                    stream::spinner(),
+                    self.options.len()
+                )
+            };
+            let line = format!("  {}", status.style(self.style.hint));
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        if self.preview.is_some() && !beside {
+            line_count += pane.write_below(out, self.style.hint, tw)?;
+        }
+
         if self.show_hints {
+            let keymap = KeyMap::resolve(self.keymap.as_ref(), self.vim_mode);
             let mut hints = vec![];
 
-            if self.vim_mode {
-                hints.push("j/k or ↑↓ to navigate");
-            } else {
-                hints.push("↑↓ to navigate");
+            if let Some(keys) = keymap.hint_pair(Action::MoveUp, Action::MoveDown) {
+                hints.push(format!("{} to navigate", keys));
             }
 
-            if self.options.len() > self.page_size {
-                hints.push("PgUp/PgDn to scroll");
+            if self.number_shortcuts {
+                match list.count_options(0..list.len()).min(9) {
+                    0 => {}
+                    1 => hints.push("1 to pick".into()),
+                    n => hints.push(format!("1-{} to pick", n)),
+                }
             }
 
-            hints.push("Enter to select");
+            if list.len() > self.page_size
+                && let Some(keys) = keymap.hint_pair(Action::PageUp, Action::PageDown)
+            {
+                hints.push(format!("{} to scroll", keys));
+            }
 
-            if self.allow_escape {
-                hints.push("Esc to cancel");
+            if self.preview.is_some()
+                && let Some(keys) = keymap.hint_pair(Action::PreviewUp, Action::PreviewDown)
+            {
+                hints.push(format!("{} to scroll preview", keys));
+            }
+
+            if let Some(key) = keymap.hint(Action::Submit) {
+                hints.push(format!("{} to select", key));
+            }
+
+            if let Some(key) = keymap.hint(Action::Cancel) {
+                if self.filter && !filter.is_empty() {
+                    hints.push(format!("{} to clear filter", key));
+                } else if self.allow_escape {
+                    hints.push(format!("{} to cancel", key));
+                }
             }
 
             let line = format!("  {}", hints.join(", ").style(self.style.hint));
@@ -460,3 +936,56 @@ impl<T: Clone> Select<T> {
         Ok(())
     }
 }
+
+impl Select<String> {
+    /// run `command` and offer each line it prints as an option, like piping
+    /// it into fzf
+    ///
+    /// a line is split at the first `delimiter` into the option's name and
+    /// description, and the name is the value. a command that can't be
+    /// started is an error here, and one that exits unsuccessfully makes
+    /// `ask` fail with what it printed to stderr. the command is killed if
+    /// the prompt is answered or cancelled before it finishes.
+    ///
+    /// ```rust,ignore
+    /// let mut command = Command::new("git");
+    /// command.args(["branch", "--format=%(refname:short)%09%(subject)"]);
+    /// let branch = Select::from_command("Branch", command, "\t")?.ask()?;
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt` - the question to ask
+    /// * `command` - the command whose stdout lines become options
+    /// * `delimiter` - what separates a line's name from its description
+    pub fn from_command(
+        prompt: impl Into<String>,
+        command: Command,
+        delimiter: &str,
+    ) -> miette::Result<Self> {
+        let mut prompt = Self::new(prompt);
+        prompt.stream = Some(Stream::from_command(command, delimiter)?);
+        Ok(prompt)
+    }
+}
+
+/// the digit 1-9 pressed, if that's what `key_event` is
+fn shortcut_number(key_event: &KeyEvent) -> Option<usize> {
+    match key_event.code {
+        KeyCode::Char(c @ '1'..='9')
+            if !key_event
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+        {
+            c.to_digit(10).map(|n| n as usize)
+        }
+        _ => None,
+    }
+}
+
+/// whether `name` starts with `c`, ignoring case
+fn starts_with(name: &str, c: char) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|first| first.to_lowercase().eq(c.to_lowercase()))
+}
@@ -1,5 +1,8 @@
 use {
     crate::{
+        bitset::BitSet,
+        keymap::{Action, KeyMap},
+        option::AskOption,
         style::SortStyle,
         util::CursorGuard,
         validation::{Validate, run_validator},
@@ -12,16 +15,24 @@ use {
     },
     miette::IntoDiagnostic,
     owo_colors::OwoColorize,
-    std::io::{Write, stdout},
+    std::{
+        io::{Write, stdout},
+        ops::Range,
+    },
 };
 
 /// a rearrangable list of items
+///
+/// grabbing an item lets the cursor keys carry it, a step or a page at a
+/// time or all the way to the top or bottom. marked items are picked up
+/// along with it and move as one block. typing a number and pressing enter
+/// sends the item to that position, and every move can be undone.
 #[derive(Clone)]
-pub struct Sort {
+pub struct Sort<T: Clone> {
     /// the prompt to display on the CLI
     prompt: String,
-    /// the choices to be provided
-    items: Vec<String>,
+    /// the items to be put in order
+    items: Vec<AskOption<T>>,
     /// the character to show before the prompt message
     prompt_prefix: String,
     /// an optional help message
@@ -32,14 +43,150 @@ pub struct Sort {
     show_hints: bool,
     /// whether to show indices
     show_indices: bool,
+    show_descriptions: bool,
     allow_escape: bool,
     vim_mode: bool,
+    keymap: Option<KeyMap>,
     style: SortStyle,
-    validation: Option<Box<dyn Validate<[String]>>>,
+    validation: Option<Box<dyn Validate<[T]>>>,
     _cursor_guard: CursorGuard,
 }
 
-impl Sort {
+/// what a [`Sort`] was answered with
+#[derive(Clone, Debug)]
+pub struct Sorted<T> {
+    /// the values, in the order the user arranged them
+    pub values: Vec<T>,
+    /// where each value was in the items the prompt was given, so
+    /// `values[i]` came from `indices[i]`
+    pub indices: Vec<usize>,
+}
+
+/// the order of a [`Sort`] while it's being answered, and what's picked up
+#[derive(Clone, Debug, Default)]
+struct SortState {
+    /// the items by where they started, in the order they're shown
+    items: Vec<usize>,
+    cursor: usize,
+    scroll: usize,
+    /// the rows being moved, which always include the cursor
+    grabbed: Option<Range<usize>>,
+    /// items, by where they started, to pick up along with the one under the
+    /// cursor
+    marked: BitSet,
+    /// the position typed so far to move to
+    target: String,
+    /// the order before each move, latest last
+    history: Vec<Vec<usize>>,
+}
+
+impl SortState {
+    fn new(len: usize) -> Self {
+        Self {
+            items: (0..len).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// put the cursor on `row`, scrolling to keep it on screen
+    fn move_to(&mut self, row: usize, page_size: usize) {
+        self.cursor = row;
+        if row < self.scroll {
+            self.scroll = row;
+        } else if row >= self.scroll + page_size {
+            self.scroll = row + 1 - page_size;
+        }
+    }
+
+    /// put the cursor back on `item`, wherever it is now
+    fn follow(&mut self, item: usize, page_size: usize) {
+        let row = self.items.iter().position(|&i| i == item).unwrap_or(0);
+        self.move_to(row, page_size);
+    }
+
+    /// pick up the item under the cursor, gathering the marked ones around it
+    /// so they move together
+    fn grab(&mut self, page_size: usize) {
+        self.history.push(self.items.clone());
+
+        let current = self.items[self.cursor];
+        let (block, rest): (Vec<usize>, Vec<usize>) = self
+            .items
+            .iter()
+            .partition(|&&i| i == current || self.marked.contains(i));
+        let before = block.iter().position(|&i| i == current).unwrap_or(0);
+        let start = self.cursor - before;
+
+        self.items = rest;
+        self.items.splice(start..start, block.iter().copied());
+        self.grabbed = Some(start..start + block.len());
+        self.marked.clear();
+        self.follow(current, page_size);
+    }
+
+    /// put down whatever is picked up, forgetting it was ever grabbed if it
+    /// didn't move
+    fn release(&mut self) {
+        if self.grabbed.take().is_some() && self.history.last() == Some(&self.items) {
+            self.history.pop();
+        }
+    }
+
+    /// move the picked up rows so they start at `row`, or as near as they
+    /// can get
+    fn move_block(&mut self, row: usize, page_size: usize) {
+        let Some(block) = self.grabbed.clone() else {
+            return;
+        };
+        let row = row.min(self.items.len() - block.len());
+
+        if row < block.start {
+            self.items[row..block.end].rotate_right(block.len());
+        } else {
+            self.items[block.start..row + block.len()].rotate_left(block.len());
+        }
+        self.grabbed = Some(row..row + block.len());
+        self.move_to(self.cursor - block.start + row, page_size);
+    }
+
+    /// move what's picked up, or the item under the cursor if nothing is, so
+    /// it starts at `row`
+    fn send(&mut self, row: usize, page_size: usize) {
+        if self.grabbed.is_some() {
+            self.move_block(row, page_size);
+        } else {
+            self.history.push(self.items.clone());
+            self.grabbed = Some(self.cursor..self.cursor + 1);
+            self.move_block(row, page_size);
+            self.release();
+        }
+    }
+
+    /// change the whole order, keeping the cursor on its item
+    fn rearrange(&mut self, page_size: usize, rearrange: impl FnOnce(&mut Vec<usize>)) {
+        let current = self.items[self.cursor];
+        self.history.push(self.items.clone());
+        rearrange(&mut self.items);
+        if self.history.last() == Some(&self.items) {
+            self.history.pop();
+        }
+        self.follow(current, page_size);
+    }
+
+    /// go back to the order before the last move, returning whether there
+    /// was one
+    fn undo(&mut self, page_size: usize) -> bool {
+        let Some(items) = self.history.pop() else {
+            return false;
+        };
+        let current = self.items[self.cursor];
+        self.items = items;
+        self.follow(current, page_size);
+        true
+    }
+}
+
+impl<T: Clone> Sort<T> {
     pub fn new(prompt: impl Into<String>) -> Self {
         let _cursor_guard = CursorGuard::new().expect("Failed to initialize cursor guard");
         Self {
@@ -50,8 +197,10 @@ impl Sort {
             page_size: 10,
             show_hints: true,
             show_indices: true,
+            show_descriptions: true,
             allow_escape: true,
             vim_mode: false,
+            keymap: None,
             style: SortStyle::default(),
             validation: None,
             _cursor_guard,
@@ -62,13 +211,18 @@ impl Sort {
         &self.prompt
     }
 
-    pub fn with_items(mut self, items: Vec<impl Into<String>>) -> Self {
-        self.items = items.into_iter().map(|s| s.into()).collect();
+    pub fn items(&self) -> &[AskOption<T>] {
+        &self.items
+    }
+
+    /// put these options in order, showing their names and descriptions
+    pub fn with_options(mut self, options: Vec<AskOption<T>>) -> Self {
+        self.items = options;
         self
     }
 
-    pub fn with_item(mut self, item: impl Into<String>) -> Self {
-        self.items.push(item.into());
+    pub fn with_option(mut self, option: AskOption<T>) -> Self {
+        self.items.push(option);
         self
     }
 
@@ -97,6 +251,11 @@ impl Sort {
         self
     }
 
+    pub fn with_descriptions(mut self, enabled: bool) -> Self {
+        self.show_descriptions = enabled;
+        self
+    }
+
     pub fn with_escape(mut self, allow: bool) -> Self {
         self.allow_escape = allow;
         self
@@ -107,17 +266,31 @@ impl Sort {
         self
     }
 
+    /// set which keys do what, instead of the bindings `with_vim_mode` picks
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.keymap = Some(keymap);
+        self
+    }
+
     pub fn with_style(mut self, style: SortStyle) -> Self {
         self.style = style;
         self
     }
 
-    pub fn with_validation(mut self, validation: impl Validate<[String]> + 'static) -> Self {
+    /// check the values in their new order before accepting them
+    pub fn with_validation(mut self, validation: impl Validate<[T]> + 'static) -> Self {
         self.validation = Some(Box::new(validation));
         self
     }
 
-    pub fn ask(&self) -> miette::Result<Vec<String>> {
+    /// the values in the order the user arranged them
+    pub fn ask(&self) -> miette::Result<Vec<T>> {
+        Ok(self.ask_sorted()?.values)
+    }
+
+    /// the values in the order the user arranged them, along with where
+    /// each one started out
+    pub fn ask_sorted(&self) -> miette::Result<Sorted<T>> {
         if self.items.is_empty() {
             return Err(miette::miette!("No items provided"));
         }
@@ -136,11 +309,8 @@ impl Sort {
         result
     }
 
-    fn ask_internal(&self) -> miette::Result<Vec<String>> {
-        let mut items = self.items.clone();
-        let mut cursor = 0usize;
-        let mut grabbed = false;
-        let mut scroll_offset = 0usize;
+    fn ask_internal(&self) -> miette::Result<Sorted<T>> {
+        let mut state = SortState::new(self.items.len());
         let mut error_message: Option<String> = None;
         let mut buf = Vec::with_capacity(4096);
         let mut out = stdout();
@@ -151,14 +321,7 @@ impl Sort {
             event::read().into_diagnostic()?;
         }
 
-        let mut last_render_lines = self.render(
-            &mut buf,
-            &items,
-            cursor,
-            grabbed,
-            scroll_offset,
-            error_message.as_deref(),
-        )?;
+        let mut last_render_lines = self.render(&mut buf, &state, error_message.as_deref())?;
         out.write_all(&buf).into_diagnostic()?;
         out.flush().into_diagnostic()?;
 
@@ -170,13 +333,7 @@ impl Sort {
 
                 error_message = None;
 
-                match self.handle_key(
-                    key_event,
-                    &mut items,
-                    &mut cursor,
-                    &mut grabbed,
-                    &mut scroll_offset,
-                ) {
+                match self.handle_key(key_event, &mut state) {
                     Ok(Some(())) => {
                         terminal::disable_raw_mode().into_diagnostic()?;
                         buf.clear();
@@ -186,10 +343,13 @@ impl Sort {
                         }
                         queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
                         queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
-                        self.show_result(&mut buf, &items)?;
+                        self.show_result(&mut buf, &state.items)?;
                         out.write_all(&buf).into_diagnostic()?;
                         out.flush().into_diagnostic()?;
-                        return Ok(items);
+                        return Ok(Sorted {
+                            values: self.values(&state.items),
+                            indices: state.items,
+                        });
                     }
                     Ok(None) => {}
                     Err(e) => {
@@ -217,28 +377,30 @@ impl Sort {
                 }
                 queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
                 queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
-                last_render_lines = self.render(
-                    &mut buf,
-                    &items,
-                    cursor,
-                    grabbed,
-                    scroll_offset,
-                    error_message.as_deref(),
-                )?;
+                last_render_lines = self.render(&mut buf, &state, error_message.as_deref())?;
                 out.write_all(&buf).into_diagnostic()?;
                 out.flush().into_diagnostic()?;
             }
         }
     }
 
-    fn handle_key(
-        &self,
-        key_event: KeyEvent,
-        items: &mut [String],
-        cursor: &mut usize,
-        grabbed: &mut bool,
-        scroll_offset: &mut usize,
-    ) -> Result<Option<()>, String> {
+    const ACTIONS: &[Action] = &[
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveToFirst,
+        Action::MoveToLast,
+        Action::PageUp,
+        Action::PageDown,
+        Action::Grab,
+        Action::Mark,
+        Action::SortByName,
+        Action::Reverse,
+        Action::Undo,
+        Action::Submit,
+        Action::Cancel,
+    ];
+
+    fn handle_key(&self, key_event: KeyEvent, state: &mut SortState) -> Result<Option<()>, String> {
         if key_event.modifiers.contains(KeyModifiers::CONTROL)
             && matches!(key_event.code, KeyCode::Char('c'))
         {
@@ -246,91 +408,100 @@ impl Sort {
             std::process::exit(130);
         }
 
-        let total = items.len();
-
-        match key_event.code {
-            KeyCode::Up | KeyCode::Char('k') if self.vim_mode || key_event.code == KeyCode::Up => {
-                if *grabbed && *cursor > 0 {
-                    items.swap(*cursor, *cursor - 1);
-                    *cursor -= 1;
-                } else if !*grabbed && *cursor > 0 {
-                    *cursor -= 1;
-                } else if !*grabbed {
-                    *cursor = total - 1;
-                    *scroll_offset = cursor.saturating_sub(self.page_size - 1);
+        let total = state.items.len();
+        let page_size = self.page_size;
+        let keymap = KeyMap::resolve(self.keymap.as_ref(), self.vim_mode);
+
+        match keymap.action(&key_event, Self::ACTIONS) {
+            Some(Action::MoveUp) => match state.grabbed {
+                Some(ref block) => state.move_block(block.start.saturating_sub(1), page_size),
+                None if state.cursor > 0 => state.move_to(state.cursor - 1, page_size),
+                None => state.move_to(total - 1, page_size),
+            },
+            Some(Action::MoveDown) => match state.grabbed {
+                Some(ref block) => state.move_block(block.start + 1, page_size),
+                None if state.cursor + 1 < total => state.move_to(state.cursor + 1, page_size),
+                None => state.move_to(0, page_size),
+            },
+            Some(Action::MoveToFirst) => match state.grabbed {
+                Some(_) => state.move_block(0, page_size),
+                None => state.move_to(0, page_size),
+            },
+            Some(Action::MoveToLast) => match state.grabbed {
+                Some(_) => state.move_block(total, page_size),
+                None => state.move_to(total - 1, page_size),
+            },
+            Some(Action::PageUp) => match state.grabbed {
+                Some(ref block) => {
+                    state.move_block(block.start.saturating_sub(page_size), page_size)
                 }
-
-                if *cursor < *scroll_offset {
-                    *scroll_offset = *cursor;
+                None => state.move_to(state.cursor.saturating_sub(page_size), page_size),
+            },
+            Some(Action::PageDown) => match state.grabbed {
+                Some(ref block) => state.move_block(block.start + page_size, page_size),
+                None => state.move_to((state.cursor + page_size).min(total - 1), page_size),
+            },
+            Some(Action::Grab) if state.grabbed.is_some() => state.release(),
+            Some(Action::Grab) => state.grab(page_size),
+            Some(Action::Mark) if state.grabbed.is_none() => {
+                let item = state.items[state.cursor];
+                if !state.marked.remove(item) {
+                    state.marked.insert(item);
                 }
-                Ok(None)
             }
-            KeyCode::Down | KeyCode::Char('j')
-                if self.vim_mode || key_event.code == KeyCode::Down =>
-            {
-                if *grabbed && *cursor < total - 1 {
-                    items.swap(*cursor, *cursor + 1);
-                    *cursor += 1;
-                } else if !*grabbed && *cursor < total - 1 {
-                    *cursor += 1;
-                } else if !*grabbed {
-                    *cursor = 0;
-                    *scroll_offset = 0;
-                }
-
-                if *cursor >= *scroll_offset + self.page_size {
-                    *scroll_offset = cursor.saturating_sub(self.page_size - 1);
-                }
-                Ok(None)
+            Some(Action::SortByName) if state.grabbed.is_none() => {
+                state.rearrange(page_size, |items| {
+                    items.sort_by_cached_key(|&i| self.items[i].name.to_lowercase())
+                });
             }
-            KeyCode::Char(' ') => {
-                *grabbed = !*grabbed;
-                Ok(None)
+            Some(Action::Reverse) if state.grabbed.is_none() => {
+                state.rearrange(page_size, |items| items.reverse());
             }
-            KeyCode::Enter => {
-                if *grabbed {
-                    *grabbed = false;
-                    return Ok(None);
-                }
-
-                if let Some(ref validator) = self.validation {
-                    run_validator(validator.as_ref(), items)?;
+            Some(Action::Undo) => {
+                state.release();
+                if !state.undo(page_size) {
+                    return Err("Nothing to undo".into());
                 }
-                Ok(Some(()))
             }
-            KeyCode::Home | KeyCode::Char('g')
-                if !*grabbed && (self.vim_mode || key_event.code == KeyCode::Home) =>
-            {
-                *cursor = 0;
-                *scroll_offset = 0;
-                Ok(None)
-            }
-            KeyCode::End | KeyCode::Char('G')
-                if !*grabbed && (self.vim_mode || key_event.code == KeyCode::End) =>
-            {
-                *cursor = total - 1;
-                *scroll_offset = cursor.saturating_sub(self.page_size - 1);
-                Ok(None)
+            Some(Action::Submit) if !state.target.is_empty() => {
+                let target = std::mem::take(&mut state.target);
+                match target.parse::<usize>() {
+                    Ok(position) if (1..=total).contains(&position) => {
+                        state.send(position - 1, page_size)
+                    }
+                    _ => return Err(format!("Position must be between 1 and {}", total)),
+                }
             }
-            KeyCode::Esc if self.allow_escape => {
-                if *grabbed {
-                    *grabbed = false;
-                    Ok(None)
-                } else {
-                    Err("Cancelled".into())
+            Some(Action::Submit) if state.grabbed.is_some() => state.release(),
+            Some(Action::Submit) => {
+                if let Some(ref validator) = self.validation {
+                    run_validator(validator.as_ref(), self.values(&state.items).as_slice())?;
                 }
+                return Ok(Some(()));
             }
-            _ => Ok(None),
+            Some(Action::Cancel) if !state.target.is_empty() => state.target.clear(),
+            Some(Action::Cancel) if state.grabbed.is_some() => state.release(),
+            Some(Action::Cancel) if self.allow_escape => return Err("Cancelled".into()),
+            _ => match key_event.code {
+                KeyCode::Char(c) if c.is_ascii_digit() => state.target.push(c),
+                KeyCode::Backspace => {
+                    state.target.pop();
+                }
+                _ => {}
+            },
         }
+        Ok(None)
+    }
+
+    /// the values of the items at `order`
+    fn values(&self, order: &[usize]) -> Vec<T> {
+        order.iter().map(|&i| self.items[i].value.clone()).collect()
     }
 
     fn render(
         &self,
         out: &mut impl Write,
-        items: &[String],
-        cursor: usize,
-        grabbed: bool,
-        scroll_offset: usize,
+        state: &SortState,
         error: Option<&str>,
     ) -> miette::Result<usize> {
         let tw = crate::util::term_width();
@@ -348,44 +519,56 @@ impl Sort {
             line_count += crate::util::writeln_physical(out, &line, tw)?;
         }
 
-        let end_offset = (scroll_offset + self.page_size).min(items.len());
-        let visible_items = &items[scroll_offset..end_offset];
+        let scroll_offset = state.scroll;
+        let end_offset = (scroll_offset + self.page_size).min(state.items.len());
+        let visible_items = &state.items[scroll_offset..end_offset];
 
-        for (i, item) in visible_items.iter().enumerate() {
+        for (i, &index) in visible_items.iter().enumerate() {
+            let item = &self.items[index];
             let absolute_index = scroll_offset + i;
-            let is_cursor = absolute_index == cursor;
+            let is_cursor = absolute_index == state.cursor;
+            let is_grabbed = state
+                .grabbed
+                .as_ref()
+                .is_some_and(|block| block.contains(&absolute_index));
 
-            let (marker, item_style) = if is_cursor && grabbed {
+            let (marker, item_style) = if is_grabbed {
                 ("≡", self.style.grabbed)
             } else if is_cursor {
                 ("▸", self.style.cursor)
+            } else if state.marked.contains(index) {
+                ("•", self.style.item)
             } else {
                 (" ", self.style.item)
             };
+            let marker = marker.style(if is_grabbed || state.marked.contains(index) {
+                self.style.grabbed
+            } else {
+                self.style.cursor
+            });
 
-            let line = if self.show_indices {
-                format!(
-                    "  {} {} {}",
-                    marker.style(if is_cursor && grabbed {
-                        self.style.grabbed
-                    } else {
-                        self.style.cursor
-                    }),
-                    format!("{}.", absolute_index + 1).style(self.style.index),
-                    item.style(item_style)
-                )
+            let number = if self.show_indices {
+                format!("{}. ", absolute_index + 1)
             } else {
-                format!(
-                    "  {} {}",
-                    marker.style(if is_cursor && grabbed {
-                        self.style.grabbed
-                    } else {
-                        self.style.cursor
-                    }),
-                    item.style(item_style)
-                )
+                String::new()
             };
+
+            let line = format!(
+                "  {} {}{}",
+                marker,
+                number.style(self.style.index),
+                item.name.style(item_style)
+            );
             line_count += crate::util::writeln_physical(out, &line, tw)?;
+
+            if self.show_descriptions && !item.description.is_empty() {
+                let line = format!(
+                    "    {}{}",
+                    " ".repeat(number.chars().count()),
+                    item.description.style(self.style.description)
+                );
+                line_count += crate::util::writeln_physical(out, &line, tw)?;
+            }
         }
 
         if scroll_offset > 0 {
@@ -396,10 +579,19 @@ impl Sort {
             line_count += crate::util::writeln_physical(out, &line, tw)?;
         }
 
-        if end_offset < items.len() {
+        if end_offset < state.items.len() {
             let line = format!(
                 "  {}",
-                format!("(↓ {} more below)", items.len() - end_offset).style(self.style.hint)
+                format!("(↓ {} more below)", state.items.len() - end_offset).style(self.style.hint)
+            );
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        if !state.target.is_empty() {
+            let line = format!(
+                "  {} {}",
+                "move to position".style(self.style.hint),
+                state.target.style(self.style.prompt)
             );
             line_count += crate::util::writeln_physical(out, &line, tw)?;
         }
@@ -414,19 +606,61 @@ impl Sort {
         }
 
         if self.show_hints {
+            let keymap = KeyMap::resolve(self.keymap.as_ref(), self.vim_mode);
             let mut hints = vec![];
 
-            if self.vim_mode {
-                hints.push("j/k or ↑↓ to navigate");
+            if state.grabbed.is_some() {
+                if let Some(keys) = keymap.hint_pair(Action::MoveUp, Action::MoveDown) {
+                    hints.push(format!("{} to move", keys));
+                }
+
+                if let Some(keys) = keymap.hint_pair(Action::MoveToFirst, Action::MoveToLast) {
+                    hints.push(format!("{} to move to the top/bottom", keys));
+                }
+
+                if let Some(keys) = keymap.hint_pair(Action::PageUp, Action::PageDown) {
+                    hints.push(format!("{} to move a page", keys));
+                }
+
+                hints.push("type a number to move there".into());
+
+                if let Some(key) = keymap.hint(Action::Grab) {
+                    hints.push(format!("{} to release", key));
+                }
             } else {
-                hints.push("↑↓ to navigate");
-            }
+                if let Some(keys) = keymap.hint_pair(Action::MoveUp, Action::MoveDown) {
+                    hints.push(format!("{} to navigate", keys));
+                }
+
+                if let Some(key) = keymap.hint(Action::Grab) {
+                    hints.push(format!("{} to grab", key));
+                }
 
-            hints.push("Space to grab/release");
-            hints.push("Enter to submit");
+                for (action, text) in [
+                    (Action::Mark, "mark"),
+                    (Action::SortByName, "sort by name"),
+                    (Action::Reverse, "reverse"),
+                ] {
+                    if let Some(key) = keymap.hint(action) {
+                        hints.push(format!("{} to {}", key, text));
+                    }
+                }
+
+                if !state.history.is_empty()
+                    && let Some(key) = keymap.hint(Action::Undo)
+                {
+                    hints.push(format!("{} to undo", key));
+                }
+
+                if let Some(key) = keymap.hint(Action::Submit) {
+                    hints.push(format!("{} to submit", key));
+                }
 
-            if self.allow_escape {
-                hints.push("Esc to cancel/release");
+                if self.allow_escape
+                    && let Some(key) = keymap.hint(Action::Cancel)
+                {
+                    hints.push(format!("{} to cancel", key));
+                }
             }
 
             let line = format!("  {}", hints.join(", ").style(self.style.hint));
@@ -448,11 +682,11 @@ impl Sort {
         Ok(())
     }
 
-    fn show_result(&self, out: &mut impl Write, items: &[String]) -> miette::Result<()> {
+    fn show_result(&self, out: &mut impl Write, items: &[usize]) -> miette::Result<()> {
         let result_text = items
             .iter()
             .enumerate()
-            .map(|(i, item)| format!("{}. {}", i + 1, item))
+            .map(|(i, &item)| format!("{}. {}", i + 1, self.items[item].name))
             .collect::<Vec<_>>()
             .join(", ");
 
@@ -468,3 +702,22 @@ impl Sort {
         Ok(())
     }
 }
+
+impl Sort<String> {
+    /// put these strings in order, each one its own value
+    pub fn with_items(mut self, items: Vec<impl Into<String>>) -> Self {
+        self.items = items.into_iter().map(string_item).collect();
+        self
+    }
+
+    pub fn with_item(mut self, item: impl Into<String>) -> Self {
+        self.items.push(string_item(item));
+        self
+    }
+}
+
+/// an option for a plain string, named after it
+pub(crate) fn string_item(name: impl Into<String>) -> AskOption<String> {
+    let name = name.into();
+    AskOption::with_name(name.clone(), name)
+}
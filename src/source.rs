@@ -0,0 +1,178 @@
+use {
+    crate::{filter::fuzzy_match, option::AskOption},
+    std::{
+        sync::{
+            Arc, Mutex, PoisonError,
+            mpsc::{self, Receiver, TryRecvError},
+        },
+        time::{Duration, Instant},
+    },
+};
+
+/// how long typing has to pause before a `Select` asks its source again
+pub(crate) const DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// where a `Select` gets its options when there are too many to load up
+/// front
+///
+/// `fetch` runs on another thread once typing pauses, and the options from
+/// the last fetch stay up until the next one is done. closures taking the
+/// query work as sources too.
+pub trait OptionSource<T>: Send {
+    /// the options matching `query`, in the order to show them
+    fn fetch(&mut self, query: &str) -> Vec<AskOption<T>>;
+}
+
+impl<T, F> OptionSource<T> for F
+where
+    F: FnMut(&str) -> Vec<AskOption<T>> + Send,
+{
+    fn fetch(&mut self, query: &str) -> Vec<AskOption<T>> {
+        self(query)
+    }
+}
+
+/// a source over options already in memory, fuzzy matching them like a
+/// `Select`'s own filter does
+///
+/// handy for trying out a prompt before the real source exists.
+#[derive(Clone, Debug)]
+pub struct MemorySource<T> {
+    options: Vec<AskOption<T>>,
+    limit: Option<usize>,
+    delay: Duration,
+}
+
+impl<T: Clone> MemorySource<T> {
+    pub fn new(options: Vec<AskOption<T>>) -> Self {
+        Self {
+            options,
+            limit: None,
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// return at most `limit` options from a fetch
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// wait this long in each fetch, like a slow database or network would
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+impl<T: Clone + Send> OptionSource<T> for MemorySource<T> {
+    fn fetch(&mut self, query: &str) -> Vec<AskOption<T>> {
+        std::thread::sleep(self.delay);
+
+        let query: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).collect();
+        let mut scored: Vec<(i64, &AskOption<T>)> = self
+            .options
+            .iter()
+            .filter_map(|option| {
+                fuzzy_match(&option.name, &query).map(|(score, _)| (score, option))
+            })
+            .collect();
+        scored.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+
+        scored
+            .into_iter()
+            .take(self.limit.unwrap_or(usize::MAX))
+            .map(|(_, option)| option.clone())
+            .collect()
+    }
+}
+
+/// fetches from an [`OptionSource`] for a `Select`, one query at a time
+#[derive(Clone)]
+pub(crate) struct Fetcher<T> {
+    /// starts a fetch on another thread
+    spawn: Arc<dyn Fn(String) -> Receiver<Vec<AskOption<T>>>>,
+    /// the query to fetch next, and when to
+    pending: Option<(String, Instant)>,
+    /// the fetch under way
+    running: Option<Arc<Mutex<Receiver<Vec<AskOption<T>>>>>>,
+    /// the last query asked for
+    query: String,
+}
+
+impl<T> Fetcher<T> {
+    pub(crate) fn new<S>(source: S) -> Self
+    where
+        S: OptionSource<T> + 'static,
+        T: Send + 'static,
+    {
+        let source = Arc::new(Mutex::new(source));
+        let spawn = move |query: String| {
+            let (sender, receiver) = mpsc::channel();
+            let source = Arc::clone(&source);
+            std::thread::spawn(move || {
+                let options = source
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .fetch(&query);
+                let _ = sender.send(options);
+            });
+            receiver
+        };
+
+        Self {
+            spawn: Arc::new(spawn),
+            pending: Some((String::new(), Instant::now())),
+            running: None,
+            query: String::new(),
+        }
+    }
+
+    /// fetch for `query` once typing has paused, unless it was the last
+    /// query asked for
+    pub(crate) fn request(&mut self, query: &str) {
+        if query != self.query {
+            self.query = query.to_string();
+            self.pending = Some((self.query.clone(), Instant::now() + DEBOUNCE));
+        }
+    }
+
+    /// whether there's a fetch under way or waiting to start
+    pub(crate) fn busy(&self) -> bool {
+        self.pending.is_some() || self.running.is_some()
+    }
+
+    /// the options from a fetch that's finished, starting the next one if
+    /// it's due
+    ///
+    /// results for an older query still come back, since they're closer to
+    /// what's typed than whatever is showing.
+    pub(crate) fn poll(&mut self) -> Option<Vec<AskOption<T>>> {
+        let mut options = None;
+
+        if let Some(ref running) = self.running {
+            let received = running
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .try_recv();
+            match received {
+                Ok(fetched) => {
+                    options = Some(fetched);
+                    self.running = None;
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => self.running = None,
+            }
+        }
+
+        if self.running.is_none()
+            && let Some((_, due)) = self.pending
+            && Instant::now() >= due
+            && let Some((query, _)) = self.pending.take()
+        {
+            self.running = Some(Arc::new(Mutex::new((self.spawn)(query))));
+        }
+
+        options
+    }
+}
@@ -0,0 +1,209 @@
+use {
+    crate::option::{AskOption, OptionList},
+    std::{
+        io::{BufRead, BufReader, Read},
+        process::{Child, Command, Stdio},
+        sync::{
+            Arc, Mutex, PoisonError,
+            atomic::{AtomicBool, Ordering},
+            mpsc::{self, Receiver, TryRecvError},
+        },
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    },
+};
+
+/// how long a (Multi)Select waits for a key before checking for new options
+pub(crate) const TICK: Duration = Duration::from_millis(80);
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// options that are still arriving for a (Multi)Select
+#[derive(Clone)]
+pub(crate) struct Stream<T> {
+    receiver: Arc<Mutex<Receiver<AskOption<T>>>>,
+    /// why the options stopped coming, if something went wrong
+    failure: Arc<Mutex<Option<String>>>,
+    /// the process printing the options, if they come from one
+    child: Option<Arc<Mutex<Child>>>,
+    /// whether a prompt has taken options from this already
+    opened: Arc<AtomicBool>,
+    /// whether everything has arrived
+    pub(crate) done: bool,
+}
+
+impl<T> Stream<T> {
+    pub(crate) fn new(receiver: Receiver<AskOption<T>>) -> Self {
+        Self {
+            receiver: Arc::new(Mutex::new(receiver)),
+            failure: Arc::default(),
+            child: None,
+            opened: Arc::default(),
+            done: false,
+        }
+    }
+
+    /// start taking options for an ask, which only works once since the
+    /// options that arrive aren't kept once that ask is over
+    pub(crate) fn open(&self) -> miette::Result<()> {
+        if self.opened.swap(true, Ordering::Relaxed) {
+            return Err(miette::miette!(
+                "The streamed options were used up by an earlier ask"
+            ));
+        }
+        Ok(())
+    }
+
+    /// pull options out of `iter` on another thread, so a slow iterator
+    /// doesn't hold up the prompt
+    pub(crate) fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = AskOption<T>>,
+        I::IntoIter: Send + 'static,
+        T: Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let iter = iter.into_iter();
+        std::thread::spawn(move || {
+            for option in iter {
+                if sender.send(option).is_err() {
+                    break;
+                }
+            }
+        });
+        Self::new(receiver)
+    }
+
+    /// move whatever has arrived onto the end of `options`, returning whether
+    /// anything did, or why the options stopped coming if it went wrong
+    pub(crate) fn take(&mut self, options: &mut OptionList<T>) -> Result<bool, String> {
+        let receiver = self.receiver.lock().unwrap_or_else(PoisonError::into_inner);
+        let before = options.len();
+
+        loop {
+            match receiver.try_recv() {
+                Ok(option) => options.push(option),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.done = true;
+                    break;
+                }
+            }
+        }
+
+        let failure = self.failure.lock().unwrap_or_else(PoisonError::into_inner);
+        match *failure {
+            Some(ref failure) if self.done => Err(failure.clone()),
+            _ => Ok(options.len() > before),
+        }
+    }
+
+    /// kill the process printing the options, if it's still going
+    pub(crate) fn stop(&self) {
+        if let Some(ref child) = self.child {
+            let _ = child.lock().unwrap_or_else(PoisonError::into_inner).kill();
+        }
+    }
+}
+
+impl Stream<String> {
+    /// run `command`, making an option out of each line it prints
+    ///
+    /// a line is split at the first `delimiter` into the option's name and
+    /// description, and the name is its value too. the command failing to
+    /// start is an error straight away, and exiting unsuccessfully is one
+    /// once its output has been read.
+    pub(crate) fn from_command(mut command: Command, delimiter: &str) -> miette::Result<Self> {
+        let program = command.get_program().to_string_lossy().into_owned();
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| miette::miette!("Failed to run {}: {}", program, e))?;
+
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        let child = Arc::new(Mutex::new(child));
+
+        // stderr is read on its own so the command can't block on it filling
+        // up while stdout is being read
+        let stderr = std::thread::spawn(move || {
+            let mut text = String::new();
+            if let Some(mut stderr) = stderr {
+                let _ = stderr.read_to_string(&mut text);
+            }
+            text
+        });
+
+        let (sender, receiver) = mpsc::channel();
+        let mut stream = Self::new(receiver);
+        stream.child = Some(Arc::clone(&child));
+
+        let failure = Arc::clone(&stream.failure);
+        let delimiter = delimiter.to_string();
+        std::thread::spawn(move || {
+            if let Some(stdout) = stdout {
+                for line in BufReader::new(stdout).lines() {
+                    let Ok(line) = line else {
+                        break;
+                    };
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    if sender.send(parse_line(&line, &delimiter)).is_err() {
+                        break;
+                    }
+                }
+            }
+
+            let status = loop {
+                // the lock is let go between checks so the prompt can kill
+                // a command that closed stdout without exiting
+                let status = child
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .try_wait();
+                match status {
+                    Ok(Some(status)) => break Ok(status),
+                    Ok(None) => std::thread::sleep(TICK),
+                    Err(e) => break Err(e),
+                }
+            };
+            let stderr = stderr.join().unwrap_or_default();
+
+            let message = match status {
+                Ok(status) if status.success() => None,
+                Ok(status) => Some(match stderr.trim() {
+                    "" => format!("{} failed: {}", program, status),
+                    stderr => format!("{} failed: {}: {}", program, status, stderr),
+                }),
+                Err(e) => Some(format!("{} failed: {}", program, e)),
+            };
+            *failure.lock().unwrap_or_else(PoisonError::into_inner) = message;
+
+            // the sender goes last, so the failure is there by the time the
+            // prompt sees the options have stopped
+            drop(sender);
+        });
+
+        Ok(stream)
+    }
+}
+
+fn parse_line(line: &str, delimiter: &str) -> AskOption<String> {
+    match line.split_once(delimiter) {
+        Some((name, description)) if !delimiter.is_empty() => {
+            let name = name.trim();
+            AskOption::new(name, description.trim(), name.to_string())
+        }
+        _ => AskOption::with_name(line, line.to_string()),
+    }
+}
+
+/// the frame of the loading spinner to show now
+pub(crate) fn spinner() -> char {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis());
+    SPINNER[(millis / TICK.as_millis()) as usize % SPINNER.len()]
+}
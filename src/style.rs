@@ -387,6 +387,9 @@ pub struct SelectStyle {
     pub selected_description: Style,
     pub error: Style,
     pub error_hint: Style,
+    pub filter_match: Style,
+    pub group_header: Style,
+    pub disabled: Style,
 }
 
 impl Default for SelectStyle {
@@ -407,6 +410,9 @@ impl SelectStyle {
             selected_description: Style::new().fg::<CustomColor<166, 227, 161>>(),
             error: Style::new().fg::<CustomColor<243, 139, 168>>().bold(),
             error_hint: Style::new().fg::<CustomColor<147, 153, 178>>(),
+            filter_match: Style::new().fg::<CustomColor<249, 226, 175>>().bold(),
+            group_header: Style::new().fg::<CustomColor<203, 166, 247>>().bold(),
+            disabled: Style::new().fg::<CustomColor<108, 112, 134>>().dimmed(),
         }
     }
 
@@ -421,6 +427,9 @@ impl SelectStyle {
             selected_description: Style::new().fg::<CustomColor<166, 209, 137>>(),
             error: Style::new().fg::<CustomColor<231, 130, 132>>().bold(),
             error_hint: Style::new().fg::<CustomColor<165, 173, 206>>(),
+            filter_match: Style::new().fg::<CustomColor<229, 200, 144>>().bold(),
+            group_header: Style::new().fg::<CustomColor<202, 158, 230>>().bold(),
+            disabled: Style::new().fg::<CustomColor<115, 121, 148>>().dimmed(),
         }
     }
 
@@ -435,6 +444,9 @@ impl SelectStyle {
             selected_description: Style::new().fg::<CustomColor<166, 218, 149>>(),
             error: Style::new().fg::<CustomColor<237, 135, 150>>().bold(),
             error_hint: Style::new().fg::<CustomColor<153, 160, 187>>(),
+            filter_match: Style::new().fg::<CustomColor<238, 212, 159>>().bold(),
+            group_header: Style::new().fg::<CustomColor<198, 160, 246>>().bold(),
+            disabled: Style::new().fg::<CustomColor<110, 115, 141>>().dimmed(),
         }
     }
 
@@ -449,6 +461,9 @@ impl SelectStyle {
             selected_description: Style::new().fg::<CustomColor<64, 160, 43>>(),
             error: Style::new().fg::<CustomColor<210, 15, 57>>().bold(),
             error_hint: Style::new().fg::<CustomColor<124, 127, 147>>(),
+            filter_match: Style::new().fg::<CustomColor<223, 142, 29>>().bold(),
+            group_header: Style::new().fg::<CustomColor<136, 57, 239>>().bold(),
+            disabled: Style::new().fg::<CustomColor<156, 160, 176>>().dimmed(),
         }
     }
 
@@ -463,6 +478,9 @@ impl SelectStyle {
             selected_description: Style::new(),
             error: Style::new().bold(),
             error_hint: Style::new().dimmed(),
+            filter_match: Style::new().bold().underline(),
+            group_header: Style::new().bold(),
+            disabled: Style::new().dimmed(),
         }
     }
 }
@@ -477,11 +495,15 @@ pub struct MultiSelectStyle {
     pub cursor: Style,
     pub cursor_description: Style,
     pub selected: Style,
+    pub grabbed: Style,
     pub checkbox_selected: Style,
     pub checkbox_unselected: Style,
     pub selection_count: Style,
     pub error: Style,
     pub error_hint: Style,
+    pub filter_match: Style,
+    pub group_header: Style,
+    pub disabled: Style,
 }
 
 impl Default for MultiSelectStyle {
@@ -501,11 +523,15 @@ impl MultiSelectStyle {
             cursor: Style::new().fg::<CustomColor<137, 180, 250>>().bold(),
             cursor_description: Style::new().fg::<CustomColor<166, 227, 161>>(),
             selected: Style::new().fg::<CustomColor<166, 227, 161>>(),
+            grabbed: Style::new().fg::<CustomColor<166, 227, 161>>().bold(),
             checkbox_selected: Style::new().fg::<CustomColor<166, 227, 161>>(),
             checkbox_unselected: Style::new().fg::<CustomColor<127, 132, 156>>(),
             selection_count: Style::new().fg::<CustomColor<186, 194, 222>>().dimmed(),
             error: Style::new().fg::<CustomColor<243, 139, 168>>().bold(),
             error_hint: Style::new().fg::<CustomColor<147, 153, 178>>(),
+            filter_match: Style::new().fg::<CustomColor<249, 226, 175>>().bold(),
+            group_header: Style::new().fg::<CustomColor<203, 166, 247>>().bold(),
+            disabled: Style::new().fg::<CustomColor<108, 112, 134>>().dimmed(),
         }
     }
 
@@ -519,11 +545,15 @@ impl MultiSelectStyle {
             cursor: Style::new().fg::<CustomColor<140, 170, 238>>().bold(),
             cursor_description: Style::new().fg::<CustomColor<166, 209, 137>>(),
             selected: Style::new().fg::<CustomColor<166, 209, 137>>(),
+            grabbed: Style::new().fg::<CustomColor<166, 209, 137>>().bold(),
             checkbox_selected: Style::new().fg::<CustomColor<166, 209, 137>>(),
             checkbox_unselected: Style::new().fg::<CustomColor<131, 139, 167>>(),
             selection_count: Style::new().fg::<CustomColor<181, 191, 226>>().dimmed(),
             error: Style::new().fg::<CustomColor<231, 130, 132>>().bold(),
             error_hint: Style::new().fg::<CustomColor<165, 173, 206>>(),
+            filter_match: Style::new().fg::<CustomColor<229, 200, 144>>().bold(),
+            group_header: Style::new().fg::<CustomColor<202, 158, 230>>().bold(),
+            disabled: Style::new().fg::<CustomColor<115, 121, 148>>().dimmed(),
         }
     }
 
@@ -537,11 +567,15 @@ impl MultiSelectStyle {
             cursor: Style::new().fg::<CustomColor<138, 173, 244>>().bold(),
             cursor_description: Style::new().fg::<CustomColor<166, 218, 149>>(),
             selected: Style::new().fg::<CustomColor<166, 218, 149>>(),
+            grabbed: Style::new().fg::<CustomColor<166, 218, 149>>().bold(),
             checkbox_selected: Style::new().fg::<CustomColor<166, 218, 149>>(),
             checkbox_unselected: Style::new().fg::<CustomColor<128, 135, 162>>(),
             selection_count: Style::new().fg::<CustomColor<184, 192, 224>>().dimmed(),
             error: Style::new().fg::<CustomColor<237, 135, 150>>().bold(),
             error_hint: Style::new().fg::<CustomColor<153, 160, 187>>(),
+            filter_match: Style::new().fg::<CustomColor<238, 212, 159>>().bold(),
+            group_header: Style::new().fg::<CustomColor<198, 160, 246>>().bold(),
+            disabled: Style::new().fg::<CustomColor<110, 115, 141>>().dimmed(),
         }
     }
 
@@ -555,11 +589,15 @@ impl MultiSelectStyle {
             cursor: Style::new().fg::<CustomColor<30, 102, 245>>().bold(),
             cursor_description: Style::new().fg::<CustomColor<64, 160, 43>>(),
             selected: Style::new().fg::<CustomColor<64, 160, 43>>(),
+            grabbed: Style::new().fg::<CustomColor<64, 160, 43>>().bold(),
             checkbox_selected: Style::new().fg::<CustomColor<64, 160, 43>>(),
             checkbox_unselected: Style::new().fg::<CustomColor<156, 160, 176>>(),
             selection_count: Style::new().fg::<CustomColor<92, 95, 119>>().dimmed(),
             error: Style::new().fg::<CustomColor<210, 15, 57>>().bold(),
             error_hint: Style::new().fg::<CustomColor<124, 127, 147>>(),
+            filter_match: Style::new().fg::<CustomColor<223, 142, 29>>().bold(),
+            group_header: Style::new().fg::<CustomColor<136, 57, 239>>().bold(),
+            disabled: Style::new().fg::<CustomColor<156, 160, 176>>().dimmed(),
         }
     }
 
@@ -573,11 +611,15 @@ impl MultiSelectStyle {
             cursor: Style::new().bold(),
             cursor_description: Style::new(),
             selected: Style::new(),
+            grabbed: Style::new().bold(),
             checkbox_selected: Style::new(),
             checkbox_unselected: Style::new().dimmed(),
             selection_count: Style::new().dimmed(),
             error: Style::new().bold(),
             error_hint: Style::new().dimmed(),
+            filter_match: Style::new().bold().underline(),
+            group_header: Style::new().bold(),
+            disabled: Style::new().dimmed(),
         }
     }
 }
@@ -588,6 +630,7 @@ pub struct SortStyle {
     pub prompt_prefix: Style,
     pub hint: Style,
     pub item: Style,
+    pub description: Style,
     pub cursor: Style,
     pub grabbed: Style,
     pub index: Style,
@@ -608,6 +651,7 @@ impl SortStyle {
             prompt_prefix: Style::new().fg::<CustomColor<137, 180, 250>>(),
             hint: Style::new().fg::<CustomColor<127, 132, 156>>(),
             item: Style::new().fg::<CustomColor<186, 194, 222>>(),
+            description: Style::new().fg::<CustomColor<147, 153, 178>>(),
             cursor: Style::new().fg::<CustomColor<137, 180, 250>>().bold(),
             grabbed: Style::new().fg::<CustomColor<166, 227, 161>>().bold(),
             index: Style::new().fg::<CustomColor<147, 153, 178>>().dimmed(),
@@ -622,6 +666,7 @@ impl SortStyle {
             prompt_prefix: Style::new().fg::<CustomColor<140, 170, 238>>(),
             hint: Style::new().fg::<CustomColor<131, 139, 167>>(),
             item: Style::new().fg::<CustomColor<181, 191, 226>>(),
+            description: Style::new().fg::<CustomColor<165, 173, 206>>(),
             cursor: Style::new().fg::<CustomColor<140, 170, 238>>().bold(),
             grabbed: Style::new().fg::<CustomColor<166, 209, 137>>().bold(),
             index: Style::new().fg::<CustomColor<165, 173, 206>>().dimmed(),
@@ -636,6 +681,7 @@ impl SortStyle {
             prompt_prefix: Style::new().fg::<CustomColor<138, 173, 244>>(),
             hint: Style::new().fg::<CustomColor<128, 135, 162>>(),
             item: Style::new().fg::<CustomColor<184, 192, 224>>(),
+            description: Style::new().fg::<CustomColor<153, 160, 187>>(),
             cursor: Style::new().fg::<CustomColor<138, 173, 244>>().bold(),
             grabbed: Style::new().fg::<CustomColor<166, 218, 149>>().bold(),
             index: Style::new().fg::<CustomColor<153, 160, 187>>().dimmed(),
@@ -650,6 +696,7 @@ impl SortStyle {
             prompt_prefix: Style::new().fg::<CustomColor<30, 102, 245>>(),
             hint: Style::new().fg::<CustomColor<156, 160, 176>>(),
             item: Style::new().fg::<CustomColor<92, 95, 119>>(),
+            description: Style::new().fg::<CustomColor<124, 127, 147>>(),
             cursor: Style::new().fg::<CustomColor<30, 102, 245>>().bold(),
             grabbed: Style::new().fg::<CustomColor<64, 160, 43>>().bold(),
             index: Style::new().fg::<CustomColor<124, 127, 147>>().dimmed(),
@@ -664,6 +711,7 @@ impl SortStyle {
             prompt_prefix: Style::new().bold(),
             hint: Style::new().dimmed(),
             item: Style::new(),
+            description: Style::new().dimmed(),
             cursor: Style::new().bold(),
             grabbed: Style::new().bold(),
             index: Style::new().dimmed(),
@@ -673,6 +721,142 @@ impl SortStyle {
     }
 }
 
+/// the look of a [`crate::TransferList`], which takes its list colors from a
+/// [`MultiSelectStyle`] and its ordering colors from a [`SortStyle`]
+#[derive(Clone)]
+pub struct TransferStyle {
+    pub prompt: Style,
+    pub prompt_prefix: Style,
+    pub hint: Style,
+    pub item: Style,
+    pub cursor: Style,
+    /// the title of the side the cursor is in
+    pub title: Style,
+    /// the title of the other side
+    pub title_inactive: Style,
+    /// the position numbers on the selected side
+    pub index: Style,
+    /// an item being moved up or down
+    pub moved: Style,
+    pub selected: Style,
+    pub filter_match: Style,
+    pub disabled: Style,
+    pub error: Style,
+    pub error_hint: Style,
+}
+
+impl Default for TransferStyle {
+    fn default() -> Self {
+        Self::mocha()
+    }
+}
+
+impl TransferStyle {
+    /// # Arguments
+    ///
+    /// * `multiselect` - the colors for the items, titles and filter
+    /// * `sort` - the colors for the position numbers and moving items
+    pub fn from_styles(multiselect: &MultiSelectStyle, sort: &SortStyle) -> Self {
+        Self {
+            prompt: multiselect.prompt,
+            prompt_prefix: multiselect.prompt_prefix,
+            hint: multiselect.hint,
+            item: multiselect.option_name,
+            cursor: multiselect.cursor,
+            title: multiselect.group_header,
+            title_inactive: multiselect.hint,
+            index: sort.index,
+            moved: sort.grabbed,
+            selected: multiselect.selected,
+            filter_match: multiselect.filter_match,
+            disabled: multiselect.disabled,
+            error: multiselect.error,
+            error_hint: multiselect.error_hint,
+        }
+    }
+
+    pub fn mocha() -> Self {
+        Self::from_styles(&MultiSelectStyle::mocha(), &SortStyle::mocha())
+    }
+
+    pub fn frappe() -> Self {
+        Self::from_styles(&MultiSelectStyle::frappe(), &SortStyle::frappe())
+    }
+
+    pub fn macchiato() -> Self {
+        Self::from_styles(&MultiSelectStyle::macchiato(), &SortStyle::macchiato())
+    }
+
+    pub fn latte() -> Self {
+        Self::from_styles(&MultiSelectStyle::latte(), &SortStyle::latte())
+    }
+
+    pub fn minimal() -> Self {
+        Self::from_styles(&MultiSelectStyle::minimal(), &SortStyle::minimal())
+    }
+}
+
+/// the look of a [`crate::Rank`], which takes its colors from a [`SortStyle`]
+#[derive(Clone)]
+pub struct RankStyle {
+    pub prompt: Style,
+    pub prompt_prefix: Style,
+    pub hint: Style,
+    pub item: Style,
+    pub description: Style,
+    /// the key shown before each of the two items
+    pub key: Style,
+    /// the done part of the progress bar
+    pub progress: Style,
+    pub error: Style,
+    pub error_hint: Style,
+}
+
+impl Default for RankStyle {
+    fn default() -> Self {
+        Self::mocha()
+    }
+}
+
+impl RankStyle {
+    /// # Arguments
+    ///
+    /// * `sort` - the colors to match
+    pub fn from_style(sort: &SortStyle) -> Self {
+        Self {
+            prompt: sort.prompt,
+            prompt_prefix: sort.prompt_prefix,
+            hint: sort.hint,
+            item: sort.item,
+            description: sort.description,
+            key: sort.cursor,
+            progress: sort.grabbed,
+            error: sort.error,
+            error_hint: sort.error_hint,
+        }
+    }
+
+    pub fn mocha() -> Self {
+        Self::from_style(&SortStyle::mocha())
+    }
+
+    pub fn frappe() -> Self {
+        Self::from_style(&SortStyle::frappe())
+    }
+
+    pub fn macchiato() -> Self {
+        Self::from_style(&SortStyle::macchiato())
+    }
+
+    pub fn latte() -> Self {
+        Self::from_style(&SortStyle::latte())
+    }
+
+    pub fn minimal() -> Self {
+        Self::from_style(&SortStyle::minimal())
+    }
+}
+
 #[derive(Clone)]
 pub struct EditorStyle {
     pub prompt: Style,
@@ -752,6 +936,121 @@ impl EditorStyle {
     }
 }
 
+#[derive(Clone)]
+pub struct DiffConfirmStyle {
+    pub prompt: Style,
+    pub prompt_prefix: Style,
+    pub hint: Style,
+    pub added: Style,
+    pub removed: Style,
+    pub context: Style,
+    pub hunk_header: Style,
+    pub line_number: Style,
+    pub yes_style: Style,
+    pub no_style: Style,
+    pub error: Style,
+    pub error_hint: Style,
+    pub error_prefix: Option<String>,
+}
+
+impl Default for DiffConfirmStyle {
+    fn default() -> Self {
+        Self::mocha()
+    }
+}
+
+impl DiffConfirmStyle {
+    pub fn mocha() -> Self {
+        Self {
+            prompt: Style::new().fg::<CustomColor<205, 214, 244>>(),
+            prompt_prefix: Style::new().fg::<CustomColor<137, 180, 250>>(),
+            hint: Style::new().fg::<CustomColor<127, 132, 156>>(),
+            added: Style::new().fg::<CustomColor<166, 227, 161>>(),
+            removed: Style::new().fg::<CustomColor<243, 139, 168>>(),
+            context: Style::new().fg::<CustomColor<186, 194, 222>>(),
+            hunk_header: Style::new().fg::<CustomColor<203, 166, 247>>().bold(),
+            line_number: Style::new().fg::<CustomColor<127, 132, 156>>().dimmed(),
+            yes_style: Style::new().fg::<CustomColor<166, 227, 161>>(),
+            no_style: Style::new().fg::<CustomColor<243, 139, 168>>(),
+            error: Style::new().fg::<CustomColor<243, 139, 168>>().bold(),
+            error_hint: Style::new().fg::<CustomColor<147, 153, 178>>(),
+            error_prefix: Some("✗".into()),
+        }
+    }
+
+    pub fn frappe() -> Self {
+        Self {
+            prompt: Style::new().fg::<CustomColor<198, 208, 245>>(),
+            prompt_prefix: Style::new().fg::<CustomColor<140, 170, 238>>(),
+            hint: Style::new().fg::<CustomColor<131, 139, 167>>(),
+            added: Style::new().fg::<CustomColor<166, 209, 137>>(),
+            removed: Style::new().fg::<CustomColor<231, 130, 132>>(),
+            context: Style::new().fg::<CustomColor<181, 191, 226>>(),
+            hunk_header: Style::new().fg::<CustomColor<202, 158, 230>>().bold(),
+            line_number: Style::new().fg::<CustomColor<131, 139, 167>>().dimmed(),
+            yes_style: Style::new().fg::<CustomColor<166, 209, 137>>(),
+            no_style: Style::new().fg::<CustomColor<231, 130, 132>>(),
+            error: Style::new().fg::<CustomColor<231, 130, 132>>().bold(),
+            error_hint: Style::new().fg::<CustomColor<165, 173, 206>>(),
+            error_prefix: Some("✗".into()),
+        }
+    }
+
+    pub fn macchiato() -> Self {
+        Self {
+            prompt: Style::new().fg::<CustomColor<202, 211, 245>>(),
+            prompt_prefix: Style::new().fg::<CustomColor<138, 173, 244>>(),
+            hint: Style::new().fg::<CustomColor<128, 135, 162>>(),
+            added: Style::new().fg::<CustomColor<166, 218, 149>>(),
+            removed: Style::new().fg::<CustomColor<237, 135, 150>>(),
+            context: Style::new().fg::<CustomColor<184, 192, 224>>(),
+            hunk_header: Style::new().fg::<CustomColor<198, 160, 246>>().bold(),
+            line_number: Style::new().fg::<CustomColor<128, 135, 162>>().dimmed(),
+            yes_style: Style::new().fg::<CustomColor<166, 218, 149>>(),
+            no_style: Style::new().fg::<CustomColor<237, 135, 150>>(),
+            error: Style::new().fg::<CustomColor<237, 135, 150>>().bold(),
+            error_hint: Style::new().fg::<CustomColor<153, 160, 187>>(),
+            error_prefix: Some("✗".into()),
+        }
+    }
+
+    pub fn latte() -> Self {
+        Self {
+            prompt: Style::new().fg::<CustomColor<76, 79, 105>>(),
+            prompt_prefix: Style::new().fg::<CustomColor<30, 102, 245>>(),
+            hint: Style::new().fg::<CustomColor<156, 160, 176>>(),
+            added: Style::new().fg::<CustomColor<64, 160, 43>>(),
+            removed: Style::new().fg::<CustomColor<210, 15, 57>>(),
+            context: Style::new().fg::<CustomColor<92, 95, 119>>(),
+            hunk_header: Style::new().fg::<CustomColor<136, 57, 239>>().bold(),
+            line_number: Style::new().fg::<CustomColor<156, 160, 176>>().dimmed(),
+            yes_style: Style::new().fg::<CustomColor<64, 160, 43>>(),
+            no_style: Style::new().fg::<CustomColor<210, 15, 57>>(),
+            error: Style::new().fg::<CustomColor<210, 15, 57>>().bold(),
+            error_hint: Style::new().fg::<CustomColor<124, 127, 147>>(),
+            error_prefix: Some("✗".into()),
+        }
+    }
+
+    pub fn minimal() -> Self {
+        Self {
+            prompt: Style::new(),
+            prompt_prefix: Style::new().bold(),
+            hint: Style::new().dimmed(),
+            added: Style::new().bold(),
+            removed: Style::new().dimmed(),
+            context: Style::new(),
+            hunk_header: Style::new().bold(),
+            line_number: Style::new().dimmed(),
+            yes_style: Style::new(),
+            no_style: Style::new(),
+            error: Style::new().bold(),
+            error_hint: Style::new().dimmed(),
+            error_prefix: Some("error:".into()),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct FormStyle {
     pub title: Style,
@@ -0,0 +1,783 @@
+use {
+    crate::{
+        bitset::BitSet,
+        keymap::{Action, KeyMap},
+        style::MultiSelectStyle,
+        util::{CursorGuard, truncate},
+    },
+    crossterm::{
+        cursor,
+        event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+        queue,
+        terminal::{self, Clear, ClearType},
+    },
+    miette::IntoDiagnostic,
+    owo_colors::OwoColorize,
+    std::{
+        cmp::Ordering,
+        io::{Write, stdout},
+    },
+};
+
+/// a row of a [`TableSelect`], and the value it stands for
+#[derive(Clone, Debug)]
+pub struct TableRow<T> {
+    /// the text in each column
+    pub cells: Vec<String>,
+    pub value: T,
+}
+
+impl<T> TableRow<T> {
+    /// # Arguments
+    ///
+    /// * `cells` - the text in each column, left to right
+    /// * `value` - what picking the row gives back
+    pub fn new(cells: impl IntoIterator<Item = impl Into<String>>, value: T) -> Self {
+        Self {
+            cells: cells.into_iter().map(Into::into).collect(),
+            value,
+        }
+    }
+
+    /// the text in `column`, or nothing if the row is short
+    pub fn cell(&self, column: usize) -> &str {
+        self.cells.get(column).map_or("", String::as_str)
+    }
+}
+
+/// pick one row, or several, from a table
+///
+/// columns are sized to fit the terminal, with text that doesn't fit cut
+/// short, and the rows can be sorted by any column.
+#[derive(Clone)]
+pub struct TableSelect<T: Clone> {
+    prompt: String,
+    header: Vec<String>,
+    rows: Vec<TableRow<T>>,
+    page_size: usize,
+    prompt_prefix: String,
+    help_message: Option<String>,
+    show_hints: bool,
+    allow_escape: bool,
+    vim_mode: bool,
+    keymap: Option<KeyMap>,
+    min_selections: Option<usize>,
+    max_selections: Option<usize>,
+    style: MultiSelectStyle,
+    _cursor_guard: CursorGuard,
+}
+
+/// where the cursor is, how the rows are sorted and which are selected
+struct TableState {
+    /// row indices in the order they're shown
+    order: Vec<usize>,
+    /// the position in `order` the cursor is at
+    cursor: usize,
+    scroll: usize,
+    /// the column the rows are sorted by, and whether it's descending
+    sort: Option<(usize, bool)>,
+    selected: BitSet,
+}
+
+impl<T: Clone> TableSelect<T> {
+    pub fn new(prompt: impl Into<String>) -> Self {
+        let _cursor_guard = CursorGuard::new().expect("Failed to initialize cursor guard");
+        Self {
+            prompt: prompt.into(),
+            header: Vec::new(),
+            rows: Vec::new(),
+            page_size: 10,
+            prompt_prefix: "?".into(),
+            help_message: None,
+            show_hints: true,
+            allow_escape: true,
+            vim_mode: false,
+            keymap: None,
+            min_selections: None,
+            max_selections: None,
+            style: MultiSelectStyle::default(),
+            _cursor_guard,
+        }
+    }
+
+    pub fn prompt(&self) -> &str {
+        &self.prompt
+    }
+
+    pub fn rows(&self) -> &[TableRow<T>] {
+        &self.rows
+    }
+
+    /// set the column titles
+    pub fn with_header(mut self, columns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.header = columns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_rows(mut self, rows: Vec<TableRow<T>>) -> Self {
+        self.rows = rows;
+        self
+    }
+
+    /// add a row
+    ///
+    /// # Arguments
+    ///
+    /// * `cells` - the text in each column, left to right
+    /// * `value` - what picking the row gives back
+    pub fn with_row(
+        mut self,
+        cells: impl IntoIterator<Item = impl Into<String>>,
+        value: T,
+    ) -> Self {
+        self.rows.push(TableRow::new(cells, value));
+        self
+    }
+
+    pub fn with_page_size(mut self, size: usize) -> Self {
+        self.page_size = size.max(3);
+        self
+    }
+
+    pub fn with_prompt_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prompt_prefix = prefix.into();
+        self
+    }
+
+    pub fn with_help_message(mut self, message: impl Into<String>) -> Self {
+        self.help_message = Some(message.into());
+        self
+    }
+
+    pub fn with_hints(mut self, enabled: bool) -> Self {
+        self.show_hints = enabled;
+        self
+    }
+
+    pub fn with_escape(mut self, allow: bool) -> Self {
+        self.allow_escape = allow;
+        self
+    }
+
+    pub fn with_vim_mode(mut self, enabled: bool) -> Self {
+        self.vim_mode = enabled;
+        self
+    }
+
+    /// set which keys do what, instead of the bindings `with_vim_mode` picks
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.keymap = Some(keymap);
+        self
+    }
+
+    /// the fewest rows [`TableSelect::ask_multiple`] accepts
+    pub fn with_min_selections(mut self, min: usize) -> Self {
+        self.min_selections = Some(min);
+        self
+    }
+
+    /// the most rows [`TableSelect::ask_multiple`] accepts
+    pub fn with_max_selections(mut self, max: usize) -> Self {
+        self.max_selections = Some(max);
+        self
+    }
+
+    pub fn with_style(mut self, style: MultiSelectStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// pick a single row
+    pub fn ask(&self) -> miette::Result<TableRow<T>> {
+        let picked = self.ask_with_hook(false)?;
+        picked
+            .into_iter()
+            .next()
+            .ok_or_else(|| miette::miette!("No row selected"))
+    }
+
+    /// select any number of rows
+    ///
+    /// returns the selected rows in the order they were added, however the
+    /// table was sorted.
+    pub fn ask_multiple(&self) -> miette::Result<Vec<TableRow<T>>> {
+        self.ask_with_hook(true)
+    }
+
+    fn ask_with_hook(&self, multiple: bool) -> miette::Result<Vec<TableRow<T>>> {
+        if self.rows.is_empty() {
+            return Err(miette::miette!("No rows provided"));
+        }
+
+        let original_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |panic_info| {
+            let _ = terminal::disable_raw_mode();
+            std::panic::take_hook()(panic_info);
+        }));
+
+        let result = self.ask_internal(multiple);
+
+        let _ = std::panic::take_hook();
+        std::panic::set_hook(original_hook);
+
+        result
+    }
+
+    fn ask_internal(&self, multiple: bool) -> miette::Result<Vec<TableRow<T>>> {
+        let mut state = TableState {
+            order: (0..self.rows.len()).collect(),
+            cursor: 0,
+            scroll: 0,
+            sort: None,
+            selected: BitSet::default(),
+        };
+
+        let mut buf = Vec::with_capacity(4096);
+        let mut out = stdout();
+
+        let mut last_render_lines = self.render(&mut buf, &state, multiple)?;
+        out.write_all(&buf).into_diagnostic()?;
+        out.flush().into_diagnostic()?;
+
+        terminal::enable_raw_mode().into_diagnostic()?;
+
+        while event::poll(std::time::Duration::from_millis(0)).into_diagnostic()? {
+            event::read().into_diagnostic()?;
+        }
+
+        loop {
+            if let Event::Key(key_event) = event::read().into_diagnostic()? {
+                if key_event.kind != KeyEventKind::Press {
+                    continue;
+                }
+
+                match self.handle_key(key_event, &mut state, multiple) {
+                    Ok(Some(picked)) => {
+                        terminal::disable_raw_mode().into_diagnostic()?;
+                        buf.clear();
+                        if last_render_lines > 0 {
+                            queue!(buf, cursor::MoveUp(last_render_lines as u16))
+                                .into_diagnostic()?;
+                        }
+                        queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
+                        queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+
+                        self.show_result(&mut buf, &picked)?;
+                        out.write_all(&buf).into_diagnostic()?;
+                        out.flush().into_diagnostic()?;
+
+                        return Ok(picked.iter().map(|&i| self.rows[i].clone()).collect());
+                    }
+                    Ok(None) => {
+                        buf.clear();
+                        if last_render_lines > 0 {
+                            queue!(buf, cursor::MoveUp(last_render_lines as u16))
+                                .into_diagnostic()?;
+                        }
+                        queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
+                        queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+                        last_render_lines = self.render(&mut buf, &state, multiple)?;
+                        out.write_all(&buf).into_diagnostic()?;
+                        out.flush().into_diagnostic()?;
+                    }
+                    Err(e) => {
+                        terminal::disable_raw_mode().into_diagnostic()?;
+                        buf.clear();
+                        if last_render_lines > 0 {
+                            queue!(buf, cursor::MoveUp(last_render_lines as u16))
+                                .into_diagnostic()?;
+                        }
+                        queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
+                        queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+                        self.show_error(&mut buf, &e)?;
+                        out.write_all(&buf).into_diagnostic()?;
+                        out.flush().into_diagnostic()?;
+                        return Err(miette::miette!(e));
+                    }
+                }
+            }
+        }
+    }
+
+    const ACTIONS: &[Action] = &[
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveToFirst,
+        Action::MoveToLast,
+        Action::PageUp,
+        Action::PageDown,
+        Action::SortColumn,
+        Action::ReverseSort,
+        Action::Toggle,
+        Action::SelectAll,
+        Action::DeselectAll,
+        Action::InvertSelection,
+        Action::Submit,
+        Action::Cancel,
+    ];
+
+    fn handle_key(
+        &self,
+        key_event: KeyEvent,
+        state: &mut TableState,
+        multiple: bool,
+    ) -> Result<Option<Vec<usize>>, String> {
+        if key_event.modifiers.contains(KeyModifiers::CONTROL)
+            && matches!(key_event.code, KeyCode::Char('c'))
+        {
+            let _ = terminal::disable_raw_mode();
+            std::process::exit(130);
+        }
+
+        let last = self.rows.len() - 1;
+        let keymap = KeyMap::resolve(self.keymap.as_ref(), self.vim_mode);
+
+        match keymap.action(&key_event, Self::ACTIONS) {
+            Some(Action::MoveUp) => {
+                let row = if state.cursor > 0 {
+                    state.cursor - 1
+                } else {
+                    last
+                };
+                self.move_to(state, row);
+                Ok(None)
+            }
+            Some(Action::MoveDown) => {
+                let row = if state.cursor < last {
+                    state.cursor + 1
+                } else {
+                    0
+                };
+                self.move_to(state, row);
+                Ok(None)
+            }
+            Some(Action::MoveToFirst) => {
+                self.move_to(state, 0);
+                Ok(None)
+            }
+            Some(Action::MoveToLast) => {
+                self.move_to(state, last);
+                Ok(None)
+            }
+            Some(Action::PageUp) => {
+                state.scroll = state.scroll.saturating_sub(self.page_size);
+                self.move_to(state, state.cursor.saturating_sub(self.page_size));
+                Ok(None)
+            }
+            Some(Action::PageDown) => {
+                self.move_to(state, (state.cursor + self.page_size).min(last));
+                Ok(None)
+            }
+            Some(Action::SortColumn) => {
+                let columns = self.columns();
+                let sort = match state.sort {
+                    Some((column, _)) if column + 1 < columns => Some((column + 1, false)),
+                    Some(_) => None,
+                    None if columns > 0 => Some((0, false)),
+                    None => None,
+                };
+                self.sort(state, sort);
+                Ok(None)
+            }
+            Some(Action::ReverseSort) => {
+                let sort = match state.sort {
+                    Some((column, descending)) => Some((column, !descending)),
+                    None => Some((0, true)),
+                };
+                self.sort(state, sort);
+                Ok(None)
+            }
+            Some(Action::Toggle) if multiple => {
+                let index = state.order[state.cursor];
+                if !state.selected.remove(index) {
+                    if let Some(max) = self.max_selections
+                        && state.selected.len() >= max
+                    {
+                        return Err(format!("Cannot select more than {} options", max));
+                    }
+                    state.selected.insert(index);
+                }
+                Ok(None)
+            }
+            Some(Action::SelectAll) if multiple => {
+                if let Some(max) = self.max_selections
+                    && self.rows.len() > max
+                {
+                    return Err(format!(
+                        "Cannot select all: maximum {} selections allowed",
+                        max
+                    ));
+                }
+                state.selected = (0..self.rows.len()).collect();
+                Ok(None)
+            }
+            Some(Action::DeselectAll) if multiple => {
+                state.selected.clear();
+                Ok(None)
+            }
+            Some(Action::InvertSelection) if multiple => {
+                let inverted: BitSet = (0..self.rows.len())
+                    .filter(|&i| !state.selected.contains(i))
+                    .collect();
+                if let Some(max) = self.max_selections
+                    && inverted.len() > max
+                {
+                    return Err(format!(
+                        "Cannot invert: would exceed maximum {} selections",
+                        max
+                    ));
+                }
+                state.selected = inverted;
+                Ok(None)
+            }
+            Some(Action::Submit) if multiple => {
+                if let Some(min) = self.min_selections
+                    && state.selected.len() < min
+                {
+                    return Err(format!("Please select at least {} option(s)", min));
+                }
+
+                Ok(Some(state.selected.iter().collect()))
+            }
+            Some(Action::Submit) => Ok(Some(vec![state.order[state.cursor]])),
+            Some(Action::Cancel) if self.allow_escape => Err("Cancelled".into()),
+            _ => Ok(None),
+        }
+    }
+
+    /// put the cursor on the `row`th row shown, scrolling to keep it on screen
+    fn move_to(&self, state: &mut TableState, row: usize) {
+        state.cursor = row;
+        if row < state.scroll {
+            state.scroll = row;
+        } else if row >= state.scroll + self.page_size {
+            state.scroll = row + 1 - self.page_size;
+        }
+    }
+
+    /// order the rows by a column, keeping the cursor on the same row
+    ///
+    /// rows that tie stay in the order they were added, and `None` puts
+    /// every row back in that order.
+    fn sort(&self, state: &mut TableState, sort: Option<(usize, bool)>) {
+        let current = state.order[state.cursor];
+
+        state.order = (0..self.rows.len()).collect();
+        if let Some((column, descending)) = sort {
+            state.order.sort_by(|&a, &b| {
+                let ordering = compare_cells(self.rows[a].cell(column), self.rows[b].cell(column));
+                if descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+        }
+        state.sort = sort;
+
+        let row = state.order.iter().position(|&i| i == current).unwrap_or(0);
+        self.move_to(state, row);
+    }
+
+    fn columns(&self) -> usize {
+        self.rows
+            .iter()
+            .map(|row| row.cells.len())
+            .chain(std::iter::once(self.header.len()))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// how wide each column is drawn, shrinking the widest ones until the
+    /// table fits in `available`
+    fn widths(&self, available: usize) -> Vec<usize> {
+        const MIN_WIDTH: usize = 3;
+
+        let columns = self.columns();
+        let mut widths: Vec<usize> = (0..columns)
+            .map(|column| {
+                // room for the sort arrow, so sorting doesn't shift things
+                let title = self
+                    .header
+                    .get(column)
+                    .map_or(0, |title| title.chars().count() + 2);
+                self.rows
+                    .iter()
+                    .map(|row| row.cell(column).chars().count())
+                    .chain(std::iter::once(title))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let gaps = 2 * columns.saturating_sub(1);
+        while widths.iter().sum::<usize>() + gaps > available {
+            match widths.iter_mut().filter(|w| **w > MIN_WIDTH).max() {
+                Some(widest) => *widest -= 1,
+                None => break,
+            }
+        }
+
+        widths
+    }
+
+    fn render(
+        &self,
+        out: &mut impl Write,
+        state: &TableState,
+        multiple: bool,
+    ) -> miette::Result<usize> {
+        let tw = crate::util::term_width();
+        let mut line_count = 0;
+
+        let line = format!(
+            "{} {}",
+            self.prompt_prefix.style(self.style.prompt_prefix),
+            self.prompt.style(self.style.prompt),
+        );
+        line_count += crate::util::writeln_physical(out, &line, tw)?;
+
+        if let Some(ref help) = self.help_message {
+            let line = format!("  {}", help.style(self.style.hint));
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        // the cursor marker and checkbox come before the first column
+        let indent = if multiple { 6 } else { 4 };
+        let widths = self.widths((tw as usize).saturating_sub(indent + 1));
+
+        if !self.header.is_empty() {
+            let titles: Vec<String> = widths
+                .iter()
+                .enumerate()
+                .map(|(column, &width)| {
+                    let title = self.header.get(column).map_or("", String::as_str);
+                    let title = match state.sort {
+                        Some((sorted, false)) if sorted == column => format!("{} ▲", title),
+                        Some((sorted, true)) if sorted == column => format!("{} ▼", title),
+                        _ => title.to_string(),
+                    };
+                    pad(&truncate(&title, width), width)
+                })
+                .collect();
+            let line = format!(
+                "{}{}",
+                " ".repeat(indent),
+                titles.join("  ").trim_end().style(self.style.group_header)
+            );
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        let end_offset = (state.scroll + self.page_size).min(state.order.len());
+
+        for (position, &index) in state
+            .order
+            .iter()
+            .enumerate()
+            .take(end_offset)
+            .skip(state.scroll)
+        {
+            let row = &self.rows[index];
+            let is_cursor = position == state.cursor;
+            let is_selected = multiple && state.selected.contains(index);
+            let cursor_marker = if is_cursor { "󰁕" } else { " " };
+
+            let checkbox = if !multiple {
+                String::new()
+            } else if is_selected {
+                format!("{} ", "󰄲".style(self.style.checkbox_selected))
+            } else {
+                format!("{} ", "󰄮".style(self.style.checkbox_unselected))
+            };
+
+            let cell_style = if is_cursor {
+                self.style.cursor
+            } else if is_selected {
+                self.style.selected
+            } else {
+                self.style.option_name
+            };
+            let cells: Vec<String> = widths
+                .iter()
+                .enumerate()
+                .map(|(column, &width)| pad(&truncate(row.cell(column), width), width))
+                .collect();
+
+            let line = format!(
+                "  {} {}{}",
+                cursor_marker.style(self.style.cursor),
+                checkbox,
+                cells.join("  ").trim_end().style(cell_style)
+            );
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        if state.scroll > 0 {
+            let line = format!(
+                "  {}",
+                format!("(↑ {} more above)", state.scroll).style(self.style.hint)
+            );
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        if end_offset < state.order.len() {
+            let line = format!(
+                "  {}",
+                format!("(↓ {} more below)", state.order.len() - end_offset).style(self.style.hint)
+            );
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        if self.show_hints {
+            let keymap = KeyMap::resolve(self.keymap.as_ref(), self.vim_mode);
+            let mut hints = vec![];
+
+            if multiple && let Some(key) = keymap.hint(Action::Toggle) {
+                hints.push(format!("{} to toggle", key));
+            }
+
+            if let Some(keys) = keymap.hint_pair(Action::MoveUp, Action::MoveDown) {
+                hints.push(format!("{} to navigate", keys));
+            }
+
+            if let Some(key) = keymap.hint(Action::SortColumn) {
+                hints.push(format!("{} to sort", key));
+            }
+
+            if let Some(key) = keymap.hint(Action::ReverseSort) {
+                hints.push(format!("{} to reverse", key));
+            }
+
+            if self.rows.len() > self.page_size
+                && let Some(keys) = keymap.hint_pair(Action::PageUp, Action::PageDown)
+            {
+                hints.push(format!("{} to scroll", keys));
+            }
+
+            if let Some(key) = keymap.hint(Action::Submit) {
+                let what = if multiple { "submit" } else { "select" };
+                hints.push(format!("{} to {}", key, what));
+            }
+
+            if self.allow_escape
+                && let Some(key) = keymap.hint(Action::Cancel)
+            {
+                hints.push(format!("{} to cancel", key));
+            }
+
+            let line = format!("  {}", hints.join(", ").style(self.style.hint));
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        if multiple {
+            let count_text = format!("[{} selected]", state.selected.len());
+            let line = format!("  {}", count_text.style(self.style.selection_count));
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        Ok(line_count)
+    }
+
+    fn show_error(&self, out: &mut impl Write, error: &str) -> miette::Result<()> {
+        let line = format!(
+            "{} {}",
+            "✗".style(self.style.error),
+            error.style(self.style.error_hint),
+        );
+        let tw = crate::util::term_width();
+        crate::util::writeln_physical(out, &line, tw)?;
+
+        Ok(())
+    }
+
+    fn show_result(&self, out: &mut impl Write, picked: &[usize]) -> miette::Result<()> {
+        let names: Vec<&str> = picked.iter().map(|&i| self.rows[i].cell(0)).collect();
+
+        let result_text = if names.is_empty() {
+            "None".to_string()
+        } else {
+            names.join(", ")
+        };
+
+        let line = format!(
+            "{} {} {}",
+            self.prompt_prefix.style(self.style.prompt_prefix),
+            self.prompt.style(self.style.prompt),
+            result_text.style(self.style.selected).bold(),
+        );
+        let tw = crate::util::term_width();
+        crate::util::writeln_physical(out, &line, tw)?;
+
+        Ok(())
+    }
+}
+
+fn pad(text: &str, width: usize) -> String {
+    format!("{:<width$}", text, width = width)
+}
+
+/// order cells that are numbers by value, ahead of the ones that aren't,
+/// which go by [`compare_text`]
+///
+/// only finite numbers count, so text like `nan` or `inf` stays text.
+///
+/// every pair goes by the same rule, so sorting a column that mixes numbers
+/// and text can't contradict itself.
+fn compare_cells(a: &str, b: &str) -> Ordering {
+    let number = |cell: &str| {
+        cell.trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|number| number.is_finite())
+    };
+    match (number(a), number(b)) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => compare_text(a, b),
+    }
+}
+
+/// order text ignoring case, with runs of digits compared by value, so
+/// `file10` comes after `file9`
+fn compare_text(a: &str, b: &str) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let x = digits(&mut a);
+                let y = digits(&mut b);
+                // leading zeros don't count, so compare by length first
+                let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                match x.len().cmp(&y.len()).then_with(|| x.cmp(y)) {
+                    Ordering::Equal => {}
+                    ordering => return ordering,
+                }
+            }
+            (Some(x), Some(y)) => {
+                match x.to_lowercase().cmp(y.to_lowercase()) {
+                    Ordering::Equal => {}
+                    ordering => return ordering,
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+fn digits(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> String {
+    let mut digits = String::new();
+    while let Some(&c) = chars.peek()
+        && c.is_ascii_digit()
+    {
+        digits.push(c);
+        chars.next();
+    }
+    digits
+}
@@ -0,0 +1,807 @@
+use {
+    crate::{
+        bitset::BitSet,
+        filter::{self, Filter},
+        keymap::{Action, KeyMap},
+        option::AskOption,
+        style::TransferStyle,
+        util::{CursorGuard, fit},
+        validation::{Validate, run_validator},
+    },
+    crossterm::{
+        cursor,
+        event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+        queue,
+        terminal::{self, Clear, ClearType},
+    },
+    miette::IntoDiagnostic,
+    owo_colors::OwoColorize,
+    std::io::{Write, stdout},
+};
+
+const AVAILABLE: usize = 0;
+const SELECTED: usize = 1;
+
+/// pick options by moving them from an "Available" list to a "Selected" one
+/// beside it, and put the selected ones in order
+///
+/// each side has its own filter, and the selected values come back in the
+/// order they were arranged in.
+#[derive(Clone)]
+pub struct TransferList<T: Clone> {
+    prompt: String,
+    options: Vec<AskOption<T>>,
+    /// the options to start on the selected side, in order
+    selected: Vec<usize>,
+    page_size: usize,
+    prompt_prefix: String,
+    help_message: Option<String>,
+    show_hints: bool,
+    allow_escape: bool,
+    vim_mode: bool,
+    keymap: Option<KeyMap>,
+    filter: bool,
+    min_selections: Option<usize>,
+    max_selections: Option<usize>,
+    style: TransferStyle,
+    validation: Option<Box<dyn Validate<[usize]>>>,
+    _cursor_guard: CursorGuard,
+}
+
+/// one of the two lists
+#[derive(Clone, Default)]
+struct Side {
+    /// option indices, in the order they're listed
+    items: Vec<usize>,
+    filter: Filter,
+    /// the items the filter matches, in the same order
+    rows: Vec<usize>,
+    /// the position in `rows` the cursor is at
+    cursor: usize,
+    scroll: usize,
+    /// whether the item under the cursor was just moved up or down
+    moved: bool,
+}
+
+impl Side {
+    /// the option under the cursor, if the side shows any
+    fn current(&self) -> Option<usize> {
+        self.rows.get(self.cursor).copied()
+    }
+
+    /// match the items against the filter again, keeping the cursor in range
+    fn refresh(&mut self, page_size: usize) {
+        let matched: BitSet = self.filter.matches.iter().map(|m| m.index).collect();
+        self.rows = self
+            .items
+            .iter()
+            .copied()
+            .filter(|&i| matched.contains(i))
+            .collect();
+        self.move_to(
+            self.cursor.min(self.rows.len().saturating_sub(1)),
+            page_size,
+        );
+    }
+
+    /// put the cursor on the `row`th row, scrolling to keep it on screen
+    fn move_to(&mut self, row: usize, page_size: usize) {
+        self.cursor = row;
+        if row < self.scroll {
+            self.scroll = row;
+        } else if row >= self.scroll + page_size {
+            self.scroll = row + 1 - page_size;
+        }
+        self.scroll = self.scroll.min(self.rows.len().saturating_sub(page_size));
+    }
+
+    /// swap the item under the cursor with the row `offset` away, moving the
+    /// cursor along with it
+    fn shift(&mut self, offset: isize, page_size: usize) {
+        let Some(target) = self.cursor.checked_add_signed(offset) else {
+            return;
+        };
+        if target >= self.rows.len() {
+            return;
+        }
+
+        let position = |item: usize| self.items.iter().position(|&i| i == item);
+        if let (Some(a), Some(b)) = (
+            position(self.rows[self.cursor]),
+            position(self.rows[target]),
+        ) {
+            self.items.swap(a, b);
+            self.rows.swap(self.cursor, target);
+            self.move_to(target, page_size);
+            self.moved = true;
+        }
+    }
+}
+
+/// both lists, and which one the cursor is in
+struct TransferState {
+    sides: [Side; 2],
+    focus: usize,
+}
+
+impl<T: Clone> TransferList<T> {
+    pub fn new(prompt: impl Into<String>) -> Self {
+        let _cursor_guard = CursorGuard::new().expect("Failed to initialize cursor guard");
+        Self {
+            prompt: prompt.into(),
+            options: Vec::new(),
+            selected: Vec::new(),
+            page_size: 10,
+            prompt_prefix: "⇄".into(),
+            help_message: None,
+            show_hints: true,
+            allow_escape: true,
+            vim_mode: false,
+            keymap: None,
+            filter: true,
+            min_selections: None,
+            max_selections: None,
+            style: TransferStyle::default(),
+            validation: None,
+            _cursor_guard,
+        }
+    }
+
+    pub fn prompt(&self) -> &str {
+        &self.prompt
+    }
+
+    pub fn options(&self) -> &[AskOption<T>] {
+        &self.options
+    }
+
+    pub fn with_options(mut self, options: Vec<AskOption<T>>) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn with_option(mut self, option: AskOption<T>) -> Self {
+        self.options.push(option);
+        self
+    }
+
+    /// start with these options on the selected side, in this order
+    pub fn with_selected(mut self, indices: &[usize]) -> Self {
+        self.selected = indices.to_vec();
+        self
+    }
+
+    pub fn with_page_size(mut self, size: usize) -> Self {
+        self.page_size = size.max(3);
+        self
+    }
+
+    pub fn with_prompt_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prompt_prefix = prefix.into();
+        self
+    }
+
+    pub fn with_help_message(mut self, message: impl Into<String>) -> Self {
+        self.help_message = Some(message.into());
+        self
+    }
+
+    pub fn with_hints(mut self, enabled: bool) -> Self {
+        self.show_hints = enabled;
+        self
+    }
+
+    pub fn with_escape(mut self, allow: bool) -> Self {
+        self.allow_escape = allow;
+        self
+    }
+
+    pub fn with_vim_mode(mut self, enabled: bool) -> Self {
+        self.vim_mode = enabled;
+        self
+    }
+
+    /// set which keys do what, instead of the bindings `with_vim_mode` picks
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.keymap = Some(keymap);
+        self
+    }
+
+    /// type to narrow down the side the cursor is in, which is on unless
+    /// turned off here
+    ///
+    /// typed letters go to the filter rather than the keymap, so vim-style
+    /// letter bindings only apply with it off.
+    pub fn with_filter(mut self, enabled: bool) -> Self {
+        self.filter = enabled;
+        self
+    }
+
+    pub fn with_min_selections(mut self, min: usize) -> Self {
+        self.min_selections = Some(min);
+        self
+    }
+
+    pub fn with_max_selections(mut self, max: usize) -> Self {
+        self.max_selections = Some(max);
+        self
+    }
+
+    pub fn with_style(mut self, style: TransferStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// check the selected option indices, in their order, before submitting
+    pub fn with_validation(mut self, validation: impl Validate<[usize]> + 'static) -> Self {
+        self.validation = Some(Box::new(validation));
+        self
+    }
+
+    /// returns the values on the selected side, in the order they were
+    /// arranged in
+    pub fn ask(&self) -> miette::Result<Vec<T>> {
+        if self.options.is_empty() {
+            return Err(miette::miette!("No options provided"));
+        }
+
+        let original_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |panic_info| {
+            let _ = terminal::disable_raw_mode();
+            std::panic::take_hook()(panic_info);
+        }));
+
+        let result = self.ask_internal();
+
+        let _ = std::panic::take_hook();
+        std::panic::set_hook(original_hook);
+
+        result
+    }
+
+    fn ask_internal(&self) -> miette::Result<Vec<T>> {
+        let mut state = self.initial_state();
+        let mut error_message: Option<String> = None;
+        let mut buf = Vec::with_capacity(4096);
+        let mut out = stdout();
+
+        terminal::enable_raw_mode().into_diagnostic()?;
+
+        while event::poll(std::time::Duration::from_millis(0)).into_diagnostic()? {
+            event::read().into_diagnostic()?;
+        }
+
+        let mut last_render_lines = self.render(&mut buf, &state, error_message.as_deref())?;
+        out.write_all(&buf).into_diagnostic()?;
+        out.flush().into_diagnostic()?;
+
+        loop {
+            if let Event::Key(key_event) = event::read().into_diagnostic()? {
+                if key_event.kind != KeyEventKind::Press {
+                    continue;
+                }
+
+                error_message = None;
+
+                match self.handle_key(key_event, &mut state) {
+                    Ok(Some(())) => {
+                        terminal::disable_raw_mode().into_diagnostic()?;
+                        buf.clear();
+                        if last_render_lines > 0 {
+                            queue!(buf, cursor::MoveUp(last_render_lines as u16))
+                                .into_diagnostic()?;
+                        }
+                        queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
+                        queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+
+                        let picked = &state.sides[SELECTED].items;
+                        self.show_result(&mut buf, picked)?;
+                        out.write_all(&buf).into_diagnostic()?;
+                        out.flush().into_diagnostic()?;
+
+                        return Ok(picked
+                            .iter()
+                            .map(|&i| self.options[i].value.clone())
+                            .collect());
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        if e == "Cancelled" {
+                            terminal::disable_raw_mode().into_diagnostic()?;
+                            buf.clear();
+                            if last_render_lines > 0 {
+                                queue!(buf, cursor::MoveUp(last_render_lines as u16))
+                                    .into_diagnostic()?;
+                            }
+                            queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
+                            queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+                            self.show_error(&mut buf, &e)?;
+                            out.write_all(&buf).into_diagnostic()?;
+                            out.flush().into_diagnostic()?;
+                            return Err(miette::miette!(e));
+                        }
+                        error_message = Some(e);
+                    }
+                }
+
+                buf.clear();
+                if last_render_lines > 0 {
+                    queue!(buf, cursor::MoveUp(last_render_lines as u16)).into_diagnostic()?;
+                }
+                queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
+                queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+                last_render_lines = self.render(&mut buf, &state, error_message.as_deref())?;
+                out.write_all(&buf).into_diagnostic()?;
+                out.flush().into_diagnostic()?;
+            }
+        }
+    }
+
+    /// both sides as they start, with the options given to `with_selected`
+    /// on the selected side and the rest available
+    fn initial_state(&self) -> TransferState {
+        let mut selected = BitSet::default();
+        let chosen: Vec<usize> = self
+            .selected
+            .iter()
+            .copied()
+            .filter(|&i| i < self.options.len() && selected.insert(i))
+            .collect();
+
+        let mut state = TransferState {
+            sides: [
+                Side {
+                    items: (0..self.options.len())
+                        .filter(|&i| !selected.contains(i))
+                        .collect(),
+                    filter: Filter::new(&self.options),
+                    ..Default::default()
+                },
+                Side {
+                    items: chosen,
+                    filter: Filter::new(&self.options),
+                    ..Default::default()
+                },
+            ],
+            focus: AVAILABLE,
+        };
+        for side in &mut state.sides {
+            side.refresh(self.page_size);
+        }
+        state
+    }
+
+    const ACTIONS: &[Action] = &[
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveToFirst,
+        Action::MoveToLast,
+        Action::PageUp,
+        Action::PageDown,
+        Action::MoveItemUp,
+        Action::MoveItemDown,
+        Action::Toggle,
+        Action::ToggleVisible,
+        Action::Expand,
+        Action::Collapse,
+        Action::SwitchView,
+        Action::Submit,
+        Action::Cancel,
+    ];
+
+    fn handle_key(
+        &self,
+        key_event: KeyEvent,
+        state: &mut TransferState,
+    ) -> Result<Option<()>, String> {
+        if key_event.modifiers.contains(KeyModifiers::CONTROL)
+            && matches!(key_event.code, KeyCode::Char('c'))
+        {
+            let _ = terminal::disable_raw_mode();
+            std::process::exit(130);
+        }
+
+        let keymap = KeyMap::resolve(self.keymap.as_ref(), self.vim_mode);
+        let action = if self.filter && Filter::takes(&key_event, false) {
+            None
+        } else {
+            keymap.action(&key_event, Self::ACTIONS)
+        };
+        let page_size = self.page_size;
+        let side = &mut state.sides[state.focus];
+        let last = side.rows.len().saturating_sub(1);
+        // only stays set if this key moves the item again
+        side.moved = false;
+
+        match action {
+            Some(Action::MoveUp) => {
+                let row = if side.cursor > 0 {
+                    side.cursor - 1
+                } else {
+                    last
+                };
+                side.move_to(row, page_size);
+                Ok(None)
+            }
+            Some(Action::MoveDown) => {
+                let row = if side.cursor < last {
+                    side.cursor + 1
+                } else {
+                    0
+                };
+                side.move_to(row, page_size);
+                Ok(None)
+            }
+            Some(Action::MoveToFirst) => {
+                side.move_to(0, page_size);
+                Ok(None)
+            }
+            Some(Action::MoveToLast) => {
+                side.move_to(last, page_size);
+                Ok(None)
+            }
+            Some(Action::PageUp) => {
+                side.move_to(side.cursor.saturating_sub(page_size), page_size);
+                Ok(None)
+            }
+            Some(Action::PageDown) => {
+                side.move_to((side.cursor + page_size).min(last), page_size);
+                Ok(None)
+            }
+            Some(Action::MoveItemUp) if state.focus == SELECTED => {
+                side.shift(-1, page_size);
+                Ok(None)
+            }
+            Some(Action::MoveItemDown) if state.focus == SELECTED => {
+                side.shift(1, page_size);
+                Ok(None)
+            }
+            Some(Action::Toggle) => match side.current() {
+                Some(index) if !self.options[index].disabled => {
+                    self.transfer(state, &[index], "more")?;
+                    Ok(None)
+                }
+                _ => Ok(None),
+            },
+            Some(Action::ToggleVisible) => {
+                let indices: Vec<usize> = side
+                    .rows
+                    .iter()
+                    .copied()
+                    .filter(|&i| !self.options[i].disabled)
+                    .collect();
+                self.transfer(state, &indices, "all of them")?;
+                Ok(None)
+            }
+            Some(Action::Expand) => {
+                state.focus = SELECTED;
+                Ok(None)
+            }
+            Some(Action::Collapse) => {
+                state.focus = AVAILABLE;
+                Ok(None)
+            }
+            Some(Action::SwitchView) => {
+                state.focus = 1 - state.focus;
+                Ok(None)
+            }
+            Some(Action::Submit) => self.validate(&state.sides[SELECTED].items),
+            Some(Action::Cancel) if self.filter && !side.filter.is_empty() => {
+                side.filter.clear(&self.options);
+                side.refresh(page_size);
+                Ok(None)
+            }
+            Some(Action::Cancel) if self.allow_escape => Err("Cancelled".into()),
+            _ => {
+                if self.filter && side.filter.edit(key_event, &self.options) {
+                    side.move_to(0, page_size);
+                    side.refresh(page_size);
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// move `indices` from the side the cursor is in to the other one
+    ///
+    /// items going back to the available side go back where they started,
+    /// and ones going to the selected side go on the end.
+    fn transfer(
+        &self,
+        state: &mut TransferState,
+        indices: &[usize],
+        what: &str,
+    ) -> Result<(), String> {
+        if indices.is_empty() {
+            return Ok(());
+        }
+
+        let to = 1 - state.focus;
+        if to == SELECTED
+            && let Some(max) = self.max_selections
+            && state.sides[SELECTED].items.len() + indices.len() > max
+        {
+            return Err(format!(
+                "Cannot select {}: maximum {} selections allowed",
+                what, max
+            ));
+        }
+
+        let moving: BitSet = indices.iter().copied().collect();
+        state.sides[state.focus]
+            .items
+            .retain(|&i| !moving.contains(i));
+
+        let target = &mut state.sides[to];
+        for &index in indices {
+            if to == SELECTED {
+                target.items.push(index);
+            } else if let Err(at) = target.items.binary_search(&index) {
+                target.items.insert(at, index);
+            }
+        }
+
+        for side in &mut state.sides {
+            side.refresh(self.page_size);
+        }
+        Ok(())
+    }
+
+    fn validate(&self, selected: &[usize]) -> Result<Option<()>, String> {
+        if let Some(min) = self.min_selections
+            && selected.len() < min
+        {
+            return Err(format!("Please select at least {} option(s)", min));
+        }
+
+        if let Some(max) = self.max_selections
+            && selected.len() > max
+        {
+            return Err(format!("Please select at most {} option(s)", max));
+        }
+
+        if let Some(ref validator) = self.validation {
+            run_validator(validator.as_ref(), selected)?;
+        }
+
+        Ok(Some(()))
+    }
+
+    fn render(
+        &self,
+        out: &mut impl Write,
+        state: &TransferState,
+        error: Option<&str>,
+    ) -> miette::Result<usize> {
+        let tw = crate::util::term_width();
+        let mut line_count = 0;
+
+        // two columns and the divider, leaving the last column of the
+        // terminal free
+        let width = (tw as usize).saturating_sub(6) / 2;
+
+        let line = format!(
+            "{} {}",
+            self.prompt_prefix.style(self.style.prompt_prefix),
+            self.prompt.style(self.style.prompt),
+        );
+        line_count += crate::util::writeln_physical(out, &line, tw)?;
+
+        if let Some(ref help) = self.help_message {
+            let line = format!("  {}", help.style(self.style.hint));
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        let titles = [AVAILABLE, SELECTED].map(|s| {
+            let side = &state.sides[s];
+            let count = match self.max_selections {
+                Some(max) if s == SELECTED => format!("{}/{}", side.items.len(), max),
+                _ => side.items.len().to_string(),
+            };
+            let name = if s == AVAILABLE {
+                "Available"
+            } else {
+                "Selected"
+            };
+            let style = if s == state.focus {
+                self.style.title
+            } else {
+                self.style.title_inactive
+            };
+            format!("{} ({})", name, count).style(style).to_string()
+        });
+        let line = self.columns(&titles[AVAILABLE], &titles[SELECTED], width);
+        line_count += crate::util::writeln_physical(out, &line, tw)?;
+
+        if self.filter {
+            let filters = [AVAILABLE, SELECTED].map(|s| {
+                let filter = &state.sides[s].filter;
+                if !filter.is_empty() {
+                    format!(
+                        "{} {}",
+                        "/".style(self.style.hint),
+                        filter.query.style(self.style.prompt)
+                    )
+                } else if s == state.focus {
+                    "/ type to filter".style(self.style.hint).to_string()
+                } else {
+                    String::new()
+                }
+            });
+            let line = self.columns(&filters[AVAILABLE], &filters[SELECTED], width);
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        let height = state
+            .sides
+            .iter()
+            .map(|side| side.rows.len())
+            .max()
+            .unwrap_or(0)
+            .clamp(1, self.page_size);
+        for row in 0..height {
+            let cells = [AVAILABLE, SELECTED].map(|s| self.cell(state, s, row));
+            let line = self.columns(&cells[AVAILABLE], &cells[SELECTED], width);
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        let scrolled = [AVAILABLE, SELECTED].map(|s| {
+            let side = &state.sides[s];
+            let above = side.scroll;
+            let below = side.rows.len().saturating_sub(side.scroll + self.page_size);
+            let mut parts = vec![];
+            if above > 0 {
+                parts.push(format!("↑ {} more", above));
+            }
+            if below > 0 {
+                parts.push(format!("↓ {} more", below));
+            }
+            parts.join("  ").style(self.style.hint).to_string()
+        });
+        if state
+            .sides
+            .iter()
+            .any(|side| side.rows.len() > self.page_size)
+        {
+            let line = self.columns(&scrolled[AVAILABLE], &scrolled[SELECTED], width);
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        if let Some(err) = error {
+            let line = format!(
+                "  {} {}",
+                "✗".style(self.style.error),
+                err.style(self.style.error_hint)
+            );
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        if self.show_hints {
+            let keymap = KeyMap::resolve(self.keymap.as_ref(), self.vim_mode);
+            let mut hints = vec![];
+
+            if let Some(keys) = keymap.hint_pair(Action::MoveUp, Action::MoveDown) {
+                hints.push(format!("{} to navigate", keys));
+            }
+
+            if let Some(key) = keymap.hint(Action::SwitchView) {
+                hints.push(format!("{} to switch sides", key));
+            }
+
+            if let Some(key) = keymap.hint(Action::Toggle) {
+                hints.push(format!("{} to move across", key));
+            }
+
+            if self.filter
+                && let Some(key) = keymap.hint(Action::ToggleVisible)
+            {
+                hints.push(format!("{} to move all matches", key));
+            }
+
+            if state.focus == SELECTED
+                && let Some(keys) = keymap.hint_pair(Action::MoveItemUp, Action::MoveItemDown)
+            {
+                hints.push(format!("{} to reorder", keys));
+            }
+
+            if let Some(key) = keymap.hint(Action::Submit) {
+                hints.push(format!("{} to submit", key));
+            }
+
+            if self.allow_escape
+                && let Some(key) = keymap.hint(Action::Cancel)
+            {
+                hints.push(format!("{} to cancel", key));
+            }
+
+            let line = format!("  {}", hints.join(", ").style(self.style.hint));
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        Ok(line_count)
+    }
+
+    /// `left` and `right` side by side, each fitted to `width`
+    fn columns(&self, left: &str, right: &str, width: usize) -> String {
+        format!(
+            "  {} {} {}",
+            fit(left, width),
+            "│".style(self.style.hint),
+            fit(right, width)
+        )
+    }
+
+    /// the text for the `row`th row on screen of a side
+    fn cell(&self, state: &TransferState, s: usize, row: usize) -> String {
+        let side = &state.sides[s];
+        let Some(&index) = side.rows.get(side.scroll + row) else {
+            return match (row, side.items.is_empty()) {
+                (0, true) => "(none)".style(self.style.hint).to_string(),
+                (0, false) => "(no matches)".style(self.style.hint).to_string(),
+                _ => String::new(),
+            };
+        };
+
+        let option = &self.options[index];
+        let is_cursor = s == state.focus && side.scroll + row == side.cursor;
+        let marker = if is_cursor { "▸" } else { " " };
+        let style = if option.disabled {
+            self.style.disabled
+        } else if is_cursor && side.moved {
+            self.style.moved
+        } else if is_cursor {
+            self.style.cursor
+        } else {
+            self.style.item
+        };
+        let (name_matched, _) = side.filter.positions(option);
+        let name = filter::highlight(&option.name, &name_matched, style, self.style.filter_match);
+
+        if s == SELECTED {
+            let position = side.items.iter().position(|&i| i == index).unwrap_or(0);
+            format!(
+                "{} {} {}",
+                marker.style(self.style.cursor),
+                format!("{}.", position + 1).style(self.style.index),
+                name
+            )
+        } else {
+            format!("{} {}", marker.style(self.style.cursor), name)
+        }
+    }
+
+    fn show_error(&self, out: &mut impl Write, error: &str) -> miette::Result<()> {
+        let line = format!(
+            "{} {}",
+            "✗".style(self.style.error),
+            error.style(self.style.error_hint),
+        );
+        let tw = crate::util::term_width();
+        crate::util::writeln_physical(out, &line, tw)?;
+
+        Ok(())
+    }
+
+    fn show_result(&self, out: &mut impl Write, picked: &[usize]) -> miette::Result<()> {
+        let result_text = if picked.is_empty() {
+            "None".to_string()
+        } else {
+            picked
+                .iter()
+                .map(|&i| self.options[i].name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let line = format!(
+            "{} {} {}",
+            self.prompt_prefix.style(self.style.prompt_prefix),
+            self.prompt.style(self.style.prompt),
+            result_text.style(self.style.selected).bold(),
+        );
+        let tw = crate::util::term_width();
+        crate::util::writeln_physical(out, &line, tw)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,812 @@
+use {
+    crate::{
+        keymap::{Action, KeyMap},
+        option::AskOption,
+        style::MultiSelectStyle,
+        util::CursorGuard,
+    },
+    crossterm::{
+        cursor,
+        event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+        queue,
+        terminal::{self, Clear, ClearType},
+    },
+    miette::IntoDiagnostic,
+    owo_colors::OwoColorize,
+    std::io::{Write, stdout},
+};
+
+/// a node in a [`TreeSelect`], with the nodes under it
+#[derive(Clone, Debug)]
+pub struct TreeNode<T> {
+    /// the option shown for this node
+    pub option: AskOption<T>,
+    /// the nodes under this one
+    pub children: Vec<TreeNode<T>>,
+    /// whether the children are shown when the prompt starts
+    pub expanded: bool,
+}
+
+impl<T> TreeNode<T> {
+    /// make a node with no children
+    ///
+    /// # Arguments
+    ///
+    /// * `option` - the option shown for this node
+    pub fn new(option: AskOption<T>) -> Self {
+        Self {
+            option,
+            children: Vec::new(),
+            expanded: false,
+        }
+    }
+
+    /// add a node under this one
+    pub fn with_child(mut self, child: impl Into<TreeNode<T>>) -> Self {
+        self.children.push(child.into());
+        self
+    }
+
+    /// add nodes under this one
+    pub fn with_children(mut self, children: Vec<TreeNode<T>>) -> Self {
+        self.children.extend(children);
+        self
+    }
+
+    /// set whether the children are shown when the prompt starts
+    pub fn expanded(mut self, expanded: bool) -> Self {
+        self.expanded = expanded;
+        self
+    }
+}
+
+impl<T> From<AskOption<T>> for TreeNode<T> {
+    fn from(option: AskOption<T>) -> Self {
+        Self::new(option)
+    }
+}
+
+/// pick one node, or several, from a tree of options
+///
+/// Right opens a node and Left closes it. when picking several, checking a
+/// node checks everything under it, and a node's checkbox shows whether all,
+/// some or none of the nodes under it are checked. only nodes without
+/// children count towards the selection limits and the count shown, so a
+/// folder of three files is three selections. a disabled node can't be picked
+/// or checked, and neither can anything under it.
+#[derive(Clone)]
+pub struct TreeSelect<T: Clone> {
+    prompt: String,
+    nodes: Vec<TreeNode<T>>,
+    page_size: usize,
+    prompt_prefix: String,
+    help_message: Option<String>,
+    show_hints: bool,
+    show_descriptions: bool,
+    allow_escape: bool,
+    vim_mode: bool,
+    keymap: Option<KeyMap>,
+    min_selections: Option<usize>,
+    max_selections: Option<usize>,
+    style: MultiSelectStyle,
+    _cursor_guard: CursorGuard,
+}
+
+/// the nodes of a tree laid out in order, so they can be found by index
+struct Flat<'a, T> {
+    option: &'a AskOption<T>,
+    depth: usize,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    /// whether this node or one above it is disabled
+    disabled: bool,
+}
+
+/// how much of what's under a node is checked
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Check {
+    All,
+    Some,
+    None,
+}
+
+/// where the cursor is and what's open and checked
+struct TreeState {
+    /// the node under the cursor
+    cursor: usize,
+    /// the first visible row shown
+    scroll: usize,
+    expanded: Vec<bool>,
+    /// which nodes without children are checked
+    checked: Vec<bool>,
+    /// how many checked nodes without children are at or under each node
+    counts: Vec<usize>,
+    /// how many nodes without children at or under each node can be checked
+    totals: Vec<usize>,
+    /// how many nodes without children are checked in all
+    selected: usize,
+}
+
+impl TreeState {
+    fn new<T>(flat: &[Flat<'_, T>], expanded: Vec<bool>) -> Self {
+        // children come after their parent, so going backwards every node's
+        // children are counted before it is
+        let mut totals = vec![0; flat.len()];
+        for (i, node) in flat.iter().enumerate().rev() {
+            totals[i] = if node.disabled {
+                0
+            } else if node.children.is_empty() {
+                1
+            } else {
+                node.children.iter().map(|&child| totals[child]).sum()
+            };
+        }
+
+        Self {
+            cursor: 0,
+            scroll: 0,
+            expanded,
+            checked: vec![false; flat.len()],
+            counts: vec![0; flat.len()],
+            totals,
+            selected: 0,
+        }
+    }
+
+    /// check or uncheck the node without children at `leaf`, updating the
+    /// counts of the nodes above it
+    fn set<T>(&mut self, flat: &[Flat<'_, T>], leaf: usize, checked: bool) {
+        if self.checked[leaf] == checked {
+            return;
+        }
+        self.checked[leaf] = checked;
+
+        let mut node = Some(leaf);
+        while let Some(i) = node {
+            if checked {
+                self.counts[i] += 1;
+            } else {
+                self.counts[i] -= 1;
+            }
+            node = flat[i].parent;
+        }
+
+        if checked {
+            self.selected += 1;
+        } else {
+            self.selected -= 1;
+        }
+    }
+
+    fn check(&self, index: usize) -> Check {
+        match self.counts[index] {
+            0 => Check::None,
+            n if n == self.totals[index] => Check::All,
+            _ => Check::Some,
+        }
+    }
+
+    /// every checked node, in order
+    fn picked(&self) -> Vec<usize> {
+        (0..self.counts.len())
+            .filter(|&i| self.check(i) == Check::All)
+            .collect()
+    }
+}
+
+impl<T: Clone> TreeSelect<T> {
+    pub fn new(prompt: impl Into<String>) -> Self {
+        let _cursor_guard = CursorGuard::new().expect("Failed to initialize cursor guard");
+        Self {
+            prompt: prompt.into(),
+            nodes: Vec::new(),
+            page_size: 10,
+            prompt_prefix: "?".into(),
+            help_message: None,
+            show_hints: true,
+            show_descriptions: true,
+            allow_escape: true,
+            vim_mode: false,
+            keymap: None,
+            min_selections: None,
+            max_selections: None,
+            style: MultiSelectStyle::default(),
+            _cursor_guard,
+        }
+    }
+
+    pub fn prompt(&self) -> &str {
+        &self.prompt
+    }
+
+    pub fn nodes(&self) -> &[TreeNode<T>] {
+        &self.nodes
+    }
+
+    pub fn with_nodes(mut self, nodes: Vec<TreeNode<T>>) -> Self {
+        self.nodes = nodes;
+        self
+    }
+
+    pub fn with_node(mut self, node: impl Into<TreeNode<T>>) -> Self {
+        self.nodes.push(node.into());
+        self
+    }
+
+    pub fn with_page_size(mut self, size: usize) -> Self {
+        self.page_size = size.max(3);
+        self
+    }
+
+    pub fn with_prompt_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prompt_prefix = prefix.into();
+        self
+    }
+
+    pub fn with_help_message(mut self, message: impl Into<String>) -> Self {
+        self.help_message = Some(message.into());
+        self
+    }
+
+    pub fn with_hints(mut self, enabled: bool) -> Self {
+        self.show_hints = enabled;
+        self
+    }
+
+    pub fn with_descriptions(mut self, enabled: bool) -> Self {
+        self.show_descriptions = enabled;
+        self
+    }
+
+    pub fn with_escape(mut self, allow: bool) -> Self {
+        self.allow_escape = allow;
+        self
+    }
+
+    pub fn with_vim_mode(mut self, enabled: bool) -> Self {
+        self.vim_mode = enabled;
+        self
+    }
+
+    /// set which keys do what, instead of the bindings `with_vim_mode` picks
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.keymap = Some(keymap);
+        self
+    }
+
+    /// the fewest nodes without children [`TreeSelect::ask_multiple`] accepts
+    pub fn with_min_selections(mut self, min: usize) -> Self {
+        self.min_selections = Some(min);
+        self
+    }
+
+    /// the most nodes without children [`TreeSelect::ask_multiple`] accepts
+    pub fn with_max_selections(mut self, max: usize) -> Self {
+        self.max_selections = Some(max);
+        self
+    }
+
+    pub fn with_style(mut self, style: MultiSelectStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// pick a single node, with or without children
+    pub fn ask(&self) -> miette::Result<AskOption<T>> {
+        let picked = self.ask_with_hook(false)?;
+        picked
+            .into_iter()
+            .next()
+            .ok_or_else(|| miette::miette!("No option selected"))
+    }
+
+    /// check any number of nodes
+    ///
+    /// returns the checked nodes in tree order. a node with children counts
+    /// as checked when everything under it is, and is returned along with
+    /// the nodes under it.
+    pub fn ask_multiple(&self) -> miette::Result<Vec<AskOption<T>>> {
+        self.ask_with_hook(true)
+    }
+
+    fn ask_with_hook(&self, multiple: bool) -> miette::Result<Vec<AskOption<T>>> {
+        if self.nodes.is_empty() {
+            return Err(miette::miette!("No options provided"));
+        }
+
+        let original_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |panic_info| {
+            let _ = terminal::disable_raw_mode();
+            std::panic::take_hook()(panic_info);
+        }));
+
+        let result = self.ask_internal(multiple);
+
+        let _ = std::panic::take_hook();
+        std::panic::set_hook(original_hook);
+
+        result
+    }
+
+    fn ask_internal(&self, multiple: bool) -> miette::Result<Vec<AskOption<T>>> {
+        let mut flat = Vec::new();
+        let mut expanded = Vec::new();
+        flatten(&self.nodes, 0, None, false, &mut flat, &mut expanded);
+
+        let mut state = TreeState::new(&flat, expanded);
+
+        let mut buf = Vec::with_capacity(4096);
+        let mut out = stdout();
+
+        let mut last_render_lines = self.render(&mut buf, &flat, &state, multiple)?;
+        out.write_all(&buf).into_diagnostic()?;
+        out.flush().into_diagnostic()?;
+
+        terminal::enable_raw_mode().into_diagnostic()?;
+
+        while event::poll(std::time::Duration::from_millis(0)).into_diagnostic()? {
+            event::read().into_diagnostic()?;
+        }
+
+        loop {
+            if let Event::Key(key_event) = event::read().into_diagnostic()? {
+                if key_event.kind != KeyEventKind::Press {
+                    continue;
+                }
+
+                match self.handle_key(key_event, &flat, &mut state, multiple) {
+                    Ok(Some(picked)) => {
+                        terminal::disable_raw_mode().into_diagnostic()?;
+                        buf.clear();
+                        if last_render_lines > 0 {
+                            queue!(buf, cursor::MoveUp(last_render_lines as u16))
+                                .into_diagnostic()?;
+                        }
+                        queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
+                        queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+
+                        self.show_result(&mut buf, &flat, &picked)?;
+                        out.write_all(&buf).into_diagnostic()?;
+                        out.flush().into_diagnostic()?;
+
+                        return Ok(picked.iter().map(|&i| flat[i].option.clone()).collect());
+                    }
+                    Ok(None) => {
+                        buf.clear();
+                        if last_render_lines > 0 {
+                            queue!(buf, cursor::MoveUp(last_render_lines as u16))
+                                .into_diagnostic()?;
+                        }
+                        queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
+                        queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+                        last_render_lines = self.render(&mut buf, &flat, &state, multiple)?;
+                        out.write_all(&buf).into_diagnostic()?;
+                        out.flush().into_diagnostic()?;
+                    }
+                    Err(e) => {
+                        terminal::disable_raw_mode().into_diagnostic()?;
+                        buf.clear();
+                        if last_render_lines > 0 {
+                            queue!(buf, cursor::MoveUp(last_render_lines as u16))
+                                .into_diagnostic()?;
+                        }
+                        queue!(buf, cursor::MoveToColumn(0)).into_diagnostic()?;
+                        queue!(buf, Clear(ClearType::FromCursorDown)).into_diagnostic()?;
+                        self.show_error(&mut buf, &e)?;
+                        out.write_all(&buf).into_diagnostic()?;
+                        out.flush().into_diagnostic()?;
+                        return Err(miette::miette!(e));
+                    }
+                }
+            }
+        }
+    }
+
+    const ACTIONS: &[Action] = &[
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveToFirst,
+        Action::MoveToLast,
+        Action::PageUp,
+        Action::PageDown,
+        Action::Expand,
+        Action::Collapse,
+        Action::Toggle,
+        Action::Submit,
+        Action::Cancel,
+    ];
+
+    fn handle_key(
+        &self,
+        key_event: KeyEvent,
+        flat: &[Flat<'_, T>],
+        state: &mut TreeState,
+        multiple: bool,
+    ) -> Result<Option<Vec<usize>>, String> {
+        if key_event.modifiers.contains(KeyModifiers::CONTROL)
+            && matches!(key_event.code, KeyCode::Char('c'))
+        {
+            let _ = terminal::disable_raw_mode();
+            std::process::exit(130);
+        }
+
+        let visible = visible_rows(flat, &state.expanded);
+        let row = visible.iter().position(|&i| i == state.cursor).unwrap_or(0);
+        let node = &flat[state.cursor];
+        let keymap = KeyMap::resolve(self.keymap.as_ref(), self.vim_mode);
+
+        match keymap.action(&key_event, Self::ACTIONS) {
+            Some(Action::MoveUp) => {
+                let row = if row > 0 { row - 1 } else { visible.len() - 1 };
+                self.move_to(state, &visible, row);
+                Ok(None)
+            }
+            Some(Action::MoveDown) => {
+                let row = if row + 1 < visible.len() { row + 1 } else { 0 };
+                self.move_to(state, &visible, row);
+                Ok(None)
+            }
+            Some(Action::MoveToFirst) => {
+                self.move_to(state, &visible, 0);
+                Ok(None)
+            }
+            Some(Action::MoveToLast) => {
+                self.move_to(state, &visible, visible.len() - 1);
+                Ok(None)
+            }
+            Some(Action::PageUp) => {
+                state.scroll = state.scroll.saturating_sub(self.page_size);
+                self.move_to(state, &visible, row.saturating_sub(self.page_size));
+                Ok(None)
+            }
+            Some(Action::PageDown) => {
+                let row = (row + self.page_size).min(visible.len() - 1);
+                self.move_to(state, &visible, row);
+                Ok(None)
+            }
+            Some(Action::Expand) if !node.children.is_empty() => {
+                if state.expanded[state.cursor] {
+                    self.move_to(state, &visible, row + 1);
+                } else {
+                    state.expanded[state.cursor] = true;
+                }
+                Ok(None)
+            }
+            Some(Action::Collapse) => {
+                if !node.children.is_empty() && state.expanded[state.cursor] {
+                    state.expanded[state.cursor] = false;
+                    let visible = visible_rows(flat, &state.expanded);
+                    self.move_to(state, &visible, row);
+                } else if let Some(parent) = node.parent {
+                    let row = visible.iter().position(|&i| i == parent).unwrap_or(0);
+                    self.move_to(state, &visible, row);
+                }
+                Ok(None)
+            }
+            Some(Action::Toggle) if multiple => {
+                let checking = state.check(state.cursor) != Check::All;
+                let unchecked =
+                    state.totals[state.cursor].saturating_sub(state.counts[state.cursor]);
+
+                if checking
+                    && let Some(max) = self.max_selections
+                    && state.selected + unchecked > max
+                {
+                    return Err(format!("Cannot select more than {} options", max));
+                }
+
+                for leaf in leaves(flat, state.cursor) {
+                    state.set(flat, leaf, checking);
+                }
+                Ok(None)
+            }
+            Some(Action::Submit) if multiple => {
+                if let Some(min) = self.min_selections
+                    && state.selected < min
+                {
+                    return Err(format!("Please select at least {} option(s)", min));
+                }
+
+                Ok(Some(state.picked()))
+            }
+            Some(Action::Submit) if !node.disabled => Ok(Some(vec![state.cursor])),
+            Some(Action::Cancel) if self.allow_escape => Err("Cancelled".into()),
+            _ => Ok(None),
+        }
+    }
+
+    /// put the cursor on the visible row `row`, scrolling to keep it on screen
+    fn move_to(&self, state: &mut TreeState, visible: &[usize], row: usize) {
+        let row = row.min(visible.len() - 1);
+        state.cursor = visible[row];
+
+        // closing a node can leave fewer rows below the scroll than fit
+        state.scroll = state
+            .scroll
+            .min(visible.len().saturating_sub(self.page_size));
+        if row < state.scroll {
+            state.scroll = row;
+        } else if row >= state.scroll + self.page_size {
+            state.scroll = row + 1 - self.page_size;
+        }
+    }
+
+    fn render(
+        &self,
+        out: &mut impl Write,
+        flat: &[Flat<'_, T>],
+        state: &TreeState,
+        multiple: bool,
+    ) -> miette::Result<usize> {
+        let tw = crate::util::term_width();
+        let mut line_count = 0;
+
+        let line = format!(
+            "{} {}",
+            self.prompt_prefix.style(self.style.prompt_prefix),
+            self.prompt.style(self.style.prompt),
+        );
+        line_count += crate::util::writeln_physical(out, &line, tw)?;
+
+        if let Some(ref help) = self.help_message {
+            let line = format!("  {}", help.style(self.style.hint));
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        let visible = visible_rows(flat, &state.expanded);
+        let end_offset = (state.scroll + self.page_size).min(visible.len());
+
+        for &i in &visible[state.scroll..end_offset] {
+            let node = &flat[i];
+            let option = node.option;
+            let is_cursor = i == state.cursor;
+            let cursor_marker = if is_cursor { "󰁕" } else { " " };
+            let indent = "  ".repeat(node.depth);
+
+            let expander = if node.children.is_empty() {
+                " "
+            } else if state.expanded[i] {
+                "▾"
+            } else {
+                "▸"
+            };
+
+            let check = state.check(i);
+            let checkbox = if !multiple {
+                String::new()
+            } else {
+                let (glyph, style) = match check {
+                    _ if node.disabled => ("󰄮", self.style.disabled),
+                    Check::All => ("󰄲", self.style.checkbox_selected),
+                    Check::Some => ("󰡖", self.style.checkbox_selected),
+                    Check::None => ("󰄮", self.style.checkbox_unselected),
+                };
+                format!("{} ", glyph.style(style))
+            };
+
+            let name_style = if node.disabled {
+                self.style.disabled
+            } else if is_cursor {
+                self.style.cursor
+            } else if multiple && check != Check::None {
+                self.style.selected
+            } else if node.children.is_empty() {
+                self.style.option_name
+            } else {
+                self.style.group_header
+            };
+            let reason = match option.disabled_reason {
+                Some(ref reason) => format!(" {}", format!("({})", reason).style(self.style.hint)),
+                None => String::new(),
+            };
+
+            let line = format!(
+                "  {} {}{} {}{}{}",
+                cursor_marker.style(self.style.cursor),
+                indent,
+                expander.style(self.style.hint),
+                checkbox,
+                option.name.style(name_style),
+                reason
+            );
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+
+            if self.show_descriptions && !option.description.is_empty() {
+                let desc_style = if node.disabled {
+                    self.style.disabled
+                } else {
+                    option.description_style
+                };
+                let pad = if multiple { 8 } else { 6 };
+                let line = format!(
+                    "{}{}{}",
+                    " ".repeat(pad),
+                    indent,
+                    option.description.style(desc_style)
+                );
+                line_count += crate::util::writeln_physical(out, &line, tw)?;
+            }
+        }
+
+        if state.scroll > 0 {
+            let line = format!(
+                "  {}",
+                format!("(↑ {} more above)", state.scroll).style(self.style.hint)
+            );
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        if end_offset < visible.len() {
+            let line = format!(
+                "  {}",
+                format!("(↓ {} more below)", visible.len() - end_offset).style(self.style.hint)
+            );
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        if self.show_hints {
+            let keymap = KeyMap::resolve(self.keymap.as_ref(), self.vim_mode);
+            let mut hints = vec![];
+
+            if multiple && let Some(key) = keymap.hint(Action::Toggle) {
+                hints.push(format!("{} to toggle", key));
+            }
+
+            if let Some(keys) = keymap.hint_pair(Action::MoveUp, Action::MoveDown) {
+                hints.push(format!("{} to navigate", keys));
+            }
+
+            if let Some(keys) = keymap.hint_pair(Action::Expand, Action::Collapse) {
+                hints.push(format!("{} to expand/collapse", keys));
+            }
+
+            if visible.len() > self.page_size
+                && let Some(keys) = keymap.hint_pair(Action::PageUp, Action::PageDown)
+            {
+                hints.push(format!("{} to scroll", keys));
+            }
+
+            if let Some(key) = keymap.hint(Action::Submit) {
+                let what = if multiple { "submit" } else { "select" };
+                hints.push(format!("{} to {}", key, what));
+            }
+
+            if self.allow_escape
+                && let Some(key) = keymap.hint(Action::Cancel)
+            {
+                hints.push(format!("{} to cancel", key));
+            }
+
+            let line = format!("  {}", hints.join(", ").style(self.style.hint));
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        if multiple {
+            let count_text = format!("[{} selected]", state.selected);
+            let line = format!("  {}", count_text.style(self.style.selection_count));
+            line_count += crate::util::writeln_physical(out, &line, tw)?;
+        }
+
+        Ok(line_count)
+    }
+
+    fn show_error(&self, out: &mut impl Write, error: &str) -> miette::Result<()> {
+        let line = format!(
+            "{} {}",
+            "✗".style(self.style.error),
+            error.style(self.style.error_hint),
+        );
+        let tw = crate::util::term_width();
+        crate::util::writeln_physical(out, &line, tw)?;
+
+        Ok(())
+    }
+
+    fn show_result(
+        &self,
+        out: &mut impl Write,
+        flat: &[Flat<'_, T>],
+        picked: &[usize],
+    ) -> miette::Result<()> {
+        let names: Vec<&str> = picked
+            .iter()
+            .map(|&i| flat[i].option.name.as_str())
+            .collect();
+
+        let result_text = if names.is_empty() {
+            "None".to_string()
+        } else {
+            names.join(", ")
+        };
+
+        let line = format!(
+            "{} {} {}",
+            self.prompt_prefix.style(self.style.prompt_prefix),
+            self.prompt.style(self.style.prompt),
+            result_text.style(self.style.selected).bold(),
+        );
+        let tw = crate::util::term_width();
+        crate::util::writeln_physical(out, &line, tw)?;
+
+        Ok(())
+    }
+}
+
+/// lay `nodes` out in order after what's already in `flat`, returning their
+/// indices
+///
+/// everything under a disabled node is disabled too.
+fn flatten<'a, T>(
+    nodes: &'a [TreeNode<T>],
+    depth: usize,
+    parent: Option<usize>,
+    disabled: bool,
+    flat: &mut Vec<Flat<'a, T>>,
+    expanded: &mut Vec<bool>,
+) -> Vec<usize> {
+    let mut indices = Vec::with_capacity(nodes.len());
+
+    for node in nodes {
+        let index = flat.len();
+        indices.push(index);
+        flat.push(Flat {
+            option: &node.option,
+            depth,
+            parent,
+            children: Vec::new(),
+            disabled: disabled || node.option.disabled,
+        });
+        expanded.push(node.expanded);
+
+        let disabled = flat[index].disabled;
+        flat[index].children = flatten(
+            &node.children,
+            depth + 1,
+            Some(index),
+            disabled,
+            flat,
+            expanded,
+        );
+    }
+
+    indices
+}
+
+/// the nodes not hidden inside a closed node, in order
+fn visible_rows<T>(flat: &[Flat<'_, T>], expanded: &[bool]) -> Vec<usize> {
+    let mut rows = Vec::with_capacity(flat.len());
+    let mut i = 0;
+
+    while i < flat.len() {
+        rows.push(i);
+        if expanded[i] || flat[i].children.is_empty() {
+            i += 1;
+        } else {
+            // nodes are laid out in order, so the next one at the same depth
+            // or above ends this node's subtree
+            let depth = flat[i].depth;
+            i += 1;
+            while i < flat.len() && flat[i].depth > depth {
+                i += 1;
+            }
+        }
+    }
+
+    rows
+}
+
+/// the nodes without children at or under `index` that can be checked
+fn leaves<T>(flat: &[Flat<'_, T>], index: usize) -> Vec<usize> {
+    if flat[index].disabled {
+        return Vec::new();
+    }
+    if flat[index].children.is_empty() {
+        return vec![index];
+    }
+
+    flat[index]
+        .children
+        .iter()
+        .flat_map(|&child| leaves(flat, child))
+        .collect()
+}
@@ -60,6 +60,76 @@ pub(crate) fn term_width() -> u16 {
     terminal::size().map(|(w, _)| w).unwrap_or(80)
 }
 
+pub(crate) fn term_height() -> u16 {
+    terminal::size().map(|(_, h)| h).unwrap_or(24)
+}
+
+pub(crate) fn truncate(s: &str, width: usize) -> String {
+    if s.chars().count() <= width {
+        return s.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let mut truncated: String = s.chars().take(width - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// cut `s` down to `width` visible chars, keeping its escape codes, and pad
+/// it with spaces to exactly `width`
+pub(crate) fn fit(s: &str, width: usize) -> String {
+    let len = visible_width(s);
+    if len <= width {
+        return format!("{}{}", s, " ".repeat(width - len));
+    }
+    if width == 0 {
+        return String::new();
+    }
+
+    let mut out = String::with_capacity(s.len());
+    let mut shown = 0;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            out.push(c);
+            match chars.next() {
+                Some('[') => {
+                    out.push('[');
+                    for c in chars.by_ref() {
+                        out.push(c);
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                Some(']') => {
+                    out.push(']');
+                    let mut prev = '\0';
+                    for c in chars.by_ref() {
+                        out.push(c);
+                        if c == '\x07' || (prev == '\x1b' && c == '\\') {
+                            break;
+                        }
+                        prev = c;
+                    }
+                }
+                Some(c) => out.push(c),
+                None => {}
+            }
+        } else if !c.is_control() {
+            if shown + 1 == width {
+                break;
+            }
+            out.push(c);
+            shown += 1;
+        }
+    }
+    out.push('…');
+    out.push_str("\x1b[0m");
+    out
+}
+
 pub(crate) fn writeln_physical(
     out: &mut impl Write,
     line: &str,
@@ -99,3 +169,39 @@ impl Default for CursorGuard {
         Self::new().expect("Failed to initialize cursor guard")
     }
 }
+
+pub(crate) fn state_dir() -> std::path::PathBuf {
+    if let Some(dir) = std::env::var_os("XDG_STATE_HOME").filter(|d| !d.is_empty()) {
+        return std::path::PathBuf::from(dir).join("bearask");
+    }
+
+    if cfg!(windows)
+        && let Some(dir) = std::env::var_os("LOCALAPPDATA")
+    {
+        return std::path::PathBuf::from(dir).join("bearask");
+    }
+
+    match std::env::var_os("HOME").filter(|d| !d.is_empty()) {
+        Some(home) => std::path::PathBuf::from(home)
+            .join(".local")
+            .join("state")
+            .join("bearask"),
+        None => std::env::temp_dir().join("bearask"),
+    }
+}
+
+/// turn a caller-chosen id into something safe to use as a file name
+///
+/// other bytes are written as `%` and their hex value, so different ids never
+/// share a file.
+pub(crate) fn file_stem(id: &str) -> String {
+    let mut stem = String::with_capacity(id.len());
+    for byte in id.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_' {
+            stem.push(byte as char);
+        } else {
+            stem.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    stem
+}